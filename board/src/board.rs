@@ -0,0 +1,375 @@
+//! Append-only boards of poll items together with their persistence.
+
+use elasticpoll_core::{
+    Clock, ExportedPoll, ParticipantApplication, PollId, PollSpec, PollStage, PollState,
+    SystemClock, TallierShare, TallierShareError, Vote, VoteError,
+};
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    ffi::OsStr,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write as _},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Item posted to a poll board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub(crate) enum PollItem {
+    Application(ParticipantApplication),
+    Vote(Vote),
+    TallierShare(TallierShare),
+}
+
+/// Accepted [`PollItem`] together with its metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Envelope {
+    /// 0-based index of the item on the board.
+    pub index: usize,
+    /// Unix timestamp (in milliseconds) when the item was accepted by the board.
+    pub received_at: f64,
+    pub item: PollItem,
+}
+
+/// Error rejecting a [`PollItem`].
+#[derive(Debug)]
+pub(crate) enum ItemError {
+    ParticipantsFinalized,
+    DuplicateParticipant,
+    /// Application validation error; stored as a message since the original error is not `Send`.
+    Application(String),
+    VotesFinalized,
+    Vote(VoteError),
+    NoVotes,
+    PollFinished,
+    DuplicateTallierShare,
+    TallierShare(TallierShareError),
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParticipantsFinalized => {
+                formatter.write_str("participants are finalized since the poll contains votes")
+            }
+            Self::DuplicateParticipant => formatter.write_str("participant is already registered"),
+            Self::Application(err) => write!(formatter, "invalid application: {err}"),
+            Self::VotesFinalized => {
+                formatter.write_str("votes are finalized since the poll contains tallier shares")
+            }
+            Self::Vote(err) => write!(formatter, "invalid vote: {err}"),
+            Self::NoVotes => formatter.write_str("poll does not contain votes"),
+            Self::PollFinished => formatter.write_str("poll is already finished"),
+            Self::DuplicateTallierShare => {
+                formatter.write_str("participant has already submitted a tallier share")
+            }
+            Self::TallierShare(err) => write!(formatter, "invalid tallier share: {err}"),
+        }
+    }
+}
+
+impl StdError for ItemError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Vote(err) => Some(err),
+            Self::TallierShare(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl ItemError {
+    /// Returns `true` if the item is rejected because of the poll stage or a duplicate
+    /// submission, rather than because the item is invalid.
+    pub fn is_conflict(&self) -> bool {
+        !matches!(
+            self,
+            Self::Application(_) | Self::Vote(_) | Self::TallierShare(_)
+        )
+    }
+}
+
+/// Error interacting with [`Boards`].
+#[derive(Debug)]
+pub(crate) enum BoardError {
+    UnknownPoll,
+    Item(ItemError),
+    Storage(io::Error),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPoll => formatter.write_str("poll is not registered on the board"),
+            Self::Item(err) => fmt::Display::fmt(err, formatter),
+            Self::Storage(err) => write!(formatter, "storage error: {err}"),
+        }
+    }
+}
+
+impl StdError for BoardError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::UnknownPoll => None,
+            Self::Item(err) => Some(err),
+            Self::Storage(err) => Some(err),
+        }
+    }
+}
+
+impl From<ItemError> for BoardError {
+    fn from(err: ItemError) -> Self {
+        Self::Item(err)
+    }
+}
+
+impl From<io::Error> for BoardError {
+    fn from(err: io::Error) -> Self {
+        Self::Storage(err)
+    }
+}
+
+/// Board for a single poll.
+#[derive(Debug)]
+struct PollBoard {
+    poll_id: PollId,
+    state: PollState,
+    items: Vec<Envelope>,
+    /// Append-only file with the spec on the first line and envelopes on the following lines.
+    file: Option<File>,
+    /// Set if a failed write could not be rolled back, i.e., the file may contain an item
+    /// missing from `items`. No more items are accepted in this case.
+    failed: bool,
+}
+
+impl PollBoard {
+    fn new(spec: PollSpec) -> Self {
+        Self {
+            poll_id: PollId::for_spec(&spec),
+            state: PollState::new(spec),
+            items: vec![],
+            file: None,
+            failed: false,
+        }
+    }
+
+    fn create_file(&mut self, path: &Path) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(path)?;
+        let spec = serde_json::to_string(self.state.spec())?;
+        writeln!(file, "{spec}")?;
+        file.sync_data()?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn load(path: &Path) -> Result<Self, Box<dyn StdError>> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let spec = lines.next().ok_or("file is empty")??;
+        let mut this = Self::new(serde_json::from_str(&spec)?);
+
+        for (i, line) in lines.enumerate() {
+            let envelope: Envelope = serde_json::from_str(&line?)?;
+            if envelope.index != i {
+                return Err(
+                    format!("unexpected item index {}, expected {i}", envelope.index).into(),
+                );
+            }
+            Self::apply(&this.poll_id, &mut this.state, &envelope.item)
+                .map_err(|err| format!("cannot apply item #{i}: {err}"))?;
+            this.items.push(envelope);
+        }
+        this.file = Some(OpenOptions::new().append(true).open(path)?);
+        Ok(this)
+    }
+
+    /// Verifies the item and applies it to the poll state. The state is unchanged
+    /// if the item is rejected.
+    fn apply(poll_id: &PollId, state: &mut PollState, item: &PollItem) -> Result<(), ItemError> {
+        match item {
+            PollItem::Application(application) => {
+                if !matches!(state.stage(), PollStage::Participants { .. }) {
+                    return Err(ItemError::ParticipantsFinalized);
+                }
+                if state.has_participant(&application.public_key) {
+                    return Err(ItemError::DuplicateParticipant);
+                }
+                application
+                    .validate(poll_id)
+                    .map_err(|err| ItemError::Application(err.to_string()))?;
+                state.insert_participant(application.clone());
+            }
+
+            PollItem::Vote(vote) => {
+                let finalized_participants = match state.stage() {
+                    PollStage::Participants { .. } => {
+                        state.finalize_participants();
+                        true
+                    }
+                    PollStage::Voting { .. } => false,
+                    PollStage::Tallying { .. } | PollStage::Finished => {
+                        return Err(ItemError::VotesFinalized);
+                    }
+                };
+                if let Err(err) = state.insert_vote(poll_id, vote.clone()) {
+                    if finalized_participants {
+                        state.rollback_to_participants_selection();
+                    }
+                    return Err(ItemError::Vote(err));
+                }
+            }
+
+            PollItem::TallierShare(share) => {
+                let finalized_votes = match state.stage() {
+                    PollStage::Participants { .. } => return Err(ItemError::NoVotes),
+                    PollStage::Voting { .. } => {
+                        state.finalize_votes();
+                        true
+                    }
+                    PollStage::Tallying { .. } => false,
+                    PollStage::Finished => return Err(ItemError::PollFinished),
+                };
+                let has_share = state
+                    .participants()
+                    .iter()
+                    .any(|p| p.public_key() == share.public_key() && p.tallier_share.is_some());
+                let result = if has_share {
+                    Err(ItemError::DuplicateTallierShare)
+                } else {
+                    state
+                        .insert_tallier_share(poll_id, share.clone())
+                        .map_err(ItemError::TallierShare)
+                };
+                if result.is_err() && finalized_votes {
+                    state.rollback_to_voting();
+                }
+                result?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies and persists the item. The item is applied to a copy of the poll state,
+    /// which replaces the current state only after the item is durably written. If writing
+    /// fails, the file is truncated to its previous length; if truncation fails as well,
+    /// the board is marked as failed and rejects all further items.
+    fn push(&mut self, item: PollItem, received_at: f64) -> Result<Envelope, BoardError> {
+        if self.failed {
+            let message = "poll file is inconsistent after a failed write";
+            return Err(io::Error::other(message).into());
+        }
+
+        let mut state = self.state.clone();
+        Self::apply(&self.poll_id, &mut state, &item)?;
+        let envelope = Envelope {
+            index: self.items.len(),
+            received_at,
+            item,
+        };
+        if let Some(file) = &mut self.file {
+            let line = serde_json::to_string(&envelope).map_err(io::Error::from)?;
+            let prev_len = file.metadata()?.len();
+            if let Err(err) = writeln!(file, "{line}").and_then(|()| file.sync_data()) {
+                // The line may be (partially) written; remove it so that the file
+                // stays consistent with `items`.
+                if file
+                    .set_len(prev_len)
+                    .and_then(|()| file.sync_data())
+                    .is_err()
+                {
+                    self.failed = true;
+                }
+                return Err(err.into());
+            }
+        }
+        self.state = state;
+        self.items.push(envelope.clone());
+        Ok(envelope)
+    }
+}
+
+/// Collection of poll boards, optionally persisted in a directory.
+///
+/// Each board has its own lock, so that verifying and persisting items for one poll
+/// does not block other polls. Methods may block on I/O and should be called
+/// outside of async contexts (e.g., via [`tokio::task::spawn_blocking()`]).
+#[derive(Debug)]
+pub(crate) struct Boards {
+    polls: RwLock<HashMap<PollId, Arc<Mutex<PollBoard>>>>,
+    data_dir: Option<PathBuf>,
+}
+
+impl Boards {
+    /// Creates boards persisted in the specified directory, loading existing polls from it.
+    /// If the directory is not specified, boards are stored in memory only.
+    pub fn new(data_dir: Option<PathBuf>) -> Result<Self, Box<dyn StdError>> {
+        let mut polls = HashMap::new();
+        if let Some(dir) = &data_dir {
+            fs::create_dir_all(dir)?;
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension() != Some(OsStr::new("jsonl")) {
+                    continue;
+                }
+                let board = PollBoard::load(&path)
+                    .map_err(|err| format!("cannot load poll from `{}`: {err}", path.display()))?;
+                polls.insert(board.poll_id, Arc::new(Mutex::new(board)));
+            }
+        }
+
+        Ok(Self {
+            polls: RwLock::new(polls),
+            data_dir,
+        })
+    }
+
+    fn board(&self, poll_id: &PollId) -> Result<Arc<Mutex<PollBoard>>, BoardError> {
+        let polls = self.polls.read().expect("boards are poisoned");
+        polls.get(poll_id).cloned().ok_or(BoardError::UnknownPoll)
+    }
+
+    /// Registers a poll with the specified spec. Returns the poll ID and a flag whether
+    /// the poll was newly created.
+    pub fn create_poll(&self, spec: PollSpec) -> Result<(PollId, bool), BoardError> {
+        let poll_id = PollId::for_spec(&spec);
+        let mut polls = self.polls.write().expect("boards are poisoned");
+        if polls.contains_key(&poll_id) {
+            return Ok((poll_id, false));
+        }
+
+        let mut board = PollBoard::new(spec);
+        if let Some(dir) = &self.data_dir {
+            board.create_file(&dir.join(format!("{poll_id}.jsonl")))?;
+        }
+        polls.insert(poll_id, Arc::new(Mutex::new(board)));
+        Ok((poll_id, true))
+    }
+
+    /// Returns the poll in the export format.
+    pub fn export_poll(&self, poll_id: &PollId) -> Result<ExportedPoll, BoardError> {
+        let board = self.board(poll_id)?;
+        let board = board.lock().expect("board is poisoned");
+        Ok(board.state.export())
+    }
+
+    /// Returns items for the poll starting from the specified index.
+    pub fn items(&self, poll_id: &PollId, since: usize) -> Result<Vec<Envelope>, BoardError> {
+        let board = self.board(poll_id)?;
+        let board = board.lock().expect("board is poisoned");
+        Ok(board.items.get(since..).unwrap_or_default().to_vec())
+    }
+
+    /// Verifies and appends an item to the poll board.
+    pub fn push_item(&self, poll_id: &PollId, item: PollItem) -> Result<Envelope, BoardError> {
+        let board = self.board(poll_id)?;
+        let mut board = board.lock().expect("board is poisoned");
+        board.push(item, SystemClock.now())
+    }
+}
@@ -0,0 +1,53 @@
+//! Self-hostable bulletin board for exchanging Elastic Poll messages.
+
+// Linter settings.
+#![warn(missing_debug_implementations, bare_trait_objects, rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+use clap::Parser;
+use tokio::net::TcpListener;
+
+use std::{error::Error as StdError, net::SocketAddr, path::PathBuf, process::ExitCode, sync::Arc};
+
+mod board;
+mod server;
+
+use crate::board::Boards;
+
+/// Bulletin board for exchanging messages of Elastic Poll polls.
+///
+/// Stores an append-only list of participant applications, votes and tallier shares
+/// for each registered poll. Each item is verified against the poll state before
+/// it is accepted.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Socket address to bind the HTTP server to.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+    /// Directory to persist polls in. If not specified, polls are stored in memory only.
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+}
+
+impl Args {
+    async fn run(self) -> Result<(), Box<dyn StdError>> {
+        let boards = Arc::new(Boards::new(self.data_dir)?);
+        let listener = TcpListener::bind(self.bind).await?;
+        println!("Listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, server::router(boards)).await?;
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Err(err) = args.run().await {
+        eprintln!("Error: {err}");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
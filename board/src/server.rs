@@ -0,0 +1,139 @@
+//! HTTP API of the bulletin board.
+
+#![allow(clippy::unused_async)] // handlers are required to be async by `axum`
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use elasticpoll_core::{ExportedPoll, PollId, PollSpec};
+use serde::{Deserialize, Serialize};
+use tower_http::cors::CorsLayer;
+
+use tokio::task;
+
+use std::{panic, sync::Arc};
+
+use crate::board::{BoardError, Boards, Envelope, PollItem};
+
+/// Error response of the API.
+#[derive(Debug)]
+enum ApiError {
+    InvalidPollId,
+    Board(BoardError),
+}
+
+impl From<BoardError> for ApiError {
+    fn from(err: BoardError) -> Self {
+        Self::Board(err)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            Self::InvalidPollId => (StatusCode::BAD_REQUEST, "invalid poll ID".to_owned()),
+            Self::Board(err) => {
+                let status = match &err {
+                    BoardError::UnknownPoll => StatusCode::NOT_FOUND,
+                    BoardError::Item(err) if err.is_conflict() => StatusCode::CONFLICT,
+                    BoardError::Item(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                    BoardError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, err.to_string())
+            }
+        };
+        (status, Json(ErrorBody { error })).into_response()
+    }
+}
+
+fn parse_poll_id(poll_id: &str) -> Result<PollId, ApiError> {
+    poll_id.parse().map_err(|_| ApiError::InvalidPollId)
+}
+
+/// Runs a blocking operation on `boards` (e.g., one verifying proofs or syncing files)
+/// without blocking the async runtime.
+async fn with_boards<T: Send + 'static>(
+    boards: Arc<Boards>,
+    action: impl FnOnce(&Boards) -> Result<T, BoardError> + Send + 'static,
+) -> Result<T, ApiError> {
+    let result = task::spawn_blocking(move || action(&boards)).await;
+    let result = result.unwrap_or_else(|err| panic::resume_unwind(err.into_panic()));
+    result.map_err(ApiError::Board)
+}
+
+#[derive(Debug, Serialize)]
+struct CreatedPoll {
+    id: PollId,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemsQuery {
+    #[serde(default)]
+    since: usize,
+}
+
+async fn create_poll(
+    State(boards): State<Arc<Boards>>,
+    Json(spec): Json<PollSpec>,
+) -> Result<(StatusCode, Json<CreatedPoll>), ApiError> {
+    let (id, created) = with_boards(boards, move |boards| boards.create_poll(spec)).await?;
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(CreatedPoll { id })))
+}
+
+async fn get_poll(
+    State(boards): State<Arc<Boards>>,
+    Path(poll_id): Path<String>,
+) -> Result<Json<ExportedPoll>, ApiError> {
+    let poll_id = parse_poll_id(&poll_id)?;
+    Ok(Json(boards.export_poll(&poll_id)?))
+}
+
+async fn get_items(
+    State(boards): State<Arc<Boards>>,
+    Path(poll_id): Path<String>,
+    Query(query): Query<ItemsQuery>,
+) -> Result<Json<Vec<Envelope>>, ApiError> {
+    let poll_id = parse_poll_id(&poll_id)?;
+    Ok(Json(boards.items(&poll_id, query.since)?))
+}
+
+async fn post_item(
+    State(boards): State<Arc<Boards>>,
+    Path(poll_id): Path<String>,
+    Json(item): Json<PollItem>,
+) -> Result<(StatusCode, Json<Envelope>), ApiError> {
+    let poll_id = parse_poll_id(&poll_id)?;
+    let envelope = with_boards(boards, move |boards| boards.push_item(&poll_id, item)).await?;
+    Ok((StatusCode::CREATED, Json(envelope)))
+}
+
+/// Creates the router for the board API:
+///
+/// - `POST /polls`: registers a poll with the spec in the request body.
+/// - `GET /polls/{id}`: returns the poll in the export format of the app.
+/// - `GET /polls/{id}/items?since={index}`: returns enveloped items starting from `index`.
+/// - `POST /polls/{id}/items`: verifies and appends an item to the poll.
+///
+/// Cross-origin requests are allowed so that the board can be used from the web app.
+pub(crate) fn router(boards: Arc<Boards>) -> Router {
+    Router::new()
+        .route("/polls", post(create_poll))
+        .route("/polls/:id", get(get_poll))
+        .route("/polls/:id/items", get(get_items).post(post_item))
+        .layer(CorsLayer::permissive())
+        .with_state(boards)
+}
@@ -0,0 +1,242 @@
+//! Integration tests for the bulletin board running as a local server.
+
+use rand::rngs::OsRng;
+use serde_json::{json, Value};
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Child, Command, Stdio},
+};
+
+use elasticpoll_core::{
+    ExportedPoll, Keypair, ParticipantApplication, PollId, PollSpec, PollStage, PollState,
+    PollType, TallierShare, Vote, VoteChoice,
+};
+
+/// Board server running in a child process. The process is killed on drop.
+struct Server {
+    process: Child,
+    url: String,
+}
+
+impl Server {
+    fn start(data_dir: Option<&Path>) -> Self {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_elasticpoll-board"));
+        command.args(["--bind", "127.0.0.1:0"]);
+        if let Some(dir) = data_dir {
+            command.arg("--data-dir").arg(dir);
+        }
+        let mut process = command.stdout(Stdio::piped()).spawn().unwrap();
+
+        let stdout = process.stdout.take().unwrap();
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line).unwrap();
+        let url = line
+            .trim()
+            .strip_prefix("Listening on ")
+            .unwrap_or_else(|| panic!("unexpected output: {line}"))
+            .to_owned();
+        Self { process, url }
+    }
+
+    fn get(&self, path: &str) -> (u16, Value) {
+        Self::handle(ureq::get(&format!("{}{path}", self.url)).call())
+    }
+
+    fn post(&self, path: &str, body: &impl serde::Serialize) -> (u16, Value) {
+        Self::handle(ureq::post(&format!("{}{path}", self.url)).send_json(body))
+    }
+
+    fn handle(result: Result<ureq::Response, ureq::Error>) -> (u16, Value) {
+        match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                (response.status(), response.into_json().unwrap())
+            }
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    fn create_poll(&self, spec: &PollSpec) -> String {
+        let (status, body) = self.post("/polls", spec);
+        assert_eq!(status, 201, "{body}");
+        let poll_id = body["id"].as_str().unwrap().to_owned();
+        assert_eq!(poll_id, PollId::for_spec(spec).to_string());
+        poll_id
+    }
+
+    fn post_item(&self, poll_id: &str, ty: &str, payload: &impl serde::Serialize) -> (u16, Value) {
+        let item = json!({ "type": ty, "payload": payload });
+        self.post(&format!("/polls/{poll_id}/items"), &item)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.process.kill().ok();
+        self.process.wait().ok();
+    }
+}
+
+fn sample_spec() -> PollSpec {
+    PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce: 0,
+        options: vec!["Option #1".to_owned(), "Option #2".to_owned()],
+    }
+}
+
+/// Mirrors the board state locally to create votes and tallier shares.
+fn local_poll(spec: &PollSpec, keys: &[Keypair]) -> (PollId, PollState) {
+    let poll_id = PollId::for_spec(spec);
+    let mut poll = PollState::new(spec.clone());
+    for our_keys in keys {
+        poll.insert_participant(ParticipantApplication::new(our_keys, &poll_id));
+    }
+    poll.finalize_participants();
+    (poll_id, poll)
+}
+
+#[test]
+fn poll_lifecycle() {
+    let server = Server::start(None);
+    let spec = sample_spec();
+    let poll_id = server.create_poll(&spec);
+    // Repeated creation is idempotent.
+    let (status, body) = server.post("/polls", &spec);
+    assert_eq!(status, 200, "{body}");
+
+    let keys: Vec<_> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+    let (local_id, mut poll) = local_poll(&spec, &keys);
+    for our_keys in &keys {
+        let application = ParticipantApplication::new(our_keys, &local_id);
+        let (status, body) = server.post_item(&poll_id, "application", &application);
+        assert_eq!(status, 201, "{body}");
+    }
+    for (i, our_keys) in keys.iter().enumerate() {
+        let choice = VoteChoice::SingleChoice(usize::from(i == 0));
+        let vote = Vote::new(our_keys, &local_id, &poll, &choice);
+        poll.insert_vote(&local_id, vote.clone()).unwrap();
+        let (status, body) = server.post_item(&poll_id, "vote", &vote);
+        assert_eq!(status, 201, "{body}");
+        assert_eq!(body["index"], 3 + i);
+    }
+    poll.finalize_votes();
+    for our_keys in &keys {
+        let share = TallierShare::new(our_keys, &local_id, &poll);
+        let (status, body) = server.post_item(&poll_id, "tallier_share", &share);
+        assert_eq!(status, 201, "{body}");
+    }
+
+    let (status, items) = server.get(&format!("/polls/{poll_id}/items?since=6"));
+    assert_eq!(status, 200, "{items}");
+    let items = items.as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0]["index"], 6);
+    assert_eq!(items[0]["item"]["type"], "tallier_share");
+    assert!(items[0]["received_at"].as_f64().unwrap() > 0.0);
+
+    let (status, exported) = server.get(&format!("/polls/{poll_id}"));
+    assert_eq!(status, 200, "{exported}");
+    let exported: ExportedPoll = serde_json::from_value(exported).unwrap();
+    let (_, imported) = PollState::import(exported).unwrap();
+    assert_eq!(imported.stage(), PollStage::Finished);
+    assert_eq!(imported.results().unwrap(), [2, 1]);
+}
+
+#[test]
+fn rejecting_invalid_items() {
+    let server = Server::start(None);
+    let spec = sample_spec();
+    let poll_id = server.create_poll(&spec);
+
+    let (status, body) = server.get("/polls/invalid");
+    assert_eq!(status, 400, "{body}");
+    let mut other_spec = sample_spec();
+    other_spec.nonce = 1;
+    let other_id = PollId::for_spec(&other_spec);
+    let (status, body) = server.get(&format!("/polls/{other_id}/items"));
+    assert_eq!(status, 404, "{body}");
+
+    let keys: Vec<_> = (0..2).map(|_| Keypair::generate(&mut OsRng)).collect();
+    // Application for another poll.
+    let application = ParticipantApplication::new(&keys[0], &other_id);
+    let (status, body) = server.post_item(&poll_id, "application", &application);
+    assert_eq!(status, 422, "{body}");
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("invalid application"));
+
+    let (local_id, poll) = local_poll(&spec, &keys[..1]);
+    let application = ParticipantApplication::new(&keys[0], &local_id);
+    let (status, body) = server.post_item(&poll_id, "application", &application);
+    assert_eq!(status, 201, "{body}");
+    let (status, body) = server.post_item(&poll_id, "application", &application);
+    assert_eq!(status, 409, "{body}");
+
+    let share = TallierShare::new(&keys[0], &local_id, &poll);
+    let (status, body) = server.post_item(&poll_id, "tallier_share", &share);
+    assert_eq!(status, 409, "{body}");
+    assert_eq!(body["error"], "poll does not contain votes");
+
+    // Vote from a non-participant must not finalize participants.
+    let (_, other_poll) = local_poll(&spec, &keys[1..]);
+    let choice = VoteChoice::SingleChoice(0);
+    let vote = Vote::new(&keys[1], &local_id, &other_poll, &choice);
+    let (status, body) = server.post_item(&poll_id, "vote", &vote);
+    assert_eq!(status, 422, "{body}");
+    let application = ParticipantApplication::new(&keys[1], &local_id);
+    let (status, body) = server.post_item(&poll_id, "application", &application);
+    assert_eq!(status, 201, "{body}");
+
+    let (local_id, poll) = local_poll(&spec, &keys);
+    let vote = Vote::new(&keys[0], &local_id, &poll, &choice);
+    let (status, body) = server.post_item(&poll_id, "vote", &vote);
+    assert_eq!(status, 201, "{body}");
+    let application = ParticipantApplication::new(&Keypair::generate(&mut OsRng), &local_id);
+    let (status, body) = server.post_item(&poll_id, "application", &application);
+    assert_eq!(status, 409, "{body}");
+
+    let (status, items) = server.get(&format!("/polls/{poll_id}/items"));
+    assert_eq!(status, 200, "{items}");
+    assert_eq!(items.as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn persisting_polls() {
+    let data_dir = env::temp_dir().join(format!("elasticpoll-board-{}", std::process::id()));
+    fs::remove_dir_all(&data_dir).ok();
+
+    let spec = sample_spec();
+    let keys: Vec<_> = (0..2).map(|_| Keypair::generate(&mut OsRng)).collect();
+    let (local_id, poll) = local_poll(&spec, &keys);
+    let poll_id = {
+        let server = Server::start(Some(&data_dir));
+        let poll_id = server.create_poll(&spec);
+        for our_keys in &keys {
+            let application = ParticipantApplication::new(our_keys, &local_id);
+            server.post_item(&poll_id, "application", &application);
+        }
+        let vote = Vote::new(&keys[0], &local_id, &poll, &VoteChoice::SingleChoice(1));
+        let (status, body) = server.post_item(&poll_id, "vote", &vote);
+        assert_eq!(status, 201, "{body}");
+        poll_id
+    };
+
+    let server = Server::start(Some(&data_dir));
+    let (status, items) = server.get(&format!("/polls/{poll_id}/items"));
+    assert_eq!(status, 200, "{items}");
+    assert_eq!(items.as_array().unwrap().len(), 3);
+    // The restored board must continue accepting items.
+    let vote = Vote::new(&keys[1], &local_id, &poll, &VoteChoice::SingleChoice(0));
+    let (status, body) = server.post_item(&poll_id, "vote", &vote);
+    assert_eq!(status, 201, "{body}");
+    assert_eq!(body["index"], 3);
+
+    drop(server);
+    fs::remove_dir_all(&data_dir).ok();
+}
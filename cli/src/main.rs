@@ -0,0 +1,311 @@
+//! Command-line participant client for the Elastic Poll app.
+
+// Linter settings.
+#![warn(missing_debug_implementations, bare_trait_objects, rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use clap::{Parser, Subcommand, ValueEnum};
+use elasticpoll_core::{
+    crypto::Kdf, keys::derive_poll_keys, CompactEncoding, ExportedPoll, Fingerprint, Keypair,
+    ParticipantApplication, PollId, PollSpec, PollStage, PollState, PollType, TallierShare, Vote,
+    VoteChoice, MAX_OPTIONS,
+};
+use rand_core::{OsRng, RngCore};
+
+use std::{
+    error::Error as StdError,
+    fs,
+    io::{self, Read as _},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+mod secret;
+
+use crate::secret::SecretFile;
+
+/// Participates in polls of the Elastic Poll app from the command line.
+///
+/// Inputs (poll specs and exported polls) can be supplied in JSON or compact format, same as
+/// in the web app; use `-` to read an input from stdin. Outputs are printed to stdout.
+/// The password for the secret is read from the `ELASTICPOLL_PASSWORD` env var;
+/// if it is not set, the password is prompted for.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Path to the file with the sealed master secret. A secret backup from the web app
+    /// can be used as this file.
+    #[arg(
+        long,
+        global = true,
+        env = "ELASTICPOLL_SECRET",
+        default_value = "elasticpoll-secret.json"
+    )]
+    secret: PathBuf,
+    /// Output data in the compact format instead of JSON.
+    #[arg(long, global = true)]
+    compact: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates a new master secret and seals it with a password.
+    NewSecret {
+        /// Key derivation function used to seal the secret. Secrets sealed with PBKDF2 can be
+        /// used by the web app with any password-based crypto implementation; Argon2id secrets
+        /// can only be opened if the app uses the Rust implementation.
+        #[arg(long, value_enum, default_value_t = KdfArg::Pbkdf2)]
+        kdf: KdfArg,
+        /// Overwrite the secret file if it exists.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Creates a poll spec.
+    Spec {
+        /// Poll title.
+        #[arg(long)]
+        title: String,
+        /// Poll description.
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Allow selecting multiple options.
+        #[arg(long)]
+        multi: bool,
+        /// Poll option. Can be specified multiple times.
+        #[arg(long = "option", required = true)]
+        options: Vec<String>,
+    },
+    /// Outputs our public key for a poll.
+    Key {
+        /// Path to the poll spec.
+        spec: PathBuf,
+    },
+    /// Creates an application to participate in a poll.
+    Apply {
+        /// Path to the poll spec.
+        spec: PathBuf,
+    },
+    /// Casts a vote in a poll. The set of participants in the exported poll is considered final.
+    Vote {
+        /// Path to the exported poll.
+        poll: PathBuf,
+        /// Selected option (1-based). Can be specified multiple times for multi-choice polls.
+        #[arg(long = "choice", required = true)]
+        choices: Vec<usize>,
+    },
+    /// Computes a tallier share for a poll. The set of votes in the exported poll
+    /// is considered final.
+    Tally {
+        /// Path to the exported poll.
+        poll: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum KdfArg {
+    Argon2id,
+    Pbkdf2,
+}
+
+impl From<KdfArg> for Kdf {
+    fn from(arg: KdfArg) -> Self {
+        match arg {
+            KdfArg::Argon2id => Kdf::ARGON2,
+            KdfArg::Pbkdf2 => Kdf::PBKDF2,
+        }
+    }
+}
+
+fn read_input(path: &Path) -> Result<String, Box<dyn StdError>> {
+    let contents = if path.as_os_str() == "-" {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        fs::read_to_string(path)
+            .map_err(|err| format!("cannot read `{}`: {err}", path.display()))?
+    };
+    Ok(contents)
+}
+
+/// Reads a poll spec, which can also be supplied as an invitation link.
+fn read_spec(path: &Path) -> Result<PollSpec, Box<dyn StdError>> {
+    let contents = read_input(path)?;
+    let is_link = !contents.trim_start().starts_with('{');
+    if let Some((_, fragment)) = contents.trim().split_once('#').filter(|_| is_link) {
+        return Ok(PollSpec::from_link_fragment(fragment)?);
+    }
+    PollSpec::decode(&contents).map_err(|err| format!("cannot parse poll spec: {err}").into())
+}
+
+fn read_poll(path: &Path) -> Result<(PollId, PollState), Box<dyn StdError>> {
+    let contents = read_input(path)?;
+    let exported = ExportedPoll::decode(&contents)
+        .map_err(|err| format!("cannot parse exported poll: {err}"))?;
+    let (poll_id, poll) = PollState::import(exported)?;
+    Ok((poll_id, poll))
+}
+
+fn ensure_participant(keys: &Keypair, poll: &PollState) -> Result<(), Box<dyn StdError>> {
+    if poll.has_participant(keys.public()) {
+        Ok(())
+    } else {
+        Err("we are not a participant of the poll".into())
+    }
+}
+
+fn vote_choice(spec: &PollSpec, choices: &[usize]) -> Result<VoteChoice, Box<dyn StdError>> {
+    let options_count = spec.options.len();
+    if let Some(&choice) = choices.iter().find(|&&i| i == 0 || i > options_count) {
+        return Err(
+            format!("invalid choice {choice}; expected a value in 1..={options_count}").into(),
+        );
+    }
+
+    match spec.poll_type {
+        PollType::SingleChoice => {
+            let [choice] = choices else {
+                return Err("exactly one choice must be specified for a single-choice poll".into());
+            };
+            Ok(VoteChoice::SingleChoice(choice - 1))
+        }
+        PollType::MultiChoice => {
+            let mut selected = vec![false; options_count];
+            for &choice in choices {
+                selected[choice - 1] = true;
+            }
+            Ok(VoteChoice::MultiChoice(selected))
+        }
+    }
+}
+
+impl Args {
+    fn output<T: CompactEncoding>(&self, value: &T) -> Result<(), Box<dyn StdError>> {
+        if self.compact {
+            println!("{}", value.to_compact());
+        } else {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        }
+        Ok(())
+    }
+
+    fn secret_file(&self) -> SecretFile {
+        SecretFile::new(self.secret.clone())
+    }
+
+    fn keys_for_poll(&self, poll_id: &PollId) -> Result<Keypair, Box<dyn StdError>> {
+        let secret = self.secret_file().open()?;
+        Ok(derive_poll_keys(&secret, poll_id))
+    }
+
+    fn run(&self) -> Result<(), Box<dyn StdError>> {
+        match &self.command {
+            Command::NewSecret { kdf, force } => {
+                self.secret_file().create((*kdf).into(), *force)?;
+                eprintln!("Created secret file `{}`", self.secret.display());
+            }
+
+            Command::Spec {
+                title,
+                description,
+                multi,
+                options,
+            } => {
+                let spec = Self::create_spec(title, description, *multi, options)?;
+                self.output(&spec)?;
+            }
+
+            Command::Key { spec } => {
+                let poll_id = PollId::for_spec(&read_spec(spec)?);
+                let keys = self.keys_for_poll(&poll_id)?;
+                let key = keys.public();
+                println!("{}", Base64UrlUnpadded::encode_string(key.as_bytes()));
+                eprintln!("Fingerprint: {}", Fingerprint::from(key));
+            }
+
+            Command::Apply { spec } => {
+                let poll_id = PollId::for_spec(&read_spec(spec)?);
+                let keys = self.keys_for_poll(&poll_id)?;
+                self.output(&ParticipantApplication::new(&keys, &poll_id))?;
+            }
+
+            Command::Vote { poll, choices } => {
+                let (poll_id, mut poll) = read_poll(poll)?;
+                if let PollStage::Participants { .. } = poll.stage() {
+                    poll.finalize_participants();
+                }
+                if !matches!(poll.stage(), PollStage::Voting { .. }) {
+                    return Err("poll is not at the voting stage".into());
+                }
+                let choice = vote_choice(poll.spec(), choices)?;
+                let keys = self.keys_for_poll(&poll_id)?;
+                ensure_participant(&keys, &poll)?;
+                self.output(&Vote::new(&keys, &poll_id, &poll, &choice))?;
+            }
+
+            Command::Tally { poll } => {
+                let (poll_id, mut poll) = read_poll(poll)?;
+                match poll.stage() {
+                    PollStage::Participants { .. } => {
+                        return Err("poll does not contain votes".into());
+                    }
+                    PollStage::Voting { .. } => poll.finalize_votes(),
+                    PollStage::Tallying { .. } => { /* ok */ }
+                    PollStage::Finished => return Err("poll is already finished".into()),
+                }
+                let keys = self.keys_for_poll(&poll_id)?;
+                ensure_participant(&keys, &poll)?;
+                self.output(&TallierShare::new(&keys, &poll_id, &poll))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn create_spec(
+        title: &str,
+        description: &str,
+        multi: bool,
+        options: &[String],
+    ) -> Result<PollSpec, Box<dyn StdError>> {
+        if title.trim().is_empty() {
+            return Err("title cannot be empty".into());
+        }
+        if options.len() > MAX_OPTIONS {
+            return Err(format!("number of options cannot exceed {MAX_OPTIONS}").into());
+        }
+        if options.iter().any(|option| option.trim().is_empty()) {
+            return Err("option title cannot be empty".into());
+        }
+        for (i, option) in options.iter().enumerate() {
+            if options[..i].contains(option) {
+                return Err(format!("option `{option}` is specified several times").into());
+            }
+        }
+
+        Ok(PollSpec {
+            title: title.to_owned(),
+            description: description.to_owned(),
+            poll_type: if multi {
+                PollType::MultiChoice
+            } else {
+                PollType::SingleChoice
+            },
+            nonce: OsRng.next_u32(),
+            options: options.to_vec(),
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Err(err) = args.run() {
+        eprintln!("Error: {err}");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
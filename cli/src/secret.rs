@@ -0,0 +1,86 @@
+//! Master secret sealed with a password and stored on disk.
+
+use elasticpoll_core::crypto::{Kdf, PasswordBox};
+use rand_core::OsRng;
+use secret_tree::{SecretTree, Seed};
+use zeroize::Zeroizing;
+
+use std::{env, error::Error as StdError, fs, io::Write as _, path::PathBuf};
+
+/// Environment variable to read the password from. If not set, the password is prompted for.
+const PASSWORD_VAR: &str = "ELASTICPOLL_PASSWORD";
+
+/// File with the sealed master secret. The file has the same format as secret backups
+/// in the web app, so a backup can be used as the secret file and vice versa.
+#[derive(Debug)]
+pub(crate) struct SecretFile {
+    path: PathBuf,
+}
+
+impl SecretFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read_password(confirm: bool) -> Result<Zeroizing<String>, Box<dyn StdError>> {
+        if let Ok(password) = env::var(PASSWORD_VAR) {
+            return Ok(Zeroizing::new(password));
+        }
+
+        let password = Zeroizing::new(rpassword::prompt_password("Password: ")?);
+        if confirm {
+            let confirmation = Zeroizing::new(rpassword::prompt_password("Repeat password: ")?);
+            if confirmation != password {
+                return Err("passwords do not match".into());
+            }
+        }
+        Ok(password)
+    }
+
+    /// Generates a new secret and seals it with a password.
+    pub fn create(&self, kdf: Kdf, force: bool) -> Result<(), Box<dyn StdError>> {
+        if !force && self.path.exists() {
+            return Err(format!(
+                "secret file `{}` already exists; use `--force` to overwrite it",
+                self.path.display()
+            )
+            .into());
+        }
+
+        let password = Self::read_password(true)?;
+        let secret = SecretTree::new(&mut OsRng);
+        let sealed = PasswordBox::seal(&password, secret.seed().expose_secret(), kdf, &mut OsRng)?;
+        let sealed = serde_json::to_string_pretty(&sealed)?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt as _;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&self.path)?;
+        file.write_all(sealed.as_bytes())?;
+        Ok(())
+    }
+
+    /// Opens the secret with a password.
+    pub fn open(&self) -> Result<SecretTree, Box<dyn StdError>> {
+        let sealed = fs::read_to_string(&self.path).map_err(|err| {
+            format!(
+                "cannot read secret file `{}`: {err}; use `new-secret` to create a secret",
+                self.path.display()
+            )
+        })?;
+        let sealed: PasswordBox = serde_json::from_str(&sealed)?;
+        let password = Self::read_password(false)?;
+        let bytes = sealed.open(&password)?;
+
+        let mut seed = Zeroizing::new([0_u8; 32]);
+        if bytes.len() != seed.len() {
+            return Err("unexpected secret length".into());
+        }
+        seed.copy_from_slice(&bytes);
+        Ok(SecretTree::from_seed(Seed::from(&*seed)))
+    }
+}
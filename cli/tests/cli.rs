@@ -0,0 +1,240 @@
+//! End-to-end tests for the participant CLI.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use secret_tree::{SecretTree, Seed};
+use serde_json::{json, Value};
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use elasticpoll_core::{
+    keys::derive_poll_keys, CompactEncoding, ParticipantApplication, PollId, PollSpec, PollStage,
+    PollState,
+};
+
+const PASSWORD: &str = "correct horse battery staple";
+
+/// Secret backup from the web app for `PASSWORD` and seed `0, 1, .., 31`.
+const APP_BACKUP: &str = r#"{
+  "kdf": "pbkdf2-sha256",
+  "cipher": "aes-128-gcm",
+  "ciphertext": "cd3ecf467d662064dc0497dda0f9e7889da0909f29a0e31894beea7f6ac5a424",
+  "mac": "e56b14cd114ac2750356fc7f8fc36d36",
+  "kdfparams": {
+    "salt": "c19ced4a090b8d4d182ffbdf61619e140cd83b880bdd68436ea73e6f3c0a9e97",
+    "iterations": 100000
+  },
+  "cipherparams": { "iv": "8f2fc32c3e606a426bd02bd7" }
+}"#;
+
+/// Temporary directory removed on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("elasticpoll-cli-{}-{name}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+fn run(secret: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_elasticpoll"))
+        .arg("--secret")
+        .arg(secret)
+        .args(args)
+        .env("ELASTICPOLL_PASSWORD", PASSWORD)
+        .output()
+        .unwrap()
+}
+
+fn run_ok(secret: &Path, args: &[&str]) -> String {
+    let output = run(secret, args);
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn run_json(secret: &Path, args: &[&str]) -> Value {
+    serde_json::from_str(&run_ok(secret, args)).unwrap()
+}
+
+fn create_spec(dir: &TempDir) -> (PollSpec, PathBuf) {
+    let secret = dir.0.join("unused.json");
+    let spec = run_ok(
+        &secret,
+        &[
+            "spec",
+            "--title",
+            "Sample poll",
+            "--option",
+            "Yes",
+            "--option",
+            "No",
+        ],
+    );
+    let spec_path = dir.write("spec.json", &spec);
+    (serde_json::from_str(&spec).unwrap(), spec_path)
+}
+
+#[test]
+fn poll_lifecycle() {
+    let dir = TempDir::new("lifecycle");
+    let secrets: Vec<_> = ["alice.json", "bob.json"]
+        .into_iter()
+        .map(|name| dir.0.join(name))
+        .collect();
+    for secret in &secrets {
+        run_ok(secret, &["new-secret", "--kdf", "pbkdf2"]);
+    }
+    // The secret must not be overwritten by accident.
+    let output = run(&secrets[0], &["new-secret", "--kdf", "pbkdf2"]);
+    assert!(!output.status.success());
+
+    let (spec, spec_path) = create_spec(&dir);
+    let spec_path = spec_path.to_str().unwrap();
+    let alice_app = run_json(&secrets[0], &["apply", spec_path]);
+    let bob_app = run_ok(&secrets[1], &["--compact", "apply", spec_path]);
+    let bob_app = ParticipantApplication::from_compact(bob_app.trim()).unwrap();
+    let mut exported = json!({
+        "spec": spec,
+        "participant_applications": [alice_app, bob_app],
+    });
+
+    let mut votes = vec![];
+    for (secret, choice) in secrets.iter().zip(["1", "2"]) {
+        let poll_path = dir.write("poll.json", &exported.to_string());
+        let poll_path = poll_path.to_str().unwrap();
+        votes.push(run_json(secret, &["vote", poll_path, "--choice", choice]));
+    }
+    exported["votes"] = votes.into();
+
+    let mut shares = vec![];
+    for secret in &secrets {
+        let poll_path = dir.write("poll.json", &exported.to_string());
+        shares.push(run_json(secret, &["tally", poll_path.to_str().unwrap()]));
+    }
+    exported["tallier_shares"] = shares.into();
+
+    let (poll_id, poll) = PollState::import(serde_json::from_value(exported).unwrap()).unwrap();
+    assert_eq!(poll_id, PollId::for_spec(&spec));
+    assert_eq!(poll.stage(), PollStage::Finished);
+    assert_eq!(poll.results().unwrap(), [1, 1]);
+}
+
+#[test]
+fn using_app_backup_as_secret() {
+    let dir = TempDir::new("backup");
+    let secret = dir.write("backup.json", APP_BACKUP);
+    let (spec, spec_path) = create_spec(&dir);
+    let key = run_ok(&secret, &["key", spec_path.to_str().unwrap()]);
+
+    let seed: Vec<u8> = (0..32).collect();
+    let seed: [u8; 32] = seed.try_into().unwrap();
+    let secret_tree = SecretTree::from_seed(Seed::from(&seed));
+    let expected_keys = derive_poll_keys(&secret_tree, &PollId::for_spec(&spec));
+    let expected_key = Base64UrlUnpadded::encode_string(expected_keys.public().as_bytes());
+    assert_eq!(key.trim(), expected_key);
+
+    // Invitation links should be supported as well.
+    let link = format!(
+        "https://elasticpoll.app/polls/join#{}",
+        spec.to_link_fragment()
+    );
+    let link_path = dir.write("link.txt", &link);
+    let key_from_link = run_ok(&secret, &["key", link_path.to_str().unwrap()]);
+    assert_eq!(key_from_link, key);
+}
+
+/// Checks that a secret created with the default options has the same format as
+/// boxes sealed by the host crypto in the web app, and thus can be imported into the app.
+#[test]
+fn default_secret_has_app_box_format() {
+    let dir = TempDir::new("format");
+    let secret = dir.0.join("secret.json");
+    run_ok(&secret, &["new-secret"]);
+    let sealed: Value = serde_json::from_str(&fs::read_to_string(&secret).unwrap()).unwrap();
+    let app_backup: Value = serde_json::from_str(APP_BACKUP).unwrap();
+
+    let field_names = |value: &Value| {
+        let mut names: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        names.sort_unstable();
+        names
+    };
+    assert_eq!(field_names(&sealed), field_names(&app_backup));
+    for field in ["kdfparams", "cipherparams"] {
+        assert_eq!(field_names(&sealed[field]), field_names(&app_backup[field]));
+    }
+    assert_eq!(sealed["kdf"], "pbkdf2-sha256");
+    assert_eq!(sealed["cipher"], "aes-128-gcm");
+    assert_eq!(sealed["kdfparams"]["iterations"], 100_000);
+
+    let hex_fields = [
+        (&sealed["ciphertext"], 32),
+        (&sealed["mac"], 16),
+        (&sealed["kdfparams"]["salt"], 32),
+        (&sealed["cipherparams"]["iv"], 12),
+    ];
+    for (field, byte_len) in hex_fields {
+        let field = field.as_str().unwrap();
+        assert_eq!(field.len(), byte_len * 2, "{field}");
+        assert!(
+            field
+                .bytes()
+                .all(|ch| matches!(ch, b'0'..=b'9' | b'a'..=b'f')),
+            "{field}"
+        );
+    }
+
+    let (_, spec_path) = create_spec(&dir);
+    run_ok(&secret, &["key", spec_path.to_str().unwrap()]);
+}
+
+#[test]
+fn voting_errors() {
+    let dir = TempDir::new("errors");
+    let secret = dir.0.join("secret.json");
+    let output = run(&secret, &["apply", "spec.json"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cannot read"), "{stderr}");
+
+    run_ok(&secret, &["new-secret", "--kdf", "pbkdf2"]);
+    let other_secret = dir.0.join("other.json");
+    run_ok(&other_secret, &["new-secret", "--kdf", "pbkdf2"]);
+
+    let (spec, spec_path) = create_spec(&dir);
+    let app = run_json(&secret, &["apply", spec_path.to_str().unwrap()]);
+    let exported = json!({ "spec": spec, "participant_applications": [app] });
+    let poll_path = dir.write("poll.json", &exported.to_string());
+    let poll_path = poll_path.to_str().unwrap();
+
+    let output = run(&secret, &["vote", poll_path, "--choice", "3"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("invalid choice 3"), "{stderr}");
+    let output = run(
+        &secret,
+        &["vote", poll_path, "--choice", "1", "--choice", "2"],
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("exactly one choice"), "{stderr}");
+    let output = run(&other_secret, &["vote", poll_path, "--choice", "1"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("not a participant"), "{stderr}");
+    let output = run(&secret, &["tally", poll_path]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("does not contain votes"), "{stderr}");
+}
@@ -0,0 +1,71 @@
+// Generated from the `elasticpoll-core` types; do not edit manually.
+
+export interface CandidateDecryption {
+  dh_element: GroupElement;
+}
+
+export interface Ciphertext {
+  blinded_element: GroupElement;
+  random_element: GroupElement;
+}
+
+export type EncryptedVoteChoice = { choices: Ciphertext[]; range_proof: RingProof; sum_proof: LogEqualityProof; type: "single_choice" } | { choices: Ciphertext[]; range_proof: RingProof; sum_proof: null; type: "multi_choice" };
+
+export interface ExportedPoll {
+  participant_applications?: ParticipantApplication[];
+  spec: PollSpec;
+  tallier_shares?: TallierShare[];
+  votes?: Vote[];
+}
+
+/** Compressed Ristretto group element encoded with base64url. */
+export type GroupElement = string;
+
+export interface LogEqualityProof {
+  challenge: Scalar;
+  response: Scalar;
+}
+
+export interface ParticipantApplication {
+  participation_consent: ProofOfPossession;
+  public_key: GroupElement;
+}
+
+export interface PollSpec {
+  description: string;
+  nonce: number;
+  options: string[];
+  poll_type: PollType;
+  title: string;
+}
+
+export type PollType = "single_choice" | "multi_choice";
+
+export interface ProofOfPossession {
+  challenge: Scalar;
+  responses: Scalar[];
+}
+
+export interface RingProof {
+  common_challenge: Scalar;
+  ring_responses: Scalar[];
+}
+
+/** Ristretto scalar encoded with base64url. */
+export type Scalar = string;
+
+export interface ShareWithProof {
+  proof: LogEqualityProof;
+  share: CandidateDecryption;
+}
+
+export interface TallierShare {
+  public_key: GroupElement;
+  shares: ShareWithProof[];
+}
+
+export interface Vote {
+  choice: EncryptedVoteChoice;
+  public_key: GroupElement;
+  signature: ProofOfPossession;
+}
@@ -0,0 +1,41 @@
+//! Clock abstraction used to timestamp poll events.
+
+use std::{fmt, sync::Arc};
+
+/// Source of timestamps for poll events (creating a poll, adding participants, submitting votes
+/// and tallier shares). Timestamps are informational; they do not influence poll verification.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current Unix timestamp in milliseconds.
+    fn now(&self) -> f64;
+}
+
+/// Default [`Clock`] based on the system time.
+///
+/// On the `wasm32-unknown-unknown` target, the system time is not available; if the `js`
+/// crate feature is enabled, the clock uses `Date.now()` from the JS host instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub(crate) fn shared() -> Arc<dyn Clock> {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    #[cfg(all(target_arch = "wasm32", feature = "js"))]
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "js")))]
+    #[allow(clippy::cast_precision_loss)] // millisecond timestamps fit into `f64` mantissa
+    fn now(&self) -> f64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        elapsed.as_millis() as f64
+    }
+}
@@ -0,0 +1,279 @@
+//! Compact binary encoding for exchanged poll data.
+//!
+//! The compact presentation is deflate-compressed [CBOR] wrapped in unpadded base64url
+//! and prepended with a short type-specific prefix. Group elements and scalars are random
+//! byte strings, so base64 encoding dominates the size of both presentations; the savings
+//! come from compressing repeated field names. For exported polls, the compact form
+//! is ~25% shorter than JSON. This is useful for transports with size limits, e.g. SMS
+//! or some messengers.
+//!
+//! [CBOR]: https://cbor.io/
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use elastic_elgamal::{
+    app::{EncryptedChoice, MultiChoice, SingleChoice},
+    ProofOfPossession,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{error::Error as StdError, fmt};
+
+use crate::{
+    EncryptedVoteChoice, ExportedPoll, Group, ParticipantApplication, PollSpec, PublicKey,
+    TallierShare, Vote,
+};
+
+/// Value that can be converted to / from the compact binary presentation.
+pub trait CompactEncoding: Sized + Serialize + DeserializeOwned {
+    /// Prefix of the compact presentation. Used to distinguish among types of encoded values.
+    const PREFIX: &'static str;
+
+    /// Encodes this value in the compact form.
+    fn to_compact(&self) -> String;
+
+    /// Decodes a value from the compact form.
+    fn from_compact(encoded: &str) -> Result<Self, CompactError>;
+
+    /// Decodes a value either from JSON or from the compact form, depending on the input.
+    fn decode(input: &str) -> Result<Self, Box<dyn StdError>> {
+        let input = input.trim();
+        if input.starts_with('{') {
+            serde_json::from_str(input).map_err(Into::into)
+        } else {
+            Self::from_compact(input).map_err(Into::into)
+        }
+    }
+}
+
+/// Errors that can occur when decoding values from the compact form.
+#[derive(Debug)]
+pub enum CompactError {
+    /// Input does not start with the expected prefix.
+    Prefix { expected: &'static str },
+    /// Error decoding base64url wrapper.
+    Base64(base64ct::Error),
+    /// Error decoding CBOR.
+    Cbor(String),
+    /// Error decompressing data.
+    Decompress(String),
+}
+
+impl fmt::Display for CompactError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Prefix { expected } => {
+                write!(
+                    formatter,
+                    "unexpected value type: expected JSON or compact value starting with `{expected}`"
+                )
+            }
+            Self::Base64(err) => write!(formatter, "cannot decode base64url: {err}"),
+            Self::Cbor(err) => write!(formatter, "cannot decode CBOR: {err}"),
+            Self::Decompress(err) => write!(formatter, "cannot decompress data: {err}"),
+        }
+    }
+}
+
+impl StdError for CompactError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Base64(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum size of decompressed data.
+const MAX_DECOMPRESSED_LEN: usize = 1 << 20;
+
+/// Encodes a value as compressed CBOR wrapped in base64url.
+fn encode_bytes<T: Serialize>(value: &T) -> String {
+    let mut bytes = vec![];
+    ciborium::into_writer(value, &mut bytes).expect("cannot serialize value to CBOR");
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, 9);
+    Base64UrlUnpadded::encode_string(&compressed)
+}
+
+fn decode_bytes<T: DeserializeOwned>(encoded: &str) -> Result<T, CompactError> {
+    let compressed = Base64UrlUnpadded::decode_vec(encoded).map_err(CompactError::Base64)?;
+    let bytes =
+        miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DECOMPRESSED_LEN)
+            .map_err(|err| CompactError::Decompress(err.to_string()))?;
+    ciborium::from_reader(bytes.as_slice()).map_err(|err| CompactError::Cbor(err.to_string()))
+}
+
+fn decode_fragment<T: DeserializeOwned>(fragment: &str) -> Result<T, CompactError> {
+    decode_bytes(fragment.trim().trim_start_matches('#'))
+}
+
+fn encode<T: Serialize>(prefix: &str, value: &T) -> String {
+    let mut encoded = prefix.to_owned();
+    encoded.push_str(&encode_bytes(value));
+    encoded
+}
+
+fn decode<T: DeserializeOwned>(prefix: &'static str, encoded: &str) -> Result<T, CompactError> {
+    let encoded = encoded
+        .trim()
+        .strip_prefix(prefix)
+        .ok_or(CompactError::Prefix { expected: prefix })?;
+    decode_bytes(encoded)
+}
+
+impl CompactEncoding for PollSpec {
+    const PREFIX: &'static str = "eps1.";
+
+    fn to_compact(&self) -> String {
+        encode(Self::PREFIX, self)
+    }
+
+    fn from_compact(encoded: &str) -> Result<Self, CompactError> {
+        decode(Self::PREFIX, encoded)
+    }
+}
+
+impl PollSpec {
+    /// Encodes this spec for embedding into a URL fragment, such as in invitation links.
+    pub fn to_link_fragment(&self) -> String {
+        encode_bytes(self)
+    }
+
+    /// Decodes a spec from a URL fragment produced by [`Self::to_link_fragment()`].
+    pub fn from_link_fragment(fragment: &str) -> Result<Self, CompactError> {
+        decode_fragment(fragment)
+    }
+}
+
+impl CompactEncoding for ParticipantApplication {
+    const PREFIX: &'static str = "epa1.";
+
+    fn to_compact(&self) -> String {
+        encode(Self::PREFIX, self)
+    }
+
+    fn from_compact(encoded: &str) -> Result<Self, CompactError> {
+        decode(Self::PREFIX, encoded)
+    }
+}
+
+/// Externally tagged version of [`EncryptedVoteChoice`]. Internally tagged enums
+/// do not play well with non-human-readable formats in `serde`, so we use this proxy instead.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CompactVoteChoice {
+    SingleChoice(EncryptedChoice<Group, SingleChoice>),
+    MultiChoice(EncryptedChoice<Group, MultiChoice>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactVote {
+    choice: CompactVoteChoice,
+    public_key: PublicKey,
+    signature: ProofOfPossession<Group>,
+}
+
+impl From<Vote> for CompactVote {
+    fn from(vote: Vote) -> Self {
+        let choice = match vote.choice {
+            EncryptedVoteChoice::SingleChoice(choice) => CompactVoteChoice::SingleChoice(choice),
+            EncryptedVoteChoice::MultiChoice(choice) => CompactVoteChoice::MultiChoice(choice),
+        };
+        Self {
+            choice,
+            public_key: vote.public_key,
+            signature: vote.signature,
+        }
+    }
+}
+
+impl From<CompactVote> for Vote {
+    fn from(vote: CompactVote) -> Self {
+        let choice = match vote.choice {
+            CompactVoteChoice::SingleChoice(choice) => EncryptedVoteChoice::SingleChoice(choice),
+            CompactVoteChoice::MultiChoice(choice) => EncryptedVoteChoice::MultiChoice(choice),
+        };
+        Self {
+            choice,
+            public_key: vote.public_key,
+            signature: vote.signature,
+        }
+    }
+}
+
+impl CompactEncoding for Vote {
+    const PREFIX: &'static str = "epv1.";
+
+    fn to_compact(&self) -> String {
+        encode(Self::PREFIX, &CompactVote::from(self.clone()))
+    }
+
+    fn from_compact(encoded: &str) -> Result<Self, CompactError> {
+        decode::<CompactVote>(Self::PREFIX, encoded).map(Vote::from)
+    }
+}
+
+impl CompactEncoding for TallierShare {
+    const PREFIX: &'static str = "ept1.";
+
+    fn to_compact(&self) -> String {
+        encode(Self::PREFIX, self)
+    }
+
+    fn from_compact(encoded: &str) -> Result<Self, CompactError> {
+        decode(Self::PREFIX, encoded)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactExportedPoll {
+    spec: PollSpec,
+    participant_applications: Vec<ParticipantApplication>,
+    votes: Vec<CompactVote>,
+    tallier_shares: Vec<TallierShare>,
+}
+
+impl From<ExportedPoll> for CompactExportedPoll {
+    fn from(poll: ExportedPoll) -> Self {
+        Self {
+            spec: poll.spec,
+            participant_applications: poll.participant_applications,
+            votes: poll.votes.into_iter().map(CompactVote::from).collect(),
+            tallier_shares: poll.tallier_shares,
+        }
+    }
+}
+
+impl From<CompactExportedPoll> for ExportedPoll {
+    fn from(poll: CompactExportedPoll) -> Self {
+        Self {
+            spec: poll.spec,
+            participant_applications: poll.participant_applications,
+            votes: poll.votes.into_iter().map(Vote::from).collect(),
+            tallier_shares: poll.tallier_shares,
+        }
+    }
+}
+
+impl CompactEncoding for ExportedPoll {
+    const PREFIX: &'static str = "epe1.";
+
+    fn to_compact(&self) -> String {
+        encode(Self::PREFIX, &CompactExportedPoll::from(self.clone()))
+    }
+
+    fn from_compact(encoded: &str) -> Result<Self, CompactError> {
+        decode::<CompactExportedPoll>(Self::PREFIX, encoded).map(ExportedPoll::from)
+    }
+}
+
+impl ExportedPoll {
+    /// Encodes this poll for embedding into a URL fragment, such as in links to poll results.
+    pub fn to_link_fragment(&self) -> String {
+        encode_bytes(&CompactExportedPoll::from(self.clone()))
+    }
+
+    /// Decodes a poll from a URL fragment produced by [`Self::to_link_fragment()`].
+    pub fn from_link_fragment(fragment: &str) -> Result<Self, CompactError> {
+        decode_fragment::<CompactExportedPoll>(fragment).map(ExportedPoll::from)
+    }
+}
@@ -0,0 +1,375 @@
+//! Password-based encryption used to seal the master secret.
+//!
+//! Boxes produced and consumed by this module have the same JSON format as boxes
+//! of the JS host in the web app (see `webpack/crypto.js`):
+//!
+//! ```text
+//! {
+//!   "kdf": "pbkdf2-sha256" | "argon2id",
+//!   "kdfparams": { "salt": hex, ... },
+//!   "cipher": "aes-128-gcm",
+//!   "cipherparams": { "iv": hex },
+//!   "ciphertext": hex,
+//!   "mac": hex
+//! }
+//! ```
+//!
+//! Besides PBKDF2 used by the host, the memory-hard Argon2id KDF is supported.
+//! Boxes with Argon2id cannot be opened by the host implementation.
+
+use aes_gcm::{
+    aead::{AeadInPlace, KeyInit},
+    Aes128Gcm, Nonce, Tag,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use std::{error::Error as StdError, fmt};
+
+const SALT_LEN: usize = 32;
+const IV_LEN: usize = 12;
+const MAC_LEN: usize = 16;
+const KEY_LEN: usize = 16;
+
+// Upper bounds on KDF params. Boxes are untrusted input (e.g., imported backups), so without
+// these bounds, a crafted box could make key derivation allocate gigabytes of memory
+// or run for minutes.
+/// Max number of PBKDF2 iterations.
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// Max Argon2id memory size in KiB (256 MiB).
+const MAX_ARGON2_MEMORY: u32 = 256 * 1_024;
+/// Max number of Argon2id iterations.
+const MAX_ARGON2_ITERATIONS: u32 = 10;
+/// Max Argon2id degree of parallelism.
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// Key derivation function used to seal new boxes.
+///
+/// KDF params are bounded: at most 10,000,000 PBKDF2 iterations; for Argon2id, at most 256 MiB
+/// of memory, 10 iterations and parallelism 16. Boxes with params exceeding these bounds
+/// can be neither sealed nor opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    /// PBKDF2 with HMAC-SHA256. Boxes sealed with this KDF can be opened by the JS host.
+    Pbkdf2Sha256 {
+        /// Number of iterations.
+        iterations: u32,
+    },
+    /// Argon2id.
+    Argon2id {
+        /// Memory size in KiB.
+        memory: u32,
+        /// Number of iterations.
+        iterations: u32,
+        /// Degree of parallelism.
+        parallelism: u32,
+    },
+}
+
+impl Kdf {
+    /// PBKDF2 with the same params as used by the JS host.
+    pub const PBKDF2: Self = Self::Pbkdf2Sha256 {
+        iterations: 100_000,
+    };
+
+    /// Argon2id with params recommended by OWASP (19 MiB of memory, 2 iterations).
+    pub const ARGON2: Self = Self::Argon2id {
+        memory: 19_456,
+        iterations: 2,
+        parallelism: 1,
+    };
+
+    fn with_salt(self, salt: Vec<u8>) -> KdfParams {
+        match self {
+            Self::Pbkdf2Sha256 { iterations } => KdfParams::Pbkdf2Sha256 { salt, iterations },
+            Self::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+            } => KdfParams::Argon2id {
+                salt,
+                memory,
+                iterations,
+                parallelism,
+            },
+        }
+    }
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Self::ARGON2
+    }
+}
+
+/// KDF together with its params, as recorded in a box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kdf", content = "kdfparams")]
+enum KdfParams {
+    #[serde(rename = "pbkdf2-sha256")]
+    Pbkdf2Sha256 {
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+        iterations: u32,
+    },
+    #[serde(rename = "argon2id")]
+    Argon2id {
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+        memory: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+}
+
+impl KdfParams {
+    fn kdf(&self) -> Kdf {
+        match self {
+            Self::Pbkdf2Sha256 { iterations, .. } => Kdf::Pbkdf2Sha256 {
+                iterations: *iterations,
+            },
+            Self::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                ..
+            } => Kdf::Argon2id {
+                memory: *memory,
+                iterations: *iterations,
+                parallelism: *parallelism,
+            },
+        }
+    }
+
+    fn check_bounds(&self) -> Result<(), BoxError> {
+        fn check(name: &str, value: u32, max: u32) -> Result<(), BoxError> {
+            if value > max {
+                let message = format!("{name} ({value}) exceeds the maximum allowed value {max}");
+                Err(BoxError::Kdf(message))
+            } else {
+                Ok(())
+            }
+        }
+
+        match self {
+            Self::Pbkdf2Sha256 { iterations, .. } => {
+                check("PBKDF2 iterations", *iterations, MAX_PBKDF2_ITERATIONS)
+            }
+            Self::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                ..
+            } => {
+                check("Argon2id memory", *memory, MAX_ARGON2_MEMORY)?;
+                check("Argon2id iterations", *iterations, MAX_ARGON2_ITERATIONS)?;
+                check("Argon2id parallelism", *parallelism, MAX_ARGON2_PARALLELISM)
+            }
+        }
+    }
+
+    fn derive_key(&self, password: &str) -> Result<Zeroizing<[u8; KEY_LEN]>, BoxError> {
+        self.check_bounds()?;
+        let mut key = Zeroizing::new([0_u8; KEY_LEN]);
+        match self {
+            Self::Pbkdf2Sha256 { salt, iterations } => {
+                if *iterations == 0 {
+                    return Err(BoxError::Kdf("zero PBKDF2 iterations".to_owned()));
+                }
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, *iterations, &mut *key);
+            }
+            Self::Argon2id {
+                salt,
+                memory,
+                iterations,
+                parallelism,
+            } => {
+                let params = Params::new(*memory, *iterations, *parallelism, Some(KEY_LEN))
+                    .map_err(|err| BoxError::Kdf(err.to_string()))?;
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(password.as_bytes(), salt, &mut *key)
+                    .map_err(|err| BoxError::Kdf(err.to_string()))?;
+            }
+        }
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Cipher {
+    #[serde(rename = "aes-128-gcm")]
+    Aes128Gcm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CipherParams {
+    #[serde(with = "hex_bytes")]
+    iv: Vec<u8>,
+}
+
+/// Errors that can occur when sealing or opening a [`PasswordBox`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BoxError {
+    /// Box cannot be parsed from JSON.
+    Json(serde_json::Error),
+    /// Invalid length of a box field.
+    InvalidLength {
+        /// Name of the field.
+        field: &'static str,
+        /// Expected length of the field in bytes.
+        expected: usize,
+    },
+    /// Invalid KDF params.
+    Kdf(String),
+    /// Decryption has failed, e.g., because the password is incorrect.
+    Decryption,
+}
+
+impl fmt::Display for BoxError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(formatter, "cannot parse box: {err}"),
+            Self::InvalidLength { field, expected } => {
+                write!(
+                    formatter,
+                    "invalid `{field}` length; expected {expected} bytes"
+                )
+            }
+            Self::Kdf(message) => write!(formatter, "invalid KDF params: {message}"),
+            Self::Decryption => {
+                formatter.write_str("failed decryption (perhaps, the password is incorrect)")
+            }
+        }
+    }
+}
+
+impl StdError for BoxError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Password-encrypted box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordBox {
+    #[serde(flatten)]
+    kdf: KdfParams,
+    cipher: Cipher,
+    cipherparams: CipherParams,
+    #[serde(with = "hex_bytes")]
+    ciphertext: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    mac: Vec<u8>,
+}
+
+impl PasswordBox {
+    /// Seals `secret` with the key derived from `password` using the specified `kdf`.
+    pub fn seal<R: CryptoRng + RngCore>(
+        password: &str,
+        secret: &[u8],
+        kdf: Kdf,
+        rng: &mut R,
+    ) -> Result<Self, BoxError> {
+        let mut salt = vec![0_u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let mut iv = [0_u8; IV_LEN];
+        rng.fill_bytes(&mut iv);
+
+        let kdf = kdf.with_salt(salt);
+        let key = kdf.derive_key(password)?;
+        let cipher = Aes128Gcm::new_from_slice(&*key).expect("invalid key length");
+        let mut ciphertext = secret.to_vec();
+        let mac = cipher
+            .encrypt_in_place_detached(Nonce::from_slice(&iv), b"", &mut ciphertext)
+            .expect("cannot encrypt secret");
+
+        Ok(Self {
+            kdf,
+            cipher: Cipher::Aes128Gcm,
+            cipherparams: CipherParams { iv: iv.to_vec() },
+            ciphertext,
+            mac: mac.to_vec(),
+        })
+    }
+
+    /// Returns the KDF used by this box.
+    pub fn kdf(&self) -> Kdf {
+        self.kdf.kdf()
+    }
+
+    /// Opens this box with the specified `password`.
+    pub fn open(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, BoxError> {
+        if self.cipherparams.iv.len() != IV_LEN {
+            return Err(BoxError::InvalidLength {
+                field: "iv",
+                expected: IV_LEN,
+            });
+        }
+        if self.mac.len() != MAC_LEN {
+            return Err(BoxError::InvalidLength {
+                field: "mac",
+                expected: MAC_LEN,
+            });
+        }
+
+        let key = self.kdf.derive_key(password)?;
+        let cipher = Aes128Gcm::new_from_slice(&*key).expect("invalid key length");
+        let mut plaintext = Zeroizing::new(self.ciphertext.clone());
+        cipher
+            .decrypt_in_place_detached(
+                Nonce::from_slice(&self.cipherparams.iv),
+                b"",
+                &mut plaintext,
+                Tag::from_slice(&self.mac),
+            )
+            .map_err(|_| BoxError::Decryption)?;
+        Ok(plaintext)
+    }
+}
+
+/// Lowercase hex encoding for byte fields, matching the JS host.
+mod hex_bytes {
+    use serde::{de::Error as DeError, Deserialize, Deserializer, Serializer};
+
+    use std::fmt::Write as _;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut encoded = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            write!(encoded, "{byte:02x}").unwrap();
+        }
+        serializer.serialize_str(&encoded)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        fn digit(ch: u8) -> Option<u8> {
+            match ch {
+                b'0'..=b'9' => Some(ch - b'0'),
+                b'a'..=b'f' => Some(ch - b'a' + 10),
+                _ => None,
+            }
+        }
+
+        let encoded = String::deserialize(deserializer)?;
+        if encoded.len() % 2 != 0 {
+            return Err(D::Error::custom("hex string has odd length"));
+        }
+        encoded
+            .as_bytes()
+            .chunks_exact(2)
+            .map(|chunk| {
+                let (hi, lo) = digit(chunk[0])
+                    .zip(digit(chunk[1]))
+                    .ok_or_else(|| D::Error::custom("hex string contains invalid chars"))?;
+                Ok((hi << 4) | lo)
+            })
+            .collect()
+    }
+}
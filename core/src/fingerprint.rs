@@ -0,0 +1,95 @@
+//! Human-comparable fingerprints for poll IDs, public keys and poll state digests.
+//!
+//! A fingerprint consists of [`FINGERPRINT_LEN`] words from [`WORDLIST`]. Each word
+//! encodes a byte of the SHA-256 digest of the fingerprinted value, so that a fingerprint
+//! carries 48 bits of the digest. This is enough to detect accidental mismatches and
+//! substitutions that are not specifically targeted at the fingerprint; the raw value
+//! should still be compared if there is doubt.
+
+use sha2::{Digest, Sha256};
+
+use std::fmt;
+
+use crate::{PollId, PublicKey};
+
+/// Number of words in a fingerprint.
+pub const FINGERPRINT_LEN: usize = 6;
+
+/// Words used in fingerprints, one per byte value. Words are sorted, distinct, consist
+/// of lowercase ASCII letters and are not longer than 8 chars.
+pub const WORDLIST: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "album", "alert", "alpha", "amber", "angle",
+    "ankle", "apple", "apron", "arena", "armor", "arrow", "atlas", "attic", "bacon", "badge",
+    "bagel", "baker", "balsa", "bamboo", "banjo", "baron", "basil", "beach", "beard", "berry",
+    "bison", "blade", "blaze", "bloom", "board", "bonus", "boxer", "brick", "broom", "brush",
+    "bugle", "cabin", "cable", "cactus", "camel", "candy", "canoe", "cargo", "carpet", "cedar",
+    "chalk", "charm", "cheese", "cherry", "chess", "cider", "circus", "clerk", "cliff", "clock",
+    "cloud", "clover", "cobra", "cocoa", "comet", "coral", "cotton", "crane", "crown", "daisy",
+    "dancer", "delta", "denim", "desert", "diary", "dingo", "dolphin", "donkey", "dragon", "drum",
+    "dune", "eagle", "easel", "ebony", "echo", "elbow", "elder", "elm", "ember", "emerald",
+    "engine", "fabric", "falcon", "fern", "ferry", "fiddle", "flame", "flute", "forest", "fossil",
+    "fox", "frost", "fudge", "galaxy", "garden", "garlic", "gecko", "geyser", "ginger", "glacier",
+    "globe", "goblin", "grape", "gravel", "guitar", "hammer", "harbor", "hazel", "helmet", "heron",
+    "hippo", "honey", "hornet", "husky", "igloo", "iguana", "inlet", "iris", "ivory", "jacket",
+    "jaguar", "jelly", "jester", "jewel", "jigsaw", "jockey", "judge", "juice", "jungle", "kayak",
+    "kernel", "kettle", "kiwi", "koala", "label", "ladder", "lagoon", "lantern", "laser", "lava",
+    "lemon", "lily", "lizard", "lobster", "locket", "lotus", "lunar", "magnet", "mango", "maple",
+    "marble", "meadow", "melon", "mirror", "mocha", "monkey", "moose", "mosaic", "motor", "muffin",
+    "napkin", "nebula", "needle", "nickel", "noodle", "nugget", "oasis", "ocean", "olive", "onion",
+    "opal", "orbit", "orchid", "otter", "paddle", "panda", "parrot", "pebble", "pepper", "piano",
+    "pickle", "pilot", "planet", "plum", "pocket", "polar", "poppy", "prism", "puzzle", "quail",
+    "quartz", "quiver", "rabbit", "radar", "raven", "reef", "ribbon", "rocket", "rodeo", "ruby",
+    "saddle", "salmon", "satin", "scarf", "shadow", "shovel", "silver", "sketch", "sloth",
+    "spider", "sponge", "squid", "statue", "summit", "sunset", "tablet", "tango", "teapot",
+    "tiger", "timber", "toast", "tomato", "topaz", "torch", "tulip", "tundra", "turtle", "tuxedo",
+    "umbrella", "unicorn", "velvet", "violin", "viper", "visor", "vortex", "waffle", "walnut",
+    "walrus", "willow", "window", "wizard", "yacht", "yogurt", "zebra", "zenith", "zipper",
+];
+
+/// Human-comparable fingerprint of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint([u8; FINGERPRINT_LEN]);
+
+impl Fingerprint {
+    const CONTEXT: &'static [u8] = b"elastic_poll::fingerprint";
+
+    /// Computes a fingerprint of the provided bytes.
+    pub fn new(bytes: &[u8]) -> Self {
+        let digest = Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(bytes)
+            .finalize();
+        let mut this = Self([0; FINGERPRINT_LEN]);
+        this.0.copy_from_slice(&digest[..FINGERPRINT_LEN]);
+        this
+    }
+
+    /// Iterates over the words in this fingerprint.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(|&byte| WORDLIST[usize::from(byte)])
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.words().enumerate() {
+            if i > 0 {
+                formatter.write_str(" ")?;
+            }
+            formatter.write_str(word)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&PollId> for Fingerprint {
+    fn from(id: &PollId) -> Self {
+        Self::new(&id.0)
+    }
+}
+
+impl From<&PublicKey> for Fingerprint {
+    fn from(key: &PublicKey) -> Self {
+        Self::new(key.as_bytes())
+    }
+}
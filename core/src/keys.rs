@@ -0,0 +1,27 @@
+//! Derivation of poll keys from the master secret.
+
+use rand_core::SeedableRng;
+use secret_tree::SecretTree;
+
+use std::hint::black_box;
+
+use crate::{Keypair, PollId};
+
+/// Derives keys for the poll from the master `secret`. The web app and native tools
+/// use the same derivation, so that a secret restored from a backup yields the same keys.
+pub fn derive_poll_keys(secret: &SecretTree, poll_id: &PollId) -> Keypair {
+    // The derived child secret is zeroized on drop, and so is the secret key
+    // of `keypair`. The RNG derived from the child secret is not, so we overwrite it
+    // manually.
+    let mut rng = secret.digest(poll_id.as_bytes()).rng();
+    let keypair = Keypair::generate(&mut rng);
+    wipe_rng(&mut rng);
+    keypair
+}
+
+/// Overwrites the RNG state, e.g., if the RNG is seeded from a secret.
+pub fn wipe_rng<R: SeedableRng>(rng: &mut R) {
+    *rng = R::from_seed(R::Seed::default());
+    // Prevent the compiler from optimizing away the overwrite.
+    black_box(rng);
+}
@@ -0,0 +1,678 @@
+//! Cryptographic core of the Elastic Poll app: poll specifications and state, participant
+//! applications, votes and tallier shares, together with their import / export.
+//!
+//! The crate does not depend on the browser environment, so it can be used in native tools.
+//! Besides poll types, it provides [password-based encryption](crypto) for the master secret
+//! and [derivation of poll keys](keys) from it, so that native tools can use secrets
+//! backed up from the app.
+//!
+//! # Environment dependencies
+//!
+//! Timestamps are obtained from a [`Clock`], which can be replaced for a specific [`PollState`]
+//! (see [`PollState::with_clock()`] and [`PollState::set_clock()`]).
+//!
+//! By default, randomness is sourced from [`OsRng`](rand_core::OsRng), i.e., from
+//! the [`getrandom`] backend configured for the target; e.g., the web app registers a custom
+//! backend using the host CSPRNG. Only the constructors
+//! of participant applications, votes and tallier shares have `*_with_rng` variants accepting
+//! an arbitrary RNG, which allows producing reproducible outputs (e.g., test vectors)
+//! with a seeded RNG. [Sealing password boxes](crypto::PasswordBox::seal()) accepts an RNG
+//! as well, while [poll keys](keys) are derived deterministically from the secret.
+//!
+//! [`getrandom`]: https://docs.rs/getrandom/
+
+// Linter settings.
+#![warn(missing_debug_implementations, bare_trait_objects, rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(
+    clippy::module_name_repetitions,
+    clippy::must_use_candidate,
+    clippy::missing_panics_doc,
+    clippy::missing_errors_doc
+)]
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use elastic_elgamal::{Ciphertext, DiscreteLogTable};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+use std::{error::Error as StdError, fmt, ops, str::FromStr, sync::Arc};
+
+mod clock;
+mod compact;
+pub mod crypto;
+mod fingerprint;
+pub mod keys;
+mod participant;
+#[cfg(feature = "schema")]
+pub mod schema;
+mod utils;
+
+pub use self::clock::{Clock, SystemClock};
+pub use self::compact::{CompactEncoding, CompactError};
+pub use self::fingerprint::{Fingerprint, FINGERPRINT_LEN, WORDLIST};
+pub use self::participant::{
+    EncryptedVoteChoice, Participant, ParticipantApplication, SubmittedTallierShare, SubmittedVote,
+    TallierShare, TallierShareError, Vote, VoteChoice, VoteError,
+};
+
+use self::utils::VecHelper;
+
+// **NB.** Keep this a single place to define the group.
+pub type Group = elastic_elgamal::group::Ristretto;
+pub type PublicKey = elastic_elgamal::PublicKey<Group>;
+pub type PublicKeyBytes = [u8; 32];
+pub type Keypair = elastic_elgamal::Keypair<Group>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PollType {
+    SingleChoice,
+    MultiChoice,
+}
+
+impl PollType {
+    pub fn as_human_string(self) -> &'static str {
+        match self {
+            Self::SingleChoice => "single choice",
+            Self::MultiChoice => "multiple choice",
+        }
+    }
+}
+
+impl FromStr for PollType {
+    type Err = Box<dyn StdError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single_choice" => Ok(Self::SingleChoice),
+            "multi_choice" => Ok(Self::MultiChoice),
+            _ => Err("Invalid `PollType` value".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PollSpec {
+    pub title: String,
+    pub description: String,
+    pub poll_type: PollType,
+    pub nonce: u32,
+    #[serde(with = "VecHelper::<String, 1, MAX_OPTIONS>")]
+    // Keep the max length in sync with `MAX_OPTIONS`.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "Vec<String>", length(min = 1, max = 16))
+    )]
+    pub options: Vec<String>,
+}
+
+/// Maximum allowed number of options in a poll (inclusive).
+pub const MAX_OPTIONS: usize = 16;
+
+/// Content-based poll ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PollId([u8; 32]);
+
+impl fmt::Display for PollId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = Base64UrlUnpadded::encode_string(&self.0);
+        formatter.write_str(&s)
+    }
+}
+
+impl FromStr for PollId {
+    type Err = Box<dyn StdError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED_INPUT_LEN: usize = 43; // ceil(32 * 4 / 3)
+
+        if s.len() != EXPECTED_INPUT_LEN {
+            return Err("Unexpected poll ID length".into());
+        }
+        let mut buffer = [0_u8; 32];
+        let decoded = Base64UrlUnpadded::decode(s, &mut buffer)?;
+        if decoded.len() != 32 {
+            return Err("Unexpected poll ID length".into());
+        }
+        Ok(Self(buffer))
+    }
+}
+
+impl Serialize for PollId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PollId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        encoded.parse().map_err(D::Error::custom)
+    }
+}
+
+impl PollId {
+    pub fn for_spec(spec: &PollSpec) -> Self {
+        let json = serde_json::to_string(&spec).expect("cannot serialize `PollSpec`");
+        let id = Sha256::digest(json.as_str());
+        let mut this = Self([0_u8; 32]);
+        this.0.copy_from_slice(&id);
+        this
+    }
+
+    /// Returns the byte presentation of this ID.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollStage {
+    Participants { participants: usize },
+    Voting { votes: usize, participants: usize },
+    Tallying { shares: usize, participants: usize },
+    Finished,
+}
+
+impl PollStage {
+    pub const PARTICIPANTS_IDX: usize = 1;
+    pub const VOTING_IDX: usize = 2;
+    pub const TALLYING_IDX: usize = 3;
+    pub const FINISHED_IDX: usize = 4;
+    pub const MAX_INDEX: usize = Self::FINISHED_IDX;
+
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Participants { .. } => Self::PARTICIPANTS_IDX,
+            Self::Voting { .. } => Self::VOTING_IDX,
+            Self::Tallying { .. } => Self::TALLYING_IDX,
+            Self::Finished => Self::FINISHED_IDX,
+        }
+    }
+
+    /// Returns the `snake_case` name of this stage: `participants`, `voting`, `tallying`
+    /// or `finished`. Used to denote the stage in events and reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Participants { .. } => "participants",
+            Self::Voting { .. } => "voting",
+            Self::Tallying { .. } => "tallying",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TallyResult {
+    InProgress,
+    Finished(Vec<u64>),
+}
+
+/// Local information about participation in a poll. Not exported together with the poll.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Participation {
+    /// Name of the profile used to participate in the poll.
+    pub profile: String,
+    /// Our public key in the poll. Allows detecting that the secret was replaced after
+    /// joining the poll.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<PublicKey>,
+}
+
+/// Ongoing or finished poll state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollState {
+    /// Unix timestamp (in milliseconds).
+    pub created_at: f64,
+    spec: PollSpec,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    participants: Vec<Participant>,
+    /// Shared encryption key for the voting. Only present if the set of participants is final.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shared_key: Option<PublicKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tally_result: Option<TallyResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    participation: Option<Participation>,
+    /// URL of the bulletin board used to exchange poll items. Local metadata that is
+    /// not exported together with the poll.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    board_url: Option<String>,
+    /// Clock used to timestamp participants, votes and tallier shares.
+    #[serde(skip, default = "SystemClock::shared")]
+    clock: Arc<dyn Clock>,
+}
+
+impl PollState {
+    pub fn new(spec: PollSpec) -> Self {
+        Self::with_clock(spec, SystemClock::shared())
+    }
+
+    /// Creates a poll using the specified clock for timestamps.
+    pub fn with_clock(spec: PollSpec, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            spec,
+            created_at: clock.now(),
+            participants: Vec::new(),
+            shared_key: None,
+            tally_result: None,
+            participation: None,
+            board_url: None,
+            clock,
+        }
+    }
+
+    /// Replaces the clock used for timestamps. The clock is not persisted; deserialized polls
+    /// use [`SystemClock`].
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn spec(&self) -> &PollSpec {
+        &self.spec
+    }
+
+    pub fn stage(&self) -> PollStage {
+        if self.shared_key.is_none() {
+            PollStage::Participants {
+                participants: self.participants.len(),
+            }
+        } else {
+            match &self.tally_result {
+                None => PollStage::Voting {
+                    votes: self
+                        .participants
+                        .iter()
+                        .filter(|p| p.vote.is_some())
+                        .count(),
+                    participants: self.participants.len(),
+                },
+                Some(TallyResult::InProgress) => PollStage::Tallying {
+                    shares: self
+                        .participants
+                        .iter()
+                        .filter(|p| p.tallier_share.is_some())
+                        .count(),
+                    participants: self.participants.len(),
+                },
+                Some(TallyResult::Finished(_)) => PollStage::Finished,
+            }
+        }
+    }
+
+    /// Returns local information about our participation in this poll, if any.
+    pub fn participation(&self) -> Option<&Participation> {
+        self.participation.as_ref()
+    }
+
+    pub fn set_participation(&mut self, participation: Option<Participation>) {
+        self.participation = participation;
+    }
+
+    /// Returns the URL of the bulletin board for this poll, if any.
+    pub fn board_url(&self) -> Option<&str> {
+        self.board_url.as_deref()
+    }
+
+    pub fn set_board_url(&mut self, url: Option<String>) {
+        self.board_url = url;
+    }
+
+    pub fn participants(&self) -> &[Participant] {
+        &self.participants
+    }
+
+    pub fn has_participant(&self, public_key: &PublicKey) -> bool {
+        self.participants
+            .iter()
+            .any(|p| p.public_key() == public_key)
+    }
+
+    pub fn insert_participant(&mut self, application: ParticipantApplication) {
+        assert!(
+            self.shared_key.is_none(),
+            "cannot change participants once they are finalized"
+        );
+
+        let existing_participant = self
+            .participants
+            .iter_mut()
+            .find(|p| *p.public_key() == application.public_key);
+        let participant = Participant::new(application, self.clock.now());
+        if let Some(existing_participant) = existing_participant {
+            *existing_participant = participant;
+        } else {
+            self.participants.push(participant);
+        }
+    }
+
+    pub fn remove_participant(&mut self, index: usize) {
+        assert!(
+            self.shared_key.is_none(),
+            "cannot change participants once they are finalized"
+        );
+        self.participants.remove(index);
+    }
+
+    pub fn shared_key(&self) -> Option<PublicKey> {
+        self.participants
+            .iter()
+            .map(|participant| participant.public_key().clone())
+            .reduce(ops::Add::add)
+    }
+
+    fn finalized_shared_key(&self) -> &PublicKey {
+        self.shared_key
+            .as_ref()
+            .expect("set of participants is not finalized")
+    }
+
+    pub fn finalize_participants(&mut self) {
+        self.shared_key = self.shared_key();
+    }
+
+    pub fn rollback_to_participants_selection(&mut self) {
+        self.rollback_to_voting();
+        self.shared_key = None;
+        for participant in &mut self.participants {
+            participant.vote = None;
+        }
+    }
+
+    pub fn contains_votes(&self) -> bool {
+        self.participants
+            .iter()
+            .any(|participant| participant.vote.is_some())
+    }
+
+    pub fn insert_vote(&mut self, poll_id: &PollId, vote: Vote) -> Result<(), VoteError> {
+        vote.verify(poll_id, self)?;
+        self.insert_unchecked_vote(vote);
+        Ok(())
+    }
+
+    pub fn insert_unchecked_vote(&mut self, vote: Vote) {
+        assert!(
+            self.shared_key.is_some(),
+            "cannot insert a vote before participants are finalized"
+        );
+        assert!(
+            self.tally_result.is_none(),
+            "cannot insert a vote after votes are finalized"
+        );
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|p| *p.public_key() == vote.public_key)
+            .expect("vote does not come from an eligible voter");
+        participant.vote = Some(SubmittedVote::new(vote, self.clock.now()));
+    }
+
+    pub fn finalize_votes(&mut self) {
+        self.tally_result = Some(TallyResult::InProgress);
+    }
+
+    pub fn rollback_to_voting(&mut self) {
+        self.tally_result = None;
+        for participant in &mut self.participants {
+            participant.tallier_share = None;
+        }
+    }
+
+    pub fn cumulative_choices(&self) -> Vec<Ciphertext<Group>> {
+        let mut ciphertexts = vec![Ciphertext::zero(); self.spec.options.len()];
+
+        let participant_ciphertexts = self
+            .participants
+            .iter()
+            .filter_map(|p| p.vote.as_ref().map(SubmittedVote::choices));
+        for vote_ciphertexts in participant_ciphertexts {
+            debug_assert_eq!(vote_ciphertexts.len(), ciphertexts.len());
+            for (dest, src) in ciphertexts.iter_mut().zip(vote_ciphertexts) {
+                *dest += *src;
+            }
+        }
+        ciphertexts
+    }
+
+    pub fn insert_tallier_share(
+        &mut self,
+        poll_id: &PollId,
+        share: TallierShare,
+    ) -> Result<(), TallierShareError> {
+        share.verify(poll_id, self)?;
+        self.insert_unchecked_tallier_share(share);
+        Ok(())
+    }
+
+    pub fn insert_unchecked_tallier_share(&mut self, share: TallierShare) {
+        assert!(
+            matches!(&self.tally_result, Some(TallyResult::InProgress)),
+            "cannot insert tallier share when tallying is not active"
+        );
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|p| *p.public_key() == share.public_key)
+            .expect("vote does not come from an eligible voter");
+        participant.tallier_share = Some(SubmittedTallierShare::new(share, self.clock.now()));
+
+        let all_shares_are_collected = self.participants.iter().all(|p| p.tallier_share.is_some());
+        if all_shares_are_collected {
+            self.tally_result = Some(TallyResult::Finished(self.tally_results()));
+        }
+    }
+
+    fn tally_results(&self) -> Vec<u64> {
+        let mut blinded_elements: Vec<_> = self
+            .cumulative_choices()
+            .into_iter()
+            .map(|ciphertext| *ciphertext.blinded_element())
+            .collect();
+        for participant in &self.participants {
+            let share = &participant.tallier_share.as_ref().unwrap().inner;
+            for (dest, src) in blinded_elements.iter_mut().zip(share.shares()) {
+                *dest -= src.as_element();
+            }
+        }
+
+        let table = DiscreteLogTable::<Group>::new(0..=self.participants.len() as u64);
+        blinded_elements
+            .into_iter()
+            .map(|elt| table.get(&elt).expect("cannot decrypt"))
+            .collect()
+    }
+
+    pub fn results(&self) -> Option<&[u64]> {
+        if let Some(TallyResult::Finished(results)) = &self.tally_result {
+            Some(results)
+        } else {
+            None
+        }
+    }
+
+    /// Computes the digest of participants and their submitted votes. The digest does not
+    /// depend on the order of participants, so it can be compared among participants
+    /// to check that they have the same view of the poll.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut entries: Vec<_> = self
+            .participants
+            .iter()
+            .map(|participant| {
+                let vote_hash = participant.vote.as_ref().map(|vote| vote.hash.as_str());
+                (participant.public_key_bytes(), vote_hash)
+            })
+            .collect();
+        entries.sort_unstable();
+
+        let mut hasher = Sha256::new()
+            .chain_update(b"elastic_poll::state")
+            .chain_update(PollId::for_spec(&self.spec).0);
+        for (key_bytes, vote_hash) in entries {
+            hasher.update(key_bytes);
+            // Vote hashes are base64-encoded, so a zero byte is an unambiguous separator.
+            hasher.update(vote_hash.unwrap_or_default());
+            hasher.update([0]);
+        }
+        let mut digest = [0_u8; 32];
+        digest.copy_from_slice(&hasher.finalize());
+        digest
+    }
+
+    pub fn export(&self) -> ExportedPoll {
+        ExportedPoll {
+            spec: self.spec.clone(),
+            participant_applications: self
+                .participants
+                .iter()
+                .map(|p| p.application.clone())
+                .collect(),
+            votes: self
+                .participants
+                .iter()
+                .filter_map(|p| p.vote.as_ref().map(|vote| vote.inner.clone()))
+                .collect(),
+            tallier_shares: self
+                .participants
+                .iter()
+                .filter_map(|p| p.tallier_share.as_ref().map(|share| share.inner.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn import(exported_poll: ExportedPoll) -> Result<(PollId, Self), Box<PollValidationError>> {
+        Self::import_with_clock(exported_poll, SystemClock::shared())
+    }
+
+    /// Same as [`Self::import()`], but uses the specified clock for timestamps.
+    pub fn import_with_clock(
+        exported_poll: ExportedPoll,
+        clock: Arc<dyn Clock>,
+    ) -> Result<(PollId, Self), Box<PollValidationError>> {
+        let poll_id = PollId::for_spec(&exported_poll.spec);
+        let mut poll = PollState::with_clock(exported_poll.spec, clock);
+
+        for participant in exported_poll.participant_applications {
+            let key = participant.public_key.clone();
+            participant
+                .validate(&poll_id)
+                .map_err(|err| PollValidationError::Application { key, err })?;
+            poll.insert_participant(participant);
+        }
+
+        if exported_poll.votes.is_empty() {
+            if !exported_poll.tallier_shares.is_empty() {
+                return Err(Box::new(PollValidationError::UnexpectedShares));
+            }
+            return Ok((poll_id, poll));
+        }
+
+        poll.finalize_participants();
+        for vote in exported_poll.votes {
+            let key = vote.public_key.clone();
+            poll.insert_vote(&poll_id, vote)
+                .map_err(|err| PollValidationError::Vote { key, err })?;
+        }
+
+        if !exported_poll.tallier_shares.is_empty() {
+            poll.finalize_votes();
+        }
+        for tallier_share in exported_poll.tallier_shares {
+            let key = tallier_share.public_key.clone();
+            poll.insert_tallier_share(&poll_id, tallier_share)
+                .map_err(|err| PollValidationError::TallierShare { key, err })?;
+        }
+
+        Ok((poll_id, poll))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExportedPoll {
+    spec: PollSpec,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    participant_applications: Vec<ParticipantApplication>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    votes: Vec<Vote>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tallier_shares: Vec<TallierShare>,
+}
+
+impl ExportedPoll {
+    pub fn spec(&self) -> &PollSpec {
+        &self.spec
+    }
+
+    pub fn participant_applications(&self) -> &[ParticipantApplication] {
+        &self.participant_applications
+    }
+
+    pub fn votes(&self) -> &[Vote] {
+        &self.votes
+    }
+
+    pub fn tallier_shares(&self) -> &[TallierShare] {
+        &self.tallier_shares
+    }
+}
+
+#[derive(Debug)]
+pub enum PollValidationError {
+    Application {
+        key: PublicKey,
+        err: Box<dyn StdError>,
+    },
+    Vote {
+        key: PublicKey,
+        err: VoteError,
+    },
+    TallierShare {
+        key: PublicKey,
+        err: TallierShareError,
+    },
+    UnexpectedShares,
+}
+
+impl fmt::Display for PollValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Application { key, err } => {
+                write!(
+                    formatter,
+                    "cannot validate application for participant {}: {err}",
+                    encode_key(key)
+                )
+            }
+            Self::Vote { key, err } => {
+                write!(
+                    formatter,
+                    "cannot validate vote for participant {}: {err}",
+                    encode_key(key)
+                )
+            }
+            Self::TallierShare { key, err } => {
+                write!(
+                    formatter,
+                    "cannot validate tallier share for participant {}: {err}",
+                    encode_key(key)
+                )
+            }
+
+            Self::UnexpectedShares => {
+                formatter.write_str("tallier shares present even when votes are not")
+            }
+        }
+    }
+}
+
+impl StdError for PollValidationError {}
+
+fn encode_key(key: &PublicKey) -> String {
+    Base64UrlUnpadded::encode_string(key.as_bytes())
+}
@@ -6,29 +6,43 @@ use elastic_elgamal::{
     CandidateDecryption, Ciphertext, LogEqualityProof, ProofOfPossession, VerifiableDecryption,
     VerificationError,
 };
-use js_sys::Date;
 use merlin::Transcript;
-use rand_core::OsRng;
+use rand_core::{CryptoRng, OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use wasm_bindgen::UnwrapThrowExt;
 
 use std::{convert::TryFrom, error::Error as StdError, fmt, iter, slice};
 
-use super::{Group, Keypair, PollId, PollSpec, PollState, PollType, PublicKey, PublicKeyBytes};
+use crate::{Group, Keypair, PollId, PollSpec, PollState, PollType, PublicKey, PublicKeyBytes};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ParticipantApplication {
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::GroupElement"))]
     pub public_key: PublicKey,
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "crate::schema::ProofOfPossession")
+    )]
     pub participation_consent: ProofOfPossession<Group>,
 }
 
 impl ParticipantApplication {
     pub fn new(keypair: &Keypair, poll_id: &PollId) -> Self {
+        Self::with_rng(keypair, poll_id, &mut OsRng)
+    }
+
+    /// Creates an application using the specified RNG. With a deterministic RNG,
+    /// the application is reproducible.
+    pub fn with_rng<R: CryptoRng + RngCore>(
+        keypair: &Keypair,
+        poll_id: &PollId,
+        rng: &mut R,
+    ) -> Self {
         let mut transcript = Transcript::new(b"participation_consent");
         transcript.append_message(b"poll_id", &poll_id.0);
         let participation_consent =
-            ProofOfPossession::new(slice::from_ref(keypair), &mut transcript, &mut OsRng);
+            ProofOfPossession::new(slice::from_ref(keypair), &mut transcript, rng);
         Self {
             public_key: keypair.public().clone(),
             participation_consent,
@@ -45,7 +59,7 @@ impl ParticipantApplication {
 }
 
 /// Poll participant (voter / tallier).
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
     #[serde(flatten)]
     pub application: ParticipantApplication,
@@ -54,25 +68,23 @@ pub struct Participant {
     pub tallier_share: Option<SubmittedTallierShare>,
 }
 
-impl From<ParticipantApplication> for Participant {
-    fn from(application: ParticipantApplication) -> Self {
+impl Participant {
+    pub(crate) fn new(application: ParticipantApplication, created_at: f64) -> Self {
         Self {
             application,
-            created_at: Date::now(),
+            created_at,
             vote: None,
             tallier_share: None,
         }
     }
-}
 
-impl Participant {
     pub fn public_key(&self) -> &PublicKey {
         &self.application.public_key
     }
 
     pub fn public_key_bytes(&self) -> PublicKeyBytes {
         PublicKeyBytes::try_from(self.public_key().as_bytes())
-            .expect_throw("unexpected public key byte size")
+            .expect("unexpected public key byte size")
     }
 }
 
@@ -120,10 +132,23 @@ impl VoteChoice {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EncryptedVoteChoice {
-    SingleChoice(EncryptedChoice<Group, SingleChoice>),
-    MultiChoice(EncryptedChoice<Group, MultiChoice>),
+    SingleChoice(
+        #[cfg_attr(
+            feature = "schema",
+            schemars(with = "crate::schema::EncryptedSingleChoice")
+        )]
+        EncryptedChoice<Group, SingleChoice>,
+    ),
+    MultiChoice(
+        #[cfg_attr(
+            feature = "schema",
+            schemars(with = "crate::schema::EncryptedMultiChoice")
+        )]
+        EncryptedChoice<Group, MultiChoice>,
+    ),
 }
 
 impl EncryptedVoteChoice {
@@ -136,14 +161,36 @@ impl EncryptedVoteChoice {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Vote {
-    choice: EncryptedVoteChoice,
-    pub(super) public_key: PublicKey,
-    signature: ProofOfPossession<Group>,
+    pub(crate) choice: EncryptedVoteChoice,
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::GroupElement"))]
+    pub(crate) public_key: PublicKey,
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "crate::schema::ProofOfPossession")
+    )]
+    pub(crate) signature: ProofOfPossession<Group>,
 }
 
 impl Vote {
+    /// Returns the public key of the voter.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
     pub fn new(keypair: &Keypair, poll_id: &PollId, poll: &PollState, choice: &VoteChoice) -> Self {
+        Self::with_rng(keypair, poll_id, poll, choice, &mut OsRng)
+    }
+
+    /// Creates a vote using the specified RNG both for encrypting the choice and signing it.
+    pub fn with_rng<R: CryptoRng + RngCore>(
+        keypair: &Keypair,
+        poll_id: &PollId,
+        poll: &PollState,
+        choice: &VoteChoice,
+        rng: &mut R,
+    ) -> Self {
         debug_assert_eq!(poll.spec.poll_type, choice.poll_type());
 
         let shared_key = poll.finalized_shared_key().clone();
@@ -151,23 +198,32 @@ impl Vote {
         let choice = match choice {
             VoteChoice::SingleChoice(choice) => {
                 let choice_params = ChoiceParams::single(shared_key, options_count);
-                let enc = EncryptedChoice::single(&choice_params, *choice, &mut OsRng);
+                let enc = EncryptedChoice::single(&choice_params, *choice, rng);
                 EncryptedVoteChoice::SingleChoice(enc)
             }
             VoteChoice::MultiChoice(choices) => {
                 let choice_params = ChoiceParams::multi(shared_key, options_count);
-                let enc = EncryptedChoice::new(&choice_params, choices, &mut OsRng);
+                let enc = EncryptedChoice::new(&choice_params, choices, rng);
                 EncryptedVoteChoice::MultiChoice(enc)
             }
         };
-        Self::sign(keypair, poll_id, choice)
+        Self::sign_with_rng(keypair, poll_id, choice, rng)
     }
 
     // Public for testing
     pub fn sign(keypair: &Keypair, poll_id: &PollId, choice: EncryptedVoteChoice) -> Self {
+        Self::sign_with_rng(keypair, poll_id, choice, &mut OsRng)
+    }
+
+    /// Signs the encrypted choice using the specified RNG.
+    pub fn sign_with_rng<R: CryptoRng + RngCore>(
+        keypair: &Keypair,
+        poll_id: &PollId,
+        choice: EncryptedVoteChoice,
+        rng: &mut R,
+    ) -> Self {
         let mut transcript = Self::create_transcript(poll_id, &choice);
-        let signature =
-            ProofOfPossession::new(slice::from_ref(keypair), &mut transcript, &mut OsRng);
+        let signature = ProofOfPossession::new(slice::from_ref(keypair), &mut transcript, rng);
 
         Self {
             choice,
@@ -180,14 +236,14 @@ impl Vote {
     // any related non-determinism, such as `HashMap`s).
     fn create_transcript(poll_id: &PollId, choice: &EncryptedVoteChoice) -> Transcript {
         let serialized_choice =
-            serde_json::to_string(choice).expect_throw("cannot serialize `VoteChoice`");
+            serde_json::to_string(choice).expect("cannot serialize `VoteChoice`");
         let mut transcript = Transcript::new(b"vote");
         transcript.append_message(b"poll_id", &poll_id.0);
         transcript.append_message(b"choice", serialized_choice.as_bytes());
         transcript
     }
 
-    pub(super) fn verify(&self, poll_id: &PollId, poll: &PollState) -> Result<(), VoteError> {
+    pub(crate) fn verify(&self, poll_id: &PollId, poll: &PollState) -> Result<(), VoteError> {
         // Check that the voter is eligible.
         if !poll.has_participant(&self.public_key) {
             return Err(VoteError::IneligibleVoter);
@@ -266,7 +322,7 @@ impl StdError for VoteError {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmittedVote {
     #[serde(flatten)]
     pub inner: Vote,
@@ -276,39 +332,54 @@ pub struct SubmittedVote {
     pub submitted_at: f64,
 }
 
-impl From<Vote> for SubmittedVote {
-    fn from(vote: Vote) -> Self {
-        let json = serde_json::to_string(&vote.choice)
-            .expect_throw("cannot serialize `EncryptedVoteChoice`");
+impl SubmittedVote {
+    pub(crate) fn new(vote: Vote, submitted_at: f64) -> Self {
+        let json =
+            serde_json::to_string(&vote.choice).expect("cannot serialize `EncryptedVoteChoice`");
         let vote_hash = Sha256::digest(json);
 
         Self {
             inner: vote,
             hash: Base64UrlUnpadded::encode_string(&vote_hash),
-            submitted_at: Date::now(),
+            submitted_at,
         }
     }
-}
 
-impl SubmittedVote {
-    pub(super) fn choices(&self) -> &[Ciphertext<Group>] {
+    pub(crate) fn choices(&self) -> &[Ciphertext<Group>] {
         self.inner.choice.choices_unchecked()
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TallierShare {
     shares: Vec<ShareWithProof>,
-    pub(super) public_key: PublicKey,
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::GroupElement"))]
+    pub(crate) public_key: PublicKey,
 }
 
 impl TallierShare {
+    /// Returns the public key of the tallier.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
     pub fn new(keypair: &Keypair, poll_id: &PollId, poll_state: &PollState) -> Self {
+        Self::with_rng(keypair, poll_id, poll_state, &mut OsRng)
+    }
+
+    /// Creates a tallier share using the specified RNG for decryption proofs.
+    pub fn with_rng<R: CryptoRng + RngCore>(
+        keypair: &Keypair,
+        poll_id: &PollId,
+        poll_state: &PollState,
+        rng: &mut R,
+    ) -> Self {
         let transcript = Self::create_transcript(poll_id, poll_state);
         let ciphertexts = poll_state.cumulative_choices();
         let shares = ciphertexts.into_iter().map(|ciphertext| {
             let (share, proof) =
-                VerifiableDecryption::new(ciphertext, keypair, &mut transcript.clone(), &mut OsRng);
+                VerifiableDecryption::new(ciphertext, keypair, &mut transcript.clone(), rng);
             ShareWithProof {
                 share: share.into(),
                 proof,
@@ -330,13 +401,13 @@ impl TallierShare {
         transcript
     }
 
-    pub(super) fn shares(&self) -> impl Iterator<Item = VerifiableDecryption<Group>> + '_ {
+    pub(crate) fn shares(&self) -> impl Iterator<Item = VerifiableDecryption<Group>> + '_ {
         self.shares
             .iter()
             .map(|share_with_proof| share_with_proof.share.into_unchecked())
     }
 
-    pub(super) fn verify(
+    pub(crate) fn verify(
         &self,
         poll_id: &PollId,
         poll: &PollState,
@@ -421,12 +492,18 @@ impl TallierShareError {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 struct ShareWithProof {
-    pub(super) share: CandidateDecryption<Group>,
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "crate::schema::CandidateDecryption")
+    )]
+    pub(crate) share: CandidateDecryption<Group>,
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::LogEqualityProof"))]
     proof: LogEqualityProof<Group>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmittedTallierShare {
     #[serde(flatten)]
     pub inner: TallierShare,
@@ -434,11 +511,11 @@ pub struct SubmittedTallierShare {
     pub submitted_at: f64,
 }
 
-impl From<TallierShare> for SubmittedTallierShare {
-    fn from(share: TallierShare) -> Self {
+impl SubmittedTallierShare {
+    pub(crate) fn new(share: TallierShare, submitted_at: f64) -> Self {
         Self {
             inner: share,
-            submitted_at: Date::now(),
+            submitted_at,
         }
     }
 }
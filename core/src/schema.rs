@@ -0,0 +1,274 @@
+//! JSON Schemas and TypeScript declarations for the data exchanged by poll participants:
+//! poll specifications, participant applications, votes, tallier shares and exported polls.
+//!
+//! Schemas are generated from the Rust types. Cryptographic types from `elastic-elgamal`
+//! are described by the schema-only types in this module, which mirror their serialization
+//! format.
+
+// Schema-only types are never constructed.
+#![allow(dead_code)]
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, RootSchema, Schema, SchemaObject, SingleOrVec},
+    schema_for, JsonSchema,
+};
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use crate::{ExportedPoll, ParticipantApplication, PollSpec, TallierShare, Vote};
+
+/// Pattern for a 32-byte value encoded with base64url without padding.
+const ENCODED_BYTES_PATTERN: &str = "^[A-Za-z0-9_-]{43}$";
+
+fn encoded_bytes_schema(description: &str) -> Schema {
+    let mut schema = SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_owned()),
+            ..Metadata::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        ..SchemaObject::default()
+    };
+    schema.string().pattern = Some(ENCODED_BYTES_PATTERN.to_owned());
+    schema.into()
+}
+
+/// Ristretto group element (e.g., a public key).
+pub(crate) struct GroupElement;
+
+impl JsonSchema for GroupElement {
+    fn schema_name() -> String {
+        "GroupElement".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        encoded_bytes_schema("Compressed Ristretto group element encoded with base64url.")
+    }
+}
+
+/// Scalar of the Ristretto group.
+pub(crate) struct Scalar;
+
+impl JsonSchema for Scalar {
+    fn schema_name() -> String {
+        "Scalar".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        encoded_bytes_schema("Ristretto scalar encoded with base64url.")
+    }
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct ProofOfPossession {
+    challenge: Scalar,
+    responses: Vec<Scalar>,
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct Ciphertext {
+    random_element: GroupElement,
+    blinded_element: GroupElement,
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct RingProof {
+    common_challenge: Scalar,
+    ring_responses: Vec<Scalar>,
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct LogEqualityProof {
+    challenge: Scalar,
+    response: Scalar,
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct CandidateDecryption {
+    dh_element: GroupElement,
+}
+
+// Encrypted choices; there is no sum proof for multi-choice polls.
+#[derive(JsonSchema)]
+pub(crate) struct EncryptedSingleChoice {
+    choices: Vec<Ciphertext>,
+    range_proof: RingProof,
+    sum_proof: LogEqualityProof,
+}
+
+#[derive(JsonSchema)]
+pub(crate) struct EncryptedMultiChoice {
+    choices: Vec<Ciphertext>,
+    range_proof: RingProof,
+    sum_proof: (),
+}
+
+/// Returns JSON Schemas for the exchanged data keyed by the file stem used in the repository
+/// (e.g., `poll-spec`).
+pub fn exchange_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("poll-spec", schema_for!(PollSpec)),
+        (
+            "participant-application",
+            schema_for!(ParticipantApplication),
+        ),
+        ("vote", schema_for!(Vote)),
+        ("tallier-share", schema_for!(TallierShare)),
+        ("exported-poll", schema_for!(ExportedPoll)),
+    ]
+}
+
+/// Returns TypeScript declarations for all types described by [`exchange_schemas()`].
+pub fn typescript_declarations() -> String {
+    let mut definitions = BTreeMap::new();
+    for (_, root) in exchange_schemas() {
+        let name = root
+            .schema
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.title.clone())
+            .expect("root schema has no title");
+        definitions.extend(root.definitions);
+        definitions.insert(name, Schema::Object(root.schema));
+    }
+
+    let mut output =
+        "// Generated from the `elasticpoll-core` types; do not edit manually.\n".to_owned();
+    for (name, schema) in &definitions {
+        output.push('\n');
+        let Schema::Object(schema) = schema else {
+            writeln!(output, "export type {name} = {};", ts_type(schema)).unwrap();
+            continue;
+        };
+        write_doc_comment(&mut output, "", schema);
+
+        let is_interface = schema.reference.is_none()
+            && schema.subschemas.is_none()
+            && schema.instance_type == Some(InstanceType::Object.into());
+        if is_interface {
+            writeln!(output, "export interface {name} {{").unwrap();
+            let object = schema.object.as_deref().cloned().unwrap_or_default();
+            for (property, property_schema) in &object.properties {
+                if let Schema::Object(property_schema) = property_schema {
+                    write_doc_comment(&mut output, "  ", property_schema);
+                }
+                let optional = if object.required.contains(property) {
+                    ""
+                } else {
+                    "?"
+                };
+                let ty = ts_type(property_schema);
+                writeln!(output, "  {property}{optional}: {ty};").unwrap();
+            }
+            output.push_str("}\n");
+        } else {
+            writeln!(output, "export type {name} = {};", ts_object_type(schema)).unwrap();
+        }
+    }
+    output
+}
+
+fn write_doc_comment(output: &mut String, indent: &str, schema: &SchemaObject) {
+    let description = schema
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.description.as_deref());
+    if let Some(description) = description {
+        writeln!(output, "{indent}/** {description} */").unwrap();
+    }
+}
+
+fn ts_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_owned(),
+        Schema::Bool(false) => "never".to_owned(),
+        Schema::Object(schema) => ts_object_type(schema),
+    }
+}
+
+fn ts_object_type(schema: &SchemaObject) -> String {
+    let mut parts = vec![];
+    if let Some(reference) = &schema.reference {
+        let name = reference.trim_start_matches("#/definitions/");
+        parts.push(name.to_owned());
+    }
+
+    if let Some(values) = &schema.enum_values {
+        // JSON strings, numbers etc. are valid TS literals.
+        let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+        parts.push(values.join(" | "));
+    } else if let Some(instance_type) = &schema.instance_type {
+        let types = match instance_type {
+            SingleOrVec::Single(ty) => vec![**ty],
+            SingleOrVec::Vec(types) => types.clone(),
+        };
+        let types: Vec<_> = types
+            .into_iter()
+            .map(|ty| ts_instance_type(ty, schema))
+            .collect();
+        parts.push(types.join(" | "));
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            parts.extend(all_of.iter().map(ts_type));
+        }
+        for variants in subschemas.one_of.iter().chain(&subschemas.any_of) {
+            let variants: Vec<_> = variants.iter().map(ts_type).collect();
+            parts.push(variants.join(" | "));
+        }
+    }
+
+    match parts.len() {
+        0 => "unknown".to_owned(),
+        1 => parts.pop().unwrap(),
+        _ => {
+            let parts: Vec<_> = parts.iter().map(|part| format!("({part})")).collect();
+            parts.join(" & ")
+        }
+    }
+}
+
+fn ts_instance_type(ty: InstanceType, schema: &SchemaObject) -> String {
+    match ty {
+        InstanceType::Null => "null".to_owned(),
+        InstanceType::Boolean => "boolean".to_owned(),
+        InstanceType::Integer | InstanceType::Number => "number".to_owned(),
+        InstanceType::String => "string".to_owned(),
+        InstanceType::Array => {
+            let items = schema.array.as_ref().and_then(|array| array.items.as_ref());
+            let item_type = match items {
+                Some(SingleOrVec::Single(item)) => ts_type(item),
+                Some(SingleOrVec::Vec(items)) => {
+                    let items: Vec<_> = items.iter().map(ts_type).collect();
+                    return format!("[{}]", items.join(", "));
+                }
+                None => "unknown".to_owned(),
+            };
+            if item_type.contains(' ') {
+                format!("({item_type})[]")
+            } else {
+                format!("{item_type}[]")
+            }
+        }
+        InstanceType::Object => {
+            let Some(object) = &schema.object else {
+                return "Record<string, unknown>".to_owned();
+            };
+            let properties: Vec<_> = object
+                .properties
+                .iter()
+                .map(|(property, property_schema)| {
+                    let optional = if object.required.contains(property) {
+                        ""
+                    } else {
+                        "?"
+                    };
+                    format!("{property}{optional}: {}", ts_type(property_schema))
+                })
+                .collect();
+            format!("{{ {} }}", properties.join("; "))
+        }
+    }
+}
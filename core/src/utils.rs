@@ -0,0 +1,69 @@
+//! Misc utils.
+
+use serde::{
+    de::{DeserializeOwned, Error as _, SeqAccess, Visitor},
+    Deserializer, Serialize, Serializer,
+};
+
+use std::{fmt, marker::PhantomData};
+
+/// Helper for (de)serializing vectors with the length constrained to `MIN..=MAX`.
+pub(crate) struct VecHelper<T, const MIN: usize, const MAX: usize>(PhantomData<T>);
+
+impl<T, const MIN: usize, const MAX: usize> VecHelper<T, MIN, MAX>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    pub fn serialize<S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        debug_assert!(values.len() >= MIN && values.len() <= MAX);
+        serializer.collect_seq(values.iter())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(Self::new())
+    }
+}
+
+impl<'de, T, const MIN: usize, const MAX: usize> Visitor<'de> for VecHelper<T, MIN, MAX>
+where
+    T: DeserializeOwned,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "at least {MIN} and at most {MAX} items")
+    }
+
+    fn visit_seq<S>(self, mut access: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let mut scalars: Vec<T> = if let Some(size) = access.size_hint() {
+            if size < MIN || size > MAX {
+                return Err(S::Error::invalid_length(size, &self));
+            }
+            Vec::with_capacity(size)
+        } else {
+            Vec::new()
+        };
+
+        while let Some(value) = access.next_element::<T>()? {
+            scalars.push(value);
+        }
+        if scalars.len() >= MIN && scalars.len() <= MAX {
+            Ok(scalars)
+        } else {
+            Err(S::Error::invalid_length(scalars.len(), &self))
+        }
+    }
+}
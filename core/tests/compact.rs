@@ -0,0 +1,157 @@
+//! Tests for compact encoding of poll data.
+
+use assert_matches::assert_matches;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use rand::rngs::OsRng;
+use serde::Serialize;
+
+use elasticpoll_core::{
+    CompactEncoding, CompactError, ExportedPoll, Keypair, ParticipantApplication, PollId, PollSpec,
+    PollState, PollType, TallierShare, Vote, VoteChoice,
+};
+
+fn multi_choice_poll() -> PollSpec {
+    PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::MultiChoice,
+        nonce: 0,
+        options: (0..16).map(|i| format!("Option #{i}")).collect(),
+    }
+}
+
+/// Checks that the compact form round-trips and is equivalent to the JSON form.
+fn assert_compact_round_trip<T: CompactEncoding>(value: &T) -> T {
+    let json = serde_json::to_string_pretty(value).unwrap();
+    let compact = value.to_compact();
+    assert!(compact.starts_with(T::PREFIX), "{compact}");
+    assert!(compact.len() < json.len(), "{compact}");
+
+    let restored = T::from_compact(&compact).unwrap();
+    assert_eq!(to_json(&restored), to_json(value));
+    // `decode()` should work with both forms.
+    let restored = T::decode(&compact).unwrap();
+    assert_eq!(to_json(&restored), to_json(value));
+    let restored = T::decode(&json).unwrap();
+    assert_eq!(to_json(&restored), to_json(value));
+    restored
+}
+
+fn to_json(value: &impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap()
+}
+
+fn finished_poll(participant_count: usize) -> (PollId, PollState) {
+    let poll_spec = multi_choice_poll();
+    let poll_id = PollId::for_spec(&poll_spec);
+    let mut poll = PollState::new(poll_spec);
+    let keys: Vec<_> = (0..participant_count)
+        .map(|_| Keypair::generate(&mut OsRng))
+        .collect();
+
+    for our_keys in &keys {
+        let app = ParticipantApplication::new(our_keys, &poll_id);
+        let app = assert_compact_round_trip(&app);
+        poll.insert_participant(app);
+    }
+    poll.finalize_participants();
+
+    for our_keys in &keys {
+        let our_choice = VoteChoice::MultiChoice([true, false].repeat(8));
+        let vote = Vote::new(our_keys, &poll_id, &poll, &our_choice);
+        let vote = assert_compact_round_trip(&vote);
+        poll.insert_vote(&poll_id, vote).unwrap();
+    }
+    poll.finalize_votes();
+
+    for our_keys in &keys {
+        let share = TallierShare::new(our_keys, &poll_id, &poll);
+        let share = assert_compact_round_trip(&share);
+        poll.insert_tallier_share(&poll_id, share).unwrap();
+    }
+    (poll_id, poll)
+}
+
+#[test]
+fn compact_encoding_round_trip_for_poll_items() {
+    finished_poll(3);
+}
+
+#[test]
+fn compact_encoding_round_trip_for_exported_poll() {
+    let (poll_id, poll) = finished_poll(2);
+    let exported = poll.export();
+    let compact = exported.to_compact();
+    let json = serde_json::to_string(&exported).unwrap();
+    // Repeated field names are compressed away.
+    assert!(compact.len() * 5 < json.len() * 4, "{compact}");
+
+    let exported = assert_compact_round_trip(&exported);
+    let (imported_id, imported) = PollState::import(exported).unwrap();
+    assert_eq!(imported_id, poll_id);
+    assert_eq!(imported.results(), poll.results());
+}
+
+#[test]
+fn link_fragment_round_trip_for_exported_poll() {
+    let (poll_id, poll) = finished_poll(2);
+    let fragment = poll.export().to_link_fragment();
+    let exported = ExportedPoll::from_link_fragment(&fragment).unwrap();
+    assert_eq!(to_json(&exported), to_json(&poll.export()));
+
+    let (imported_id, imported) = PollState::import(exported).unwrap();
+    assert_eq!(imported_id, poll_id);
+    assert_eq!(imported.results(), poll.results());
+
+    let err = PollSpec::from_link_fragment(&fragment).unwrap_err();
+    assert_matches!(err, CompactError::Cbor(_));
+}
+
+#[test]
+fn compact_encoding_errors() {
+    let poll_id = PollId::for_spec(&multi_choice_poll());
+    let app = ParticipantApplication::new(&Keypair::generate(&mut OsRng), &poll_id);
+    let compact = app.to_compact();
+
+    let err = Vote::from_compact(&compact).unwrap_err();
+    assert_matches!(err, CompactError::Prefix { expected } if expected == Vote::PREFIX);
+
+    let garbled = format!("{}!!!", ParticipantApplication::PREFIX);
+    let err = ParticipantApplication::from_compact(&garbled).unwrap_err();
+    assert_matches!(err, CompactError::Base64(_));
+
+    let bytes =
+        Base64UrlUnpadded::decode_vec(&compact[ParticipantApplication::PREFIX.len()..]).unwrap();
+    let truncated = format!(
+        "{}{}",
+        ParticipantApplication::PREFIX,
+        Base64UrlUnpadded::encode_string(&bytes[..bytes.len() - 5])
+    );
+    let err = ParticipantApplication::from_compact(&truncated).unwrap_err();
+    assert_matches!(err, CompactError::Decompress(_));
+
+    let err = ExportedPoll::decode(&compact).unwrap_err();
+    assert!(err.to_string().contains(ExportedPoll::PREFIX), "{err}");
+}
+
+#[test]
+fn link_fragment_round_trip_for_spec() {
+    let mut spec = multi_choice_poll();
+    spec.description = "This is a poll with a lengthy description. ".repeat(10);
+    let fragment = spec.to_link_fragment();
+    assert!(fragment.len() < spec.to_compact().len(), "{fragment}");
+    assert!(
+        fragment
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'),
+        "{fragment}"
+    );
+
+    let restored = PollSpec::from_link_fragment(&fragment).unwrap();
+    assert_eq!(PollId::for_spec(&restored), PollId::for_spec(&spec));
+    let restored = PollSpec::from_link_fragment(&format!("#{fragment}")).unwrap();
+    assert_eq!(PollId::for_spec(&restored), PollId::for_spec(&spec));
+
+    let err = PollSpec::from_link_fragment(&fragment[..fragment.len() / 2]).unwrap_err();
+    assert_matches!(err, CompactError::Base64(_) | CompactError::Decompress(_));
+}
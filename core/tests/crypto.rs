@@ -0,0 +1,172 @@
+//! Tests for password-based encryption.
+
+use assert_matches::assert_matches;
+use rand::{rngs::OsRng, CryptoRng, Error as RngError, RngCore};
+
+use elasticpoll_core::crypto::{BoxError, Kdf, PasswordBox};
+
+const PASSWORD: &str = "correct horse battery staple";
+
+/// Box sealed by the JS host (`webpack/crypto.js`) for `PASSWORD` and secret `0, 1, .., 31`.
+const HOST_BOX: &str = r#"{
+  "kdf": "pbkdf2-sha256",
+  "cipher": "aes-128-gcm",
+  "ciphertext": "cd3ecf467d662064dc0497dda0f9e7889da0909f29a0e31894beea7f6ac5a424",
+  "mac": "e56b14cd114ac2750356fc7f8fc36d36",
+  "kdfparams": {
+    "salt": "c19ced4a090b8d4d182ffbdf61619e140cd83b880bdd68436ea73e6f3c0a9e97",
+    "iterations": 100000
+  },
+  "cipherparams": { "iv": "8f2fc32c3e606a426bd02bd7" }
+}"#;
+
+/// Box with Argon2id KDF for the same password and secret, produced
+/// with the Python `cryptography` package.
+const ARGON2_BOX: &str = r#"{
+  "kdf": "argon2id",
+  "cipher": "aes-128-gcm",
+  "ciphertext": "0aeaea3098f1c99805b47efb8884bca3c82449cb2a6396c006e020a074f7e2eb",
+  "mac": "e3a5d0e990fe4445a341456c758eee25",
+  "kdfparams": {
+    "salt": "6465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f80818283",
+    "memory": 1024,
+    "iterations": 2,
+    "parallelism": 1
+  },
+  "cipherparams": { "iv": "c8c9cacbcccdcecfd0d1d2d3" }
+}"#;
+
+/// Box sealed by `PasswordBox::seal()` for `PASSWORD` and secret `0, 1, .., 31` with
+/// [`CountingRng`] starting from `0x20`. Opened by the JS host in `webpack/crypto.test.js`.
+const RUST_BOX: &str = r#"{
+  "kdf": "pbkdf2-sha256",
+  "cipher": "aes-128-gcm",
+  "ciphertext": "c7dccd655b815020ce007ba75a9ad8640acb73ec63b120e9b610a6610a0c6dd5",
+  "mac": "71e4c20570459518a82bc68ae366d541",
+  "kdfparams": {
+    "salt": "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+    "iterations": 100000
+  },
+  "cipherparams": { "iv": "404142434445464748494a4b" }
+}"#;
+
+/// Insecure RNG producing consecutive bytes, used to seal boxes reproducibly.
+struct CountingRng(u8);
+
+impl RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0_u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0_u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            *byte = self.0;
+            self.0 = self.0.wrapping_add(1);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for CountingRng {}
+
+/// Cheap Argon2id params to keep tests fast.
+const TEST_KDF: Kdf = Kdf::Argon2id {
+    memory: 1_024,
+    iterations: 2,
+    parallelism: 1,
+};
+
+#[test]
+fn opening_reference_boxes() {
+    let expected_secret: Vec<u8> = (0..32).collect();
+    for (box_json, expected_kdf) in [(HOST_BOX, Kdf::PBKDF2), (ARGON2_BOX, TEST_KDF)] {
+        let sealed: PasswordBox = serde_json::from_str(box_json).unwrap();
+        assert_eq!(sealed.kdf(), expected_kdf);
+        assert_eq!(*sealed.open(PASSWORD).unwrap(), expected_secret);
+        let err = sealed.open("wrong").unwrap_err();
+        assert_matches!(err, BoxError::Decryption);
+
+        // Serialization must preserve all fields.
+        let reference: serde_json::Value = serde_json::from_str(box_json).unwrap();
+        assert_eq!(serde_json::to_value(&sealed).unwrap(), reference);
+    }
+}
+
+#[test]
+fn sealing_reference_box() {
+    let secret: Vec<u8> = (0..32).collect();
+    let sealed = PasswordBox::seal(PASSWORD, &secret, Kdf::PBKDF2, &mut CountingRng(0x20)).unwrap();
+    let reference: serde_json::Value = serde_json::from_str(RUST_BOX).unwrap();
+    assert_eq!(serde_json::to_value(&sealed).unwrap(), reference);
+}
+
+#[test]
+fn sealing_and_opening_boxes() {
+    for kdf in [Kdf::PBKDF2, TEST_KDF] {
+        let sealed = PasswordBox::seal(PASSWORD, b"secret", kdf, &mut OsRng).unwrap();
+        let box_json = serde_json::to_string(&sealed).unwrap();
+        let sealed: PasswordBox = serde_json::from_str(&box_json).unwrap();
+        assert_eq!(sealed.kdf(), kdf);
+        assert_eq!(sealed.open(PASSWORD).unwrap().as_slice(), b"secret");
+    }
+}
+
+#[test]
+fn invalid_boxes() {
+    let err = serde_json::from_str::<PasswordBox>(&HOST_BOX.replace("pbkdf2-sha256", "scrypt"))
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("unknown variant `scrypt`"),
+        "{err}"
+    );
+    let err = serde_json::from_str::<PasswordBox>(&HOST_BOX.replace("aes-128-gcm", "aes-256-gcm"))
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown variant"), "{err}");
+    let err = serde_json::from_str::<PasswordBox>(&HOST_BOX.replace("cd3e", "CD3E")).unwrap_err();
+    assert!(err.to_string().contains("invalid chars"), "{err}");
+
+    let truncated_mac = HOST_BOX.replace("e56b14cd", "");
+    let sealed: PasswordBox = serde_json::from_str(&truncated_mac).unwrap();
+    let err = sealed.open(PASSWORD).unwrap_err();
+    assert_matches!(err, BoxError::InvalidLength { field: "mac", .. });
+
+    let zero_memory = ARGON2_BOX.replace("\"memory\": 1024", "\"memory\": 0");
+    let sealed: PasswordBox = serde_json::from_str(&zero_memory).unwrap();
+    let err = sealed.open(PASSWORD).unwrap_err();
+    assert_matches!(err, BoxError::Kdf(_));
+}
+
+#[test]
+fn boxes_with_excessive_kdf_params() {
+    let excessive_boxes = [
+        ARGON2_BOX.replace("\"memory\": 1024", "\"memory\": 4194304"),
+        ARGON2_BOX.replace("\"iterations\": 2", "\"iterations\": 1000"),
+        ARGON2_BOX.replace("\"parallelism\": 1", "\"parallelism\": 255"),
+        HOST_BOX.replace("\"iterations\": 100000", "\"iterations\": 4000000000"),
+    ];
+    for box_json in &excessive_boxes {
+        let sealed: PasswordBox = serde_json::from_str(box_json).unwrap();
+        let err = sealed.open(PASSWORD).unwrap_err();
+        assert_matches!(err, BoxError::Kdf(message) if message.contains("exceeds"));
+    }
+
+    let excessive_kdf = Kdf::Argon2id {
+        memory: 1 << 20,
+        iterations: 2,
+        parallelism: 1,
+    };
+    let err = PasswordBox::seal(PASSWORD, b"secret", excessive_kdf, &mut OsRng).unwrap_err();
+    assert_matches!(err, BoxError::Kdf(_));
+}
@@ -0,0 +1,90 @@
+//! Tests for human-comparable fingerprints.
+
+use rand::rngs::OsRng;
+
+use std::collections::HashSet;
+
+use elasticpoll_core::{
+    Fingerprint, Keypair, ParticipantApplication, PollId, PollSpec, PollState, PollType, Vote,
+    VoteChoice, FINGERPRINT_LEN, WORDLIST,
+};
+
+fn single_choice_poll() -> PollSpec {
+    PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce: 0,
+        options: vec!["Option #1".to_owned(), "Option #2".to_owned()],
+    }
+}
+
+#[test]
+fn wordlist_is_well_formed() {
+    let words: HashSet<_> = WORDLIST.iter().copied().collect();
+    assert_eq!(words.len(), WORDLIST.len());
+    assert!(WORDLIST.windows(2).all(|pair| pair[0] < pair[1]));
+    for word in WORDLIST {
+        assert!((3..=8).contains(&word.len()), "{word}");
+        assert!(word.bytes().all(|ch| ch.is_ascii_lowercase()), "{word}");
+    }
+}
+
+#[test]
+fn fingerprints_for_poll_ids_and_keys() {
+    let poll_id = PollId::for_spec(&single_choice_poll());
+    let fingerprint = Fingerprint::from(&poll_id);
+    assert_eq!(fingerprint, Fingerprint::from(&poll_id));
+    let fingerprint_str = fingerprint.to_string();
+    let words: Vec<_> = fingerprint_str.split(' ').collect();
+    assert_eq!(words.len(), FINGERPRINT_LEN);
+    assert!(words.iter().all(|word| WORDLIST.contains(word)));
+    assert_eq!(words, fingerprint.words().collect::<Vec<_>>());
+
+    let other_spec = PollSpec {
+        nonce: 1,
+        ..single_choice_poll()
+    };
+    let other_fingerprint = Fingerprint::from(&PollId::for_spec(&other_spec));
+    assert_ne!(other_fingerprint, fingerprint);
+
+    let key = Keypair::generate(&mut OsRng).public().clone();
+    let other_key = Keypair::generate(&mut OsRng).public().clone();
+    assert_eq!(Fingerprint::from(&key), Fingerprint::from(&key));
+    assert_ne!(Fingerprint::from(&key), Fingerprint::from(&other_key));
+}
+
+#[test]
+fn state_digest_does_not_depend_on_participant_order() {
+    let poll_spec = single_choice_poll();
+    let poll_id = PollId::for_spec(&poll_spec);
+    let keys: Vec<_> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+    let applications: Vec<_> = keys
+        .iter()
+        .map(|keys| ParticipantApplication::new(keys, &poll_id))
+        .collect();
+
+    let mut poll = PollState::new(poll_spec.clone());
+    let mut reordered_poll = PollState::new(poll_spec);
+    for application in &applications {
+        poll.insert_participant(application.clone());
+    }
+    for application in applications.iter().rev() {
+        reordered_poll.insert_participant(application.clone());
+    }
+    assert_eq!(poll.digest(), reordered_poll.digest());
+
+    poll.finalize_participants();
+    reordered_poll.finalize_participants();
+    let initial_digest = poll.digest();
+    assert_eq!(initial_digest, reordered_poll.digest());
+
+    let vote = Vote::new(&keys[1], &poll_id, &poll, &VoteChoice::SingleChoice(0));
+    poll.insert_vote(&poll_id, vote).unwrap();
+    assert_ne!(poll.digest(), initial_digest);
+    assert_ne!(poll.digest(), reordered_poll.digest());
+
+    let exported = poll.export();
+    let (_, imported_poll) = PollState::import(exported).unwrap();
+    assert_eq!(imported_poll.digest(), poll.digest());
+}
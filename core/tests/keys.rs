@@ -0,0 +1,35 @@
+//! Tests for deriving poll keys from the master secret.
+
+use secret_tree::{SecretTree, Seed};
+
+use elasticpoll_core::{keys::derive_poll_keys, PollId, PollSpec, PollType};
+
+fn poll_id(nonce: u32) -> PollId {
+    PollId::for_spec(&PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce,
+        options: vec!["Yes".to_owned(), "No".to_owned()],
+    })
+}
+
+#[test]
+fn deriving_poll_keys() {
+    let seed = [5_u8; 32];
+    let secret = SecretTree::from_seed(Seed::from(&seed));
+    let keys = derive_poll_keys(&secret, &poll_id(0));
+
+    // Derivation is deterministic.
+    let restored_secret = SecretTree::from_seed(Seed::from(&seed));
+    let restored_keys = derive_poll_keys(&restored_secret, &poll_id(0));
+    assert_eq!(keys.public(), restored_keys.public());
+
+    // Keys for different polls are independent.
+    let other_keys = derive_poll_keys(&secret, &poll_id(1));
+    assert_ne!(keys.public(), other_keys.public());
+
+    let other_secret = SecretTree::from_seed(Seed::from(&[6_u8; 32]));
+    let other_keys = derive_poll_keys(&other_secret, &poll_id(0));
+    assert_ne!(keys.public(), other_keys.public());
+}
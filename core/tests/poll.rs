@@ -5,15 +5,19 @@ use base64ct::{Base64UrlUnpadded, Encoding};
 use elastic_elgamal::app::ChoiceVerificationError;
 use rand::{rngs::OsRng, Rng};
 use serde::Serialize;
-use wasm_bindgen::UnwrapThrowExt;
-use wasm_bindgen_test::*;
 
-use std::fmt;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use elasticpoll_wasm::poll::{
-    EncryptedVoteChoice, Keypair, ParticipantApplication, PollId, PollSpec, PollStage, PollState,
-    PollType, SubmittedTallierShare, SubmittedVote, TallierShare, TallierShareError, Vote,
-    VoteChoice, VoteError,
+use elasticpoll_core::{
+    Clock, EncryptedVoteChoice, Keypair, ParticipantApplication, PollId, PollSpec, PollStage,
+    PollState, PollType, SubmittedTallierShare, SubmittedVote, TallierShare, TallierShareError,
+    Vote, VoteChoice, VoteError,
 };
 
 fn single_choice_poll() -> PollSpec {
@@ -31,16 +35,16 @@ fn mangle_bytes(
     pointer: &'static str,
     bits_to_mangle: impl Iterator<Item = usize>,
 ) -> impl Iterator<Item = serde_json::Value> {
-    let value_str = json.pointer(pointer).unwrap_throw().as_str().unwrap_throw();
+    let value_str = json.pointer(pointer).unwrap().as_str().unwrap();
     let mut value = [0_u8; 32];
-    Base64UrlUnpadded::decode(value_str, &mut value).unwrap_throw();
+    Base64UrlUnpadded::decode(value_str, &mut value).unwrap();
 
     bits_to_mangle.map(move |bit_idx| {
         let mut json = json.clone();
         let mut mangled_value = value;
         mangled_value[bit_idx / 8] ^= 1 << (bit_idx % 8);
         let mangled_value = Base64UrlUnpadded::encode_string(&mangled_value);
-        *json.pointer_mut(pointer).unwrap_throw() = mangled_value.into();
+        *json.pointer_mut(pointer).unwrap() = mangled_value.into();
         json
     })
 }
@@ -59,18 +63,14 @@ fn mangle_scalar(
     mangle_bytes(json, pointer, 0..252)
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn mangle_group_element_works_as_expected() {
     let test_value = "9GrwVAQ10kkX80-0SSpdPMyJTFpvV4GGCWzCiHutjXQ";
     let json = serde_json::json!({
         "test": test_value,
     });
     for mangled_json in mangle_group_element(json, "/test") {
-        let mangled_value = mangled_json
-            .pointer("/test")
-            .unwrap_throw()
-            .as_str()
-            .unwrap_throw();
+        let mangled_value = mangled_json.pointer("/test").unwrap().as_str().unwrap();
 
         assert_eq!(mangled_value.len(), test_value.len());
         assert_ne!(mangled_value, test_value);
@@ -93,16 +93,10 @@ fn assert_eq_ignoring_timestamps<T: WithTimestamp>(lhs: Option<&T>, rhs: Option<
     match (lhs, rhs) {
         (None, None) => { /* ok */ }
         (Some(lhs), Some(rhs)) => {
-            let mut lhs_value = serde_json::to_value(lhs).unwrap_throw();
-            lhs_value
-                .as_object_mut()
-                .unwrap_throw()
-                .remove("submitted_at");
-            let mut rhs_value = serde_json::to_value(rhs).unwrap_throw();
-            rhs_value
-                .as_object_mut()
-                .unwrap_throw()
-                .remove("submitted_at");
+            let mut lhs_value = serde_json::to_value(lhs).unwrap();
+            lhs_value.as_object_mut().unwrap().remove("submitted_at");
+            let mut rhs_value = serde_json::to_value(rhs).unwrap();
+            rhs_value.as_object_mut().unwrap().remove("submitted_at");
             assert_eq!(lhs_value, rhs_value);
         }
         _ => panic!("{lhs:?} != {rhs:?}"),
@@ -111,7 +105,7 @@ fn assert_eq_ignoring_timestamps<T: WithTimestamp>(lhs: Option<&T>, rhs: Option<
 
 fn assert_poll_export(poll: &PollState) {
     let exported = poll.export();
-    let (_, imported) = PollState::import(exported).unwrap_throw();
+    let (_, imported) = PollState::import(exported).unwrap();
     assert_eq!(imported.stage(), poll.stage());
 
     let it = poll.participants().iter().zip(imported.participants());
@@ -207,27 +201,69 @@ fn test_poll_lifecycle(participant_count: usize) {
     assert_eq!(results, &expected_results);
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn poll_lifecycle_with_single_participant() {
     test_poll_lifecycle(1);
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn poll_lifecycle_with_2_participants() {
     test_poll_lifecycle(2);
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn poll_lifecycle_with_3_participants() {
     test_poll_lifecycle(3);
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn poll_lifecycle_with_5_participants() {
     test_poll_lifecycle(5);
 }
 
-#[wasm_bindgen_test]
+/// Clock advancing by 1 second each time it is queried.
+#[derive(Debug, Default)]
+struct MockClock(AtomicU64);
+
+impl Clock for MockClock {
+    fn now(&self) -> f64 {
+        (self.0.fetch_add(1, Ordering::SeqCst) * 1_000) as f64
+    }
+}
+
+#[test]
+fn poll_with_custom_clock() {
+    let poll_spec = single_choice_poll();
+    let poll_id = PollId::for_spec(&poll_spec);
+    let mut poll = PollState::with_clock(poll_spec, Arc::new(MockClock::default()));
+    assert_eq!(poll.created_at, 0.0);
+
+    let keys = Keypair::generate(&mut OsRng);
+    poll.insert_participant(ParticipantApplication::new(&keys, &poll_id));
+    poll.finalize_participants();
+    let vote = Vote::new(&keys, &poll_id, &poll, &VoteChoice::SingleChoice(1));
+    poll.insert_vote(&poll_id, vote).unwrap();
+    poll.finalize_votes();
+    let share = TallierShare::new(&keys, &poll_id, &poll);
+    poll.insert_tallier_share(&poll_id, share).unwrap();
+
+    let participant = &poll.participants()[0];
+    assert_eq!(participant.created_at, 1_000.0);
+    assert_eq!(participant.vote.as_ref().unwrap().submitted_at, 2_000.0);
+    assert_eq!(
+        participant.tallier_share.as_ref().unwrap().submitted_at,
+        3_000.0
+    );
+    assert_eq!(poll.results().unwrap(), [0, 1]);
+
+    // Timestamps are not exported, so they are re-created on import.
+    let clock = Arc::new(MockClock(AtomicU64::new(10)));
+    let (_, imported) = PollState::import_with_clock(poll.export(), clock).unwrap();
+    assert_eq!(imported.created_at, 10_000.0);
+    assert_eq!(imported.participants()[0].created_at, 11_000.0);
+}
+
+#[test]
 fn invalid_poll_id_in_participant_application() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
@@ -242,14 +278,14 @@ fn invalid_poll_id_in_participant_application() {
     assert!(err.contains("challenge"), "{err}");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn participant_application_with_mangled_public_key() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
     let our_keys = Keypair::generate(&mut OsRng);
 
     let app = ParticipantApplication::new(&our_keys, &poll_id);
-    let app_json = serde_json::to_value(app).unwrap_throw();
+    let app_json = serde_json::to_value(app).unwrap();
     let mut count = 0;
     for mangled_app_json in mangle_group_element(app_json, "/public_key") {
         let mangled_app: ParticipantApplication = match serde_json::from_value(mangled_app_json) {
@@ -262,28 +298,26 @@ fn participant_application_with_mangled_public_key() {
     assert!(count > 20, "Too few valid mangled elements: {count}");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn participant_application_with_mangled_proof() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
     let our_keys = Keypair::generate(&mut OsRng);
     let app = ParticipantApplication::new(&our_keys, &poll_id);
-    let app_json = serde_json::to_value(app).unwrap_throw();
+    let app_json = serde_json::to_value(app).unwrap();
 
     for mangled_app_json in mangle_scalar(app_json.clone(), "/participation_consent/challenge") {
-        let mangled_app: ParticipantApplication =
-            serde_json::from_value(mangled_app_json).unwrap_throw();
+        let mangled_app: ParticipantApplication = serde_json::from_value(mangled_app_json).unwrap();
         mangled_app.validate(&poll_id).unwrap_err();
     }
 
     for mangled_app_json in mangle_scalar(app_json, "/participation_consent/responses/0") {
-        let mangled_app: ParticipantApplication =
-            serde_json::from_value(mangled_app_json).unwrap_throw();
+        let mangled_app: ParticipantApplication = serde_json::from_value(mangled_app_json).unwrap();
         mangled_app.validate(&poll_id).unwrap_err();
     }
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn vote_from_ineligible_voter() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
@@ -304,14 +338,14 @@ fn vote_from_ineligible_voter() {
 
 fn extract_choice_json(vote: Vote) -> serde_json::Value {
     serde_json::to_value(vote)
-        .unwrap_throw()
+        .unwrap()
         .as_object_mut()
-        .unwrap_throw()
+        .unwrap()
         .remove("choice")
-        .unwrap_throw()
+        .unwrap()
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn vote_with_invalid_choice_type() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
@@ -324,9 +358,9 @@ fn vote_with_invalid_choice_type() {
 
     let vote = Vote::new(&our_keys, &poll_id, &poll, &VoteChoice::SingleChoice(1));
     let mut choice_json = extract_choice_json(vote);
-    *choice_json.pointer_mut("/type").unwrap_throw() = String::from("multi_choice").into();
-    *choice_json.pointer_mut("/sum_proof").unwrap_throw() = serde_json::Value::Null;
-    let mangled_choice: EncryptedVoteChoice = serde_json::from_value(choice_json).unwrap_throw();
+    *choice_json.pointer_mut("/type").unwrap() = String::from("multi_choice").into();
+    *choice_json.pointer_mut("/sum_proof").unwrap() = serde_json::Value::Null;
+    let mangled_choice: EncryptedVoteChoice = serde_json::from_value(choice_json).unwrap();
     let mangled_vote = Vote::sign(&our_keys, &poll_id, mangled_choice);
 
     let err = poll.insert_vote(&poll_id, mangled_vote).unwrap_err();
@@ -339,7 +373,7 @@ fn vote_with_invalid_choice_type() {
     );
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn vote_with_invalid_signature() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
@@ -351,15 +385,15 @@ fn vote_with_invalid_signature() {
     poll.finalize_participants();
 
     let vote = Vote::new(&our_keys, &poll_id, &poll, &VoteChoice::SingleChoice(1));
-    let vote_json = serde_json::to_value(vote).unwrap_throw();
+    let vote_json = serde_json::to_value(vote).unwrap();
 
     for mangled_vote_json in mangle_scalar(vote_json.clone(), "/signature/challenge") {
-        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap_throw();
+        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap();
         let err = poll.insert_vote(&poll_id, mangled_vote).unwrap_err();
         assert_matches!(err, VoteError::Signature(_));
     }
     for mangled_vote_json in mangle_scalar(vote_json.clone(), "/signature/responses/0") {
-        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap_throw();
+        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap();
         let err = poll.insert_vote(&poll_id, mangled_vote).unwrap_err();
         assert_matches!(err, VoteError::Signature(_));
     }
@@ -368,20 +402,20 @@ fn vote_with_invalid_signature() {
     let votes_with_mangled_range_proof =
         mangle_scalar(vote_json.clone(), "/choice/range_proof/common_challenge");
     for mangled_vote_json in votes_with_mangled_range_proof {
-        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap_throw();
+        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap();
         let err = poll.insert_vote(&poll_id, mangled_vote).unwrap_err();
         assert_matches!(err, VoteError::Signature(_));
     }
 
     let votes_with_mangled_sum_proof = mangle_scalar(vote_json, "/choice/sum_proof/challenge");
     for mangled_vote_json in votes_with_mangled_sum_proof {
-        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap_throw();
+        let mangled_vote: Vote = serde_json::from_value(mangled_vote_json).unwrap();
         let err = poll.insert_vote(&poll_id, mangled_vote).unwrap_err();
         assert_matches!(err, VoteError::Signature(_));
     }
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn vote_with_invalid_proofs() {
     let poll_spec = single_choice_poll();
     let poll_id = PollId::for_spec(&poll_spec);
@@ -399,7 +433,7 @@ fn vote_with_invalid_proofs() {
         mangle_scalar(choice_json.clone(), "/range_proof/common_challenge");
     for mangled_choice_json in choices_with_mangled_range_proof {
         let mangled_choice: EncryptedVoteChoice =
-            serde_json::from_value(mangled_choice_json).unwrap_throw();
+            serde_json::from_value(mangled_choice_json).unwrap();
         let vote = Vote::sign(&our_keys, &poll_id, mangled_choice);
         let err = poll.insert_vote(&poll_id, vote).unwrap_err();
         assert_matches!(err, VoteError::Choice(ChoiceVerificationError::Range(_)));
@@ -408,7 +442,7 @@ fn vote_with_invalid_proofs() {
     let choices_with_mangled_sum_proof = mangle_scalar(choice_json, "/sum_proof/challenge");
     for mangled_choice_json in choices_with_mangled_sum_proof {
         let mangled_choice: EncryptedVoteChoice =
-            serde_json::from_value(mangled_choice_json).unwrap_throw();
+            serde_json::from_value(mangled_choice_json).unwrap();
         let vote = Vote::sign(&our_keys, &poll_id, mangled_choice);
         let err = poll.insert_vote(&poll_id, vote).unwrap_err();
         assert_matches!(err, VoteError::Choice(ChoiceVerificationError::Sum(_)));
@@ -425,13 +459,13 @@ fn prepare_poll_for_tallying() -> (PollId, PollState, Keypair) {
     poll.insert_participant(app);
     poll.finalize_participants();
     let vote = Vote::new(&our_keys, &poll_id, &poll, &VoteChoice::SingleChoice(1));
-    poll.insert_vote(&poll_id, vote).unwrap_throw();
+    poll.insert_vote(&poll_id, vote).unwrap();
     poll.finalize_votes();
 
     (poll_id, poll, our_keys)
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn tallier_share_from_ineligible_tallier() {
     let (poll_id, mut poll, our_keys) = prepare_poll_for_tallying();
     let other_keys = Keypair::generate(&mut OsRng);
@@ -442,11 +476,11 @@ fn tallier_share_from_ineligible_tallier() {
     assert_matches!(err, TallierShareError::IneligibleTallier);
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn tallier_share_with_invalid_dh_element() {
     let (poll_id, mut poll, our_keys) = prepare_poll_for_tallying();
     let share = TallierShare::new(&our_keys, &poll_id, &poll);
-    let share_json = serde_json::to_value(share).unwrap_throw();
+    let share_json = serde_json::to_value(share).unwrap();
 
     let mut count = 0;
     let mangled_jsons = mangle_group_element(share_json, "/shares/0/share/dh_element");
@@ -465,24 +499,42 @@ fn tallier_share_with_invalid_dh_element() {
     assert!(count > 20, "Too few valid mangled elements: {count}");
 }
 
-#[wasm_bindgen_test]
+#[test]
 fn tallier_share_with_invalid_proof() {
     let (poll_id, mut poll, our_keys) = prepare_poll_for_tallying();
     let share = TallierShare::new(&our_keys, &poll_id, &poll);
-    let share_json = serde_json::to_value(share).unwrap_throw();
+    let share_json = serde_json::to_value(share).unwrap();
 
     for mangled_share_json in mangle_scalar(share_json.clone(), "/shares/1/proof/challenge") {
-        let mangled_share: TallierShare = serde_json::from_value(mangled_share_json).unwrap_throw();
+        let mangled_share: TallierShare = serde_json::from_value(mangled_share_json).unwrap();
         let err = poll
             .insert_tallier_share(&poll_id, mangled_share)
             .unwrap_err();
         assert_matches!(err, TallierShareError::InvalidShare { index: 1, .. });
     }
     for mangled_share_json in mangle_scalar(share_json, "/shares/0/proof/response") {
-        let mangled_share: TallierShare = serde_json::from_value(mangled_share_json).unwrap_throw();
+        let mangled_share: TallierShare = serde_json::from_value(mangled_share_json).unwrap();
         let err = poll
             .insert_tallier_share(&poll_id, mangled_share)
             .unwrap_err();
         assert_matches!(err, TallierShareError::InvalidShare { index: 0, .. });
     }
 }
+
+#[test]
+fn poll_stage_names() {
+    let stages = [
+        PollStage::Participants { participants: 0 },
+        PollStage::Voting {
+            votes: 1,
+            participants: 2,
+        },
+        PollStage::Tallying {
+            shares: 1,
+            participants: 2,
+        },
+        PollStage::Finished,
+    ];
+    let names: Vec<_> = stages.iter().map(PollStage::name).collect();
+    assert_eq!(names, ["participants", "voting", "tallying", "finished"]);
+}
@@ -0,0 +1,195 @@
+//! Tests for JSON Schemas and TypeScript declarations of the exchanged data.
+//!
+//! Schema files in the `schemas` dir are compared with the generated ones; run the tests
+//! with the `UPDATE_SCHEMAS` env var set to overwrite the files.
+
+#![cfg(feature = "schema")]
+
+use jsonschema::JSONSchema;
+use rand::rngs::OsRng;
+use serde::Serialize;
+
+use std::{env, fs, path::Path};
+
+use elasticpoll_core::{
+    schema::{exchange_schemas, typescript_declarations},
+    Keypair, ParticipantApplication, PollId, PollSpec, PollState, PollType, TallierShare, Vote,
+    VoteChoice, MAX_OPTIONS,
+};
+
+const SCHEMAS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/schemas");
+
+fn should_update() -> bool {
+    env::var_os("UPDATE_SCHEMAS").is_some()
+}
+
+fn load_schema(name: &str) -> serde_json::Value {
+    let path = Path::new(SCHEMAS_DIR).join(format!("{name}.json"));
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("cannot read `{}`: {err}", path.display()));
+    serde_json::from_str(&contents).unwrap()
+}
+
+fn validate<T: Serialize>(schema_name: &str, value: &T) {
+    let schema = load_schema(schema_name);
+    let schema = JSONSchema::compile(&schema).unwrap();
+    let value = serde_json::to_value(value).unwrap();
+    let result = schema.validate(&value).map_err(|errors| {
+        errors
+            .map(|err| format!("{err} at {}", err.instance_path))
+            .collect::<Vec<_>>()
+    });
+    if let Err(errors) = result {
+        panic!("{schema_name} does not conform to its schema: {errors:#?}\n{value:#}");
+    }
+}
+
+fn assert_invalid(schema_name: &str, value: &serde_json::Value) {
+    let schema = load_schema(schema_name);
+    let schema = JSONSchema::compile(&schema).unwrap();
+    assert!(!schema.is_valid(value), "{value:#}");
+}
+
+#[test]
+fn json_schemas_are_up_to_date() {
+    let update = should_update();
+    if update {
+        fs::create_dir_all(SCHEMAS_DIR).unwrap();
+    }
+
+    for (name, schema) in exchange_schemas() {
+        let generated = serde_json::to_value(&schema).unwrap();
+        let path = Path::new(SCHEMAS_DIR).join(format!("{name}.json"));
+        if update {
+            let contents = serde_json::to_string_pretty(&generated).unwrap();
+            fs::write(&path, contents + "\n").unwrap();
+        } else {
+            assert_eq!(
+                load_schema(name),
+                generated,
+                "schema `{name}` is outdated; run tests with `UPDATE_SCHEMAS=1`"
+            );
+        }
+    }
+}
+
+#[test]
+fn typescript_declarations_are_up_to_date() {
+    let generated = typescript_declarations();
+    let path = Path::new(SCHEMAS_DIR).join("index.d.ts");
+    if should_update() {
+        fs::write(&path, generated).unwrap();
+    } else {
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents, generated,
+            "TS declarations are outdated; run tests with `UPDATE_SCHEMAS=1`"
+        );
+    }
+}
+
+#[test]
+fn poll_spec_schema_reflects_options_bounds() {
+    let schema = load_schema("poll-spec");
+    let options_schema = &schema["properties"]["options"];
+    assert_eq!(options_schema["minItems"], 1);
+    assert_eq!(options_schema["maxItems"], MAX_OPTIONS);
+
+    let spec = PollSpec {
+        title: "Poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::MultiChoice,
+        nonce: 0,
+        options: vec!["Option".to_owned(); MAX_OPTIONS],
+    };
+    validate("poll-spec", &spec);
+
+    let mut spec_json = serde_json::to_value(&spec).unwrap();
+    spec_json["options"] = serde_json::json!([]);
+    assert_invalid("poll-spec", &spec_json);
+    spec_json["options"] = vec!["Option"; MAX_OPTIONS + 1].into();
+    assert_invalid("poll-spec", &spec_json);
+    spec_json["options"] = serde_json::json!(["Option"]);
+    spec_json["poll_type"] = "MultiChoice".into();
+    assert_invalid("poll-spec", &spec_json);
+}
+
+fn test_exports_conform_to_schemas(poll_type: PollType) {
+    let spec = PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "Poll with schema validation".to_owned(),
+        poll_type,
+        nonce: 1,
+        options: vec!["Yes".to_owned(), "No".to_owned(), "Maybe".to_owned()],
+    };
+    validate("poll-spec", &spec);
+    let poll_id = PollId::for_spec(&spec);
+    let mut poll = PollState::new(spec);
+    let keys: Vec<_> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+
+    for our_keys in &keys {
+        let app = ParticipantApplication::new(our_keys, &poll_id);
+        validate("participant-application", &app);
+        poll.insert_participant(app);
+    }
+    poll.finalize_participants();
+    validate("exported-poll", &poll.export());
+
+    for (i, our_keys) in keys.iter().enumerate() {
+        let choice = match poll_type {
+            PollType::SingleChoice => VoteChoice::SingleChoice(i % 3),
+            PollType::MultiChoice => VoteChoice::MultiChoice(vec![i == 0, true, false]),
+        };
+        let vote = Vote::new(our_keys, &poll_id, &poll, &choice);
+        validate("vote", &vote);
+        poll.insert_vote(&poll_id, vote).unwrap();
+    }
+    poll.finalize_votes();
+    validate("exported-poll", &poll.export());
+
+    for our_keys in &keys {
+        let share = TallierShare::new(our_keys, &poll_id, &poll);
+        validate("tallier-share", &share);
+        poll.insert_tallier_share(&poll_id, share).unwrap();
+    }
+    assert!(poll.results().is_some());
+    validate("exported-poll", &poll.export());
+}
+
+#[test]
+fn single_choice_exports_conform_to_schemas() {
+    test_exports_conform_to_schemas(PollType::SingleChoice);
+}
+
+#[test]
+fn multi_choice_exports_conform_to_schemas() {
+    test_exports_conform_to_schemas(PollType::MultiChoice);
+}
+
+#[test]
+fn mangled_vote_does_not_conform_to_schema() {
+    let spec = PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce: 0,
+        options: vec!["Yes".to_owned(), "No".to_owned()],
+    };
+    let poll_id = PollId::for_spec(&spec);
+    let mut poll = PollState::new(spec);
+    let keys = Keypair::generate(&mut OsRng);
+    poll.insert_participant(ParticipantApplication::new(&keys, &poll_id));
+    poll.finalize_participants();
+    let vote = Vote::new(&keys, &poll_id, &poll, &VoteChoice::SingleChoice(0));
+    let vote_json = serde_json::to_value(vote).unwrap();
+
+    let mut mangled_json = vote_json.clone();
+    mangled_json["choice"]["type"] = "ranked_choice".into();
+    assert_invalid("vote", &mangled_json);
+    let mut mangled_json = vote_json.clone();
+    mangled_json["choice"]["sum_proof"] = serde_json::Value::Null;
+    assert_invalid("vote", &mangled_json);
+    let mut mangled_json = vote_json;
+    mangled_json["public_key"] = "not a key".into();
+    assert_invalid("vote", &mangled_json);
+}
@@ -0,0 +1,188 @@
+//! Reproducible test vectors for participant applications, votes and tallier shares.
+//!
+//! Each vector in `vectors.json` specifies a 32-byte seed for a ChaCha20 RNG, a poll spec
+//! and participants' choices. Outputs are produced by a single RNG instance in the following
+//! order: participant keypairs, applications, votes and tallier shares (in the participant
+//! order for each kind of items). The test suite regenerates the outputs and compares them
+//! with the committed file byte-for-byte; run the tests with the `UPDATE_VECTORS` env var set
+//! to overwrite the file.
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use std::{env, fs};
+
+use elasticpoll_core::{
+    Keypair, ParticipantApplication, PollId, PollSpec, PollState, PollType, PublicKey,
+    TallierShare, Vote, VoteChoice,
+};
+
+const VECTORS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors.json");
+
+/// Choice in the same format as accepted by the JS API: an option index for single-choice polls,
+/// or a flag per option for multi-choice polls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Choice {
+    Single(usize),
+    Multi(Vec<bool>),
+}
+
+impl Choice {
+    fn to_vote_choice(&self) -> VoteChoice {
+        match self {
+            Self::Single(option) => VoteChoice::SingleChoice(*option),
+            Self::Multi(options) => VoteChoice::MultiChoice(options.clone()),
+        }
+    }
+}
+
+/// Inputs of a test vector together with the expected poll results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VectorInputs {
+    name: String,
+    /// Hex-encoded seed for the RNG.
+    seed: String,
+    spec: PollSpec,
+    choices: Vec<Choice>,
+    results: Vec<u64>,
+}
+
+impl VectorInputs {
+    fn rng(&self) -> ChaCha20Rng {
+        assert_eq!(self.seed.len(), 64, "{}: invalid seed length", self.name);
+        let mut seed = [0_u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            let hex_byte = &self.seed[2 * i..2 * i + 2];
+            *byte = u8::from_str_radix(hex_byte, 16).expect("invalid hex seed");
+        }
+        ChaCha20Rng::from_seed(seed)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TestVector {
+    #[serde(flatten)]
+    inputs: VectorInputs,
+    public_keys: Vec<PublicKey>,
+    applications: Vec<ParticipantApplication>,
+    votes: Vec<Vote>,
+    tallier_shares: Vec<TallierShare>,
+}
+
+impl TestVector {
+    fn generate(inputs: VectorInputs) -> Self {
+        let mut rng = inputs.rng();
+        let poll_id = PollId::for_spec(&inputs.spec);
+        let mut poll = PollState::new(inputs.spec.clone());
+        let keys: Vec<_> = inputs
+            .choices
+            .iter()
+            .map(|_| Keypair::generate(&mut rng))
+            .collect();
+
+        let applications: Vec<_> = keys
+            .iter()
+            .map(|keypair| ParticipantApplication::with_rng(keypair, &poll_id, &mut rng))
+            .collect();
+        for application in &applications {
+            application.validate(&poll_id).unwrap();
+            poll.insert_participant(application.clone());
+        }
+        poll.finalize_participants();
+
+        let votes: Vec<_> = keys
+            .iter()
+            .zip(&inputs.choices)
+            .map(|(keypair, choice)| {
+                let choice = choice.to_vote_choice();
+                Vote::with_rng(keypair, &poll_id, &poll, &choice, &mut rng)
+            })
+            .collect();
+        for vote in &votes {
+            poll.insert_vote(&poll_id, vote.clone()).unwrap();
+        }
+        poll.finalize_votes();
+
+        let tallier_shares: Vec<_> = keys
+            .iter()
+            .map(|keypair| TallierShare::with_rng(keypair, &poll_id, &poll, &mut rng))
+            .collect();
+        for share in &tallier_shares {
+            poll.insert_tallier_share(&poll_id, share.clone()).unwrap();
+        }
+        assert_eq!(
+            poll.results(),
+            Some(inputs.results.as_slice()),
+            "{}: unexpected results",
+            inputs.name
+        );
+
+        Self {
+            inputs,
+            public_keys: keys
+                .iter()
+                .map(|keypair| keypair.public().clone())
+                .collect(),
+            applications,
+            votes,
+            tallier_shares,
+        }
+    }
+}
+
+fn read_vectors() -> (String, Vec<VectorInputs>) {
+    let contents = fs::read_to_string(VECTORS_PATH).unwrap();
+    let inputs = serde_json::from_str(&contents).unwrap();
+    (contents, inputs)
+}
+
+fn serialize_vectors(vectors: &[TestVector]) -> String {
+    serde_json::to_string_pretty(vectors).unwrap() + "\n"
+}
+
+#[test]
+fn vectors_are_deterministic() {
+    let (_, inputs) = read_vectors();
+    for vector_inputs in inputs {
+        let vector = TestVector::generate(vector_inputs.clone());
+        let other_vector = TestVector::generate(vector_inputs);
+        let serialized = serde_json::to_string(&vector).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::to_string(&other_vector).unwrap(),
+            "{}",
+            vector.inputs.name
+        );
+    }
+}
+
+#[test]
+fn different_seeds_produce_different_outputs() {
+    let (_, mut inputs) = read_vectors();
+    let mut vector_inputs = inputs.swap_remove(0);
+    let vector = TestVector::generate(vector_inputs.clone());
+    vector_inputs.seed = "00".repeat(32);
+    let other_vector = TestVector::generate(vector_inputs);
+    assert_ne!(vector.public_keys, other_vector.public_keys);
+}
+
+#[test]
+fn vectors_match_committed_file() {
+    let (contents, inputs) = read_vectors();
+    assert!(inputs
+        .iter()
+        .any(|vector| vector.spec.poll_type == PollType::MultiChoice));
+    let vectors: Vec<_> = inputs.into_iter().map(TestVector::generate).collect();
+    let serialized = serialize_vectors(&vectors);
+
+    if env::var_os("UPDATE_VECTORS").is_some() {
+        fs::write(VECTORS_PATH, serialized).unwrap();
+    } else {
+        assert!(
+            contents == serialized,
+            "test vectors are outdated; run tests with `UPDATE_VECTORS=1`"
+        );
+    }
+}
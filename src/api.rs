@@ -0,0 +1,380 @@
+//! Headless JS API for the poll core, allowing to create polls and participate in them
+//! without the UI.
+//!
+//! All functions take and return plain JSON objects. Poll specs, participant applications,
+//! votes, tallier shares and exported polls have the same format as exported from the app,
+//! so the API interoperates with the app and the native tools. Errors are thrown as JS `Error`s.
+//!
+//! Operations requiring participant keys take a 32-byte secret (the same as the master secret
+//! of the app) and derive keys for the poll from it, exactly as the app does.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use elasticpoll_core::keys::derive_poll_keys;
+use js_sys::JSON;
+use rand_core::{OsRng, RngCore};
+use secret_tree::{SecretTree, Seed};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, UnwrapThrowExt};
+
+use std::{collections::HashSet, error::Error as StdError, fmt};
+
+use crate::{
+    pages::NewPoll,
+    poll::{
+        ExportedPoll, Keypair, Participant, ParticipantApplication, PollId, PollSpec, PollStage,
+        PollState, PollType, TallierShare, Vote, VoteChoice, MAX_OPTIONS,
+    },
+};
+
+/// TS declarations for the exchanged data (poll specs, votes, etc.), generated from
+/// the `elasticpoll-core` types.
+#[wasm_bindgen(typescript_custom_section)]
+const EXCHANGE_TS_TYPES: &str = include_str!("../core/schemas/index.d.ts");
+
+/// TS declarations for the API-specific types.
+#[wasm_bindgen(typescript_custom_section)]
+const API_TS_TYPES: &str = r#"
+/** Parameters of a new poll passed to `createPoll()`. */
+export interface PollParams {
+  title: string;
+  description?: string;
+  /** Defaults to `"single_choice"`. */
+  poll_type?: PollType;
+  options: string[];
+}
+
+/** Summary of a successfully imported poll. */
+export interface PollSummary {
+  id: string;
+  stage: "participants" | "voting" | "tallying" | "finished";
+  /** Public keys of participants. */
+  participants: string[];
+  /** Public keys of participants that have voted. */
+  voters: string[];
+  /** Public keys of participants that have submitted tallier shares. */
+  talliers: string[];
+  /** Number of votes for each option; only present for finished polls. */
+  results?: number[];
+}
+
+/** Outcome of poll verification. */
+export interface VerificationReport {
+  valid: boolean;
+  /** Present if the poll is invalid. */
+  error?: string;
+  /** Present if the poll is valid. */
+  poll?: PollSummary;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PollParams")]
+    pub type JsPollParams;
+    #[wasm_bindgen(typescript_type = "PollSpec")]
+    pub type JsPollSpec;
+    #[wasm_bindgen(typescript_type = "ParticipantApplication")]
+    pub type JsParticipantApplication;
+    #[wasm_bindgen(typescript_type = "Vote")]
+    pub type JsVote;
+    #[wasm_bindgen(typescript_type = "TallierShare")]
+    pub type JsTallierShare;
+    #[wasm_bindgen(typescript_type = "number[]")]
+    pub type JsVoteChoice;
+    #[wasm_bindgen(typescript_type = "ExportedPoll")]
+    pub type JsExportedPoll;
+    #[wasm_bindgen(typescript_type = "PollSummary")]
+    pub type JsPollSummary;
+    #[wasm_bindgen(typescript_type = "VerificationReport")]
+    pub type JsVerificationReport;
+}
+
+/// Error thrown by the API functions.
+#[derive(Debug)]
+struct ApiError(String);
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl StdError for ApiError {}
+
+impl From<&str> for ApiError {
+    fn from(message: &str) -> Self {
+        Self(message.to_owned())
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(message)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PollParams {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "PollParams::default_poll_type")]
+    poll_type: PollType,
+    options: Vec<String>,
+}
+
+impl PollParams {
+    fn default_poll_type() -> PollType {
+        PollType::SingleChoice
+    }
+
+    /// Performs the same validation as the poll creation page of the app.
+    fn validate(&self) -> Result<(), ApiError> {
+        const MAX_FIELD_LEN: usize = NewPoll::MAX_FIELD_LEN;
+        const MAX_DESCRIPTION_LEN: usize = NewPoll::MAX_DESCRIPTION_LEN;
+
+        if self.title.is_empty() || self.title.len() > MAX_FIELD_LEN {
+            return Err(
+                format!("title must be non-empty and have at most {MAX_FIELD_LEN} bytes").into(),
+            );
+        }
+        if self.description.len() > MAX_DESCRIPTION_LEN {
+            return Err(
+                format!("description must have at most {MAX_DESCRIPTION_LEN} bytes").into(),
+            );
+        }
+        if self.options.is_empty() || self.options.len() > MAX_OPTIONS {
+            return Err(format!("poll must have 1..={MAX_OPTIONS} options").into());
+        }
+        if let Some(option) = self
+            .options
+            .iter()
+            .find(|option| option.is_empty() || option.len() > MAX_FIELD_LEN)
+        {
+            return Err(format!(
+                "option {option:?} must be non-empty and have at most {MAX_FIELD_LEN} bytes"
+            )
+            .into());
+        }
+        let unique_options: HashSet<_> = self.options.iter().collect();
+        if unique_options.len() != self.options.len() {
+            return Err("poll options must be unique".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PollSummary {
+    id: PollId,
+    /// Stage name as returned by [`PollStage::name()`].
+    stage: &'static str,
+    participants: Vec<String>,
+    voters: Vec<String>,
+    talliers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<Vec<u64>>,
+}
+
+impl PollSummary {
+    fn new(poll_id: PollId, poll: &PollState) -> Self {
+        let participants = poll.participants();
+        let encode_key = |participant: &Participant| {
+            Base64UrlUnpadded::encode_string(participant.public_key().as_bytes())
+        };
+
+        Self {
+            id: poll_id,
+            stage: poll.stage().name(),
+            participants: participants.iter().map(encode_key).collect(),
+            voters: participants
+                .iter()
+                .filter(|p| p.vote.is_some())
+                .map(encode_key)
+                .collect(),
+            talliers: participants
+                .iter()
+                .filter(|p| p.tallier_share.is_some())
+                .map(encode_key)
+                .collect(),
+            results: poll.results().map(<[u64]>::to_vec),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct VerificationReport {
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poll: Option<PollSummary>,
+}
+
+/// Converts a JS value to a Rust type via JSON, so that the format is exactly the same
+/// as for exported data.
+fn from_js<T: DeserializeOwned>(value: &JsValue, name: &str) -> Result<T, ApiError> {
+    let json = JSON::stringify(value)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or_else(|| format!("{name} is not JSON-serializable"))?;
+    serde_json::from_str(&json).map_err(|err| format!("invalid {name}: {err}").into())
+}
+
+fn to_js<T: Serialize, R: JsCast>(value: &T) -> R {
+    let json = serde_json::to_string(value).expect_throw("cannot serialize value");
+    JSON::parse(&json)
+        .expect_throw("cannot parse serialized value")
+        .unchecked_into()
+}
+
+fn poll_keys(secret: &[u8], poll_id: &PollId) -> Result<Keypair, ApiError> {
+    let seed: &[u8; 32] = secret.try_into().map_err(|_| "secret must have 32 bytes")?;
+    let secret = SecretTree::from_seed(Seed::from(seed));
+    Ok(derive_poll_keys(&secret, poll_id))
+}
+
+fn import_poll_state(poll: &JsExportedPoll) -> Result<(PollId, PollState), ApiError> {
+    let exported: ExportedPoll = from_js(poll, "poll")?;
+    PollState::import(exported).map_err(|err| err.to_string().into())
+}
+
+fn ensure_participant(keys: &Keypair, poll: &PollState) -> Result<(), ApiError> {
+    if poll.has_participant(keys.public()) {
+        Ok(())
+    } else {
+        Err("secret does not correspond to a poll participant".into())
+    }
+}
+
+fn vote_choice(spec: &PollSpec, choice: &[usize]) -> Result<VoteChoice, ApiError> {
+    let options_count = spec.options.len();
+    if let Some(&idx) = choice.iter().find(|&&idx| idx >= options_count) {
+        return Err(
+            format!("invalid option index {idx}; expected a value in 0..{options_count}").into(),
+        );
+    }
+
+    match spec.poll_type {
+        PollType::SingleChoice => {
+            let [idx] = choice else {
+                return Err("exactly one option must be selected in a single-choice poll".into());
+            };
+            Ok(VoteChoice::SingleChoice(*idx))
+        }
+        PollType::MultiChoice => {
+            let mut selected = vec![false; options_count];
+            for &idx in choice {
+                selected[idx] = true;
+            }
+            Ok(VoteChoice::MultiChoice(selected))
+        }
+    }
+}
+
+/// Creates a new poll with the specified parameters and a random nonce. Returns the poll
+/// in the export format (i.e., only with the spec).
+#[wasm_bindgen(js_name = createPoll)]
+pub fn create_poll(params: &JsPollParams) -> Result<JsExportedPoll, JsError> {
+    let params: PollParams = from_js(params, "poll params")?;
+    params.validate()?;
+    let spec = PollSpec {
+        title: params.title,
+        description: params.description,
+        poll_type: params.poll_type,
+        nonce: OsRng.next_u32(),
+        options: params.options,
+    };
+    Ok(to_js(&PollState::new(spec).export()))
+}
+
+/// Returns the ID of the poll with the specified spec.
+#[wasm_bindgen(js_name = pollId)]
+pub fn poll_id(spec: &JsPollSpec) -> Result<String, JsError> {
+    let spec: PollSpec = from_js(spec, "poll spec")?;
+    Ok(PollId::for_spec(&spec).to_string())
+}
+
+/// Creates an application to participate in the poll with the specified spec.
+#[wasm_bindgen(js_name = makeApplication)]
+pub fn make_application(
+    spec: &JsPollSpec,
+    secret: &[u8],
+) -> Result<JsParticipantApplication, JsError> {
+    let spec: PollSpec = from_js(spec, "poll spec")?;
+    let poll_id = PollId::for_spec(&spec);
+    let keys = poll_keys(secret, &poll_id)?;
+    Ok(to_js(&ParticipantApplication::new(&keys, &poll_id)))
+}
+
+/// Casts a vote in the specified poll. `choice` contains 0-based indices of selected options;
+/// a single-choice poll requires exactly one index.
+///
+/// If the poll is at the participants stage, the current set of participants is considered
+/// final.
+#[wasm_bindgen(js_name = castVote)]
+pub fn cast_vote(
+    poll: &JsExportedPoll,
+    secret: &[u8],
+    choice: &JsVoteChoice,
+) -> Result<JsVote, JsError> {
+    let (poll_id, mut poll) = import_poll_state(poll)?;
+    match poll.stage() {
+        PollStage::Participants { .. } => poll.finalize_participants(),
+        PollStage::Voting { .. } => { /* ok */ }
+        PollStage::Tallying { .. } | PollStage::Finished => {
+            return Err(JsError::new("poll is not at the voting stage"));
+        }
+    }
+    let choice: Vec<usize> = from_js(choice, "choice")?;
+    let choice = vote_choice(poll.spec(), &choice)?;
+    let keys = poll_keys(secret, &poll_id)?;
+    ensure_participant(&keys, &poll)?;
+    Ok(to_js(&Vote::new(&keys, &poll_id, &poll, &choice)))
+}
+
+/// Creates a tallier share for the specified poll.
+///
+/// If the poll is at the voting stage, the current set of votes is considered final.
+#[wasm_bindgen(js_name = makeTallierShare)]
+pub fn make_tallier_share(poll: &JsExportedPoll, secret: &[u8]) -> Result<JsTallierShare, JsError> {
+    let (poll_id, mut poll) = import_poll_state(poll)?;
+    match poll.stage() {
+        PollStage::Participants { .. } => {
+            return Err(JsError::new("poll does not contain votes"));
+        }
+        PollStage::Voting { .. } => poll.finalize_votes(),
+        PollStage::Tallying { .. } => { /* ok */ }
+        PollStage::Finished => return Err(JsError::new("poll is already finished")),
+    }
+    let keys = poll_keys(secret, &poll_id)?;
+    ensure_participant(&keys, &poll)?;
+    Ok(to_js(&TallierShare::new(&keys, &poll_id, &poll)))
+}
+
+/// Imports the poll, validating all its contents. Returns the poll summary including
+/// results for finished polls.
+#[wasm_bindgen(js_name = importPoll)]
+pub fn import_poll(poll: &JsExportedPoll) -> Result<JsPollSummary, JsError> {
+    let (poll_id, poll) = import_poll_state(poll)?;
+    Ok(to_js(&PollSummary::new(poll_id, &poll)))
+}
+
+/// Verifies the poll. Unlike [`import_poll()`], does not throw if the poll is invalid,
+/// but rather returns a report with the error.
+#[wasm_bindgen(js_name = verifyPoll)]
+pub fn verify_poll(poll: &JsExportedPoll) -> JsVerificationReport {
+    let report = match import_poll_state(poll) {
+        Ok((poll_id, poll)) => VerificationReport {
+            valid: true,
+            error: None,
+            poll: Some(PollSummary::new(poll_id, &poll)),
+        },
+        Err(err) => VerificationReport {
+            valid: false,
+            error: Some(err.to_string()),
+            poll: None,
+        },
+    };
+    to_js(&report)
+}
@@ -0,0 +1,153 @@
+//! Automatic locking of the secret manager.
+
+use js_sys::Date;
+use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use web_sys::{Document, Event, Window};
+use yew::{html, Callback, Component, Context, Html, Properties};
+
+use std::rc::Rc;
+
+use crate::{
+    js::EventListener,
+    poll::{SecretManager, SecretManagerStatus},
+};
+
+/// Interval between checks of the inactivity period.
+const CHECK_INTERVAL_MS: i32 = 10_000;
+
+/// Interval timer that is cleared on drop.
+#[derive(Debug)]
+struct Interval {
+    window: Window,
+    handle: i32,
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl Interval {
+    fn new(window: Window, period_ms: i32, callback: Callback<()>) -> Self {
+        let closure = Closure::<dyn FnMut()>::new(move || callback.emit(()));
+        let handle = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                period_ms,
+            )
+            .expect_throw("cannot set interval");
+        Self {
+            window,
+            handle,
+            _closure: closure,
+        }
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        self.window.clear_interval_with_handle(self.handle);
+    }
+}
+
+#[derive(Debug, Clone, Properties)]
+pub struct AutoLockProperties {
+    /// Secrets manager.
+    pub secrets: Rc<SecretManager>,
+}
+
+impl PartialEq for AutoLockProperties {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.secrets, &other.secrets)
+    }
+}
+
+#[derive(Debug)]
+pub enum AutoLockMessage {
+    Activity,
+    Check,
+    VisibilityChanged,
+}
+
+/// Invisible component locking the secret manager according to its [`LockPolicy`].
+///
+/// [`LockPolicy`]: crate::poll::LockPolicy
+#[derive(Debug)]
+pub struct AutoLock {
+    document: Document,
+    /// Unix timestamp (in milliseconds) of the last user activity.
+    last_activity: f64,
+    _listeners: Vec<EventListener>,
+    _interval: Interval,
+}
+
+impl AutoLock {
+    /// Locks the secret manager if it has been idle for longer than the policy timeout.
+    fn check_idle(&self, secrets: &SecretManager) {
+        let is_unlocked = secrets.status() == Some(SecretManagerStatus::Unlocked);
+        if let Some(timeout) = secrets.lock_policy().idle_timeout_ms() {
+            if is_unlocked && Date::now() - self.last_activity >= timeout {
+                secrets.lock();
+            }
+        }
+    }
+}
+
+impl Component for AutoLock {
+    type Message = AutoLockMessage;
+    type Properties = AutoLockProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let window = web_sys::window().expect_throw("no Window");
+        let document = window.document().expect_throw("no Document");
+        let link = ctx.link();
+
+        let activity_events = ["pointerdown", "keydown", "wheel", "touchstart"];
+        let mut listeners: Vec<_> = activity_events
+            .into_iter()
+            .map(|event_type| {
+                let callback = link.callback(|_: Event| AutoLockMessage::Activity);
+                EventListener::new(document.clone().into(), event_type, callback)
+            })
+            .collect();
+        listeners.push(EventListener::new(
+            document.clone().into(),
+            "visibilitychange",
+            link.callback(|_: Event| AutoLockMessage::VisibilityChanged),
+        ));
+
+        Self {
+            document,
+            last_activity: Date::now(),
+            _listeners: listeners,
+            _interval: Interval::new(
+                window,
+                CHECK_INTERVAL_MS,
+                link.callback(|()| AutoLockMessage::Check),
+            ),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let secrets = &ctx.props().secrets;
+        match msg {
+            AutoLockMessage::Activity => {
+                self.last_activity = Date::now();
+            }
+            AutoLockMessage::Check => self.check_idle(secrets),
+            AutoLockMessage::VisibilityChanged => {
+                if self.document.hidden() {
+                    if secrets.lock_policy().lock_on_hide {
+                        secrets.lock();
+                    }
+                } else {
+                    // Timers may be suspended while the page is hidden, so the idle period
+                    // must be checked before returning to the page counts as activity.
+                    self.check_idle(secrets);
+                    self.last_activity = Date::now();
+                }
+            }
+        }
+        false
+    }
+
+    fn view(&self, _: &Context<Self>) -> Html {
+        html! {}
+    }
+}
@@ -0,0 +1,330 @@
+//! Synchronization of a poll with a bulletin board.
+
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, SubmitEvent};
+use yew::{
+    classes, context::ContextHandle, html, Callback, Component, Context, Html, NodeRef, Properties,
+};
+
+use crate::{
+    layout::{view_err, Icon},
+    pages::AppProperties,
+    poll::{
+        BoardClient, BoardError, BoardItem, ItemReport, ItemStatus, PollId, PollManager, PollState,
+    },
+    utils::Encode,
+};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct BoardSyncProperties {
+    pub poll_id: PollId,
+    /// Called after the poll is updated, i.e., after the board URL is changed or items
+    /// pulled from the board are added to the poll. The poll should be reloaded
+    /// from the storage.
+    pub onupdate: Callback<()>,
+}
+
+#[derive(Debug)]
+pub enum BoardSyncMessage {
+    UrlSubmitted,
+    UrlReset,
+    PushRequested,
+    Pushed(Result<ItemReport, BoardError>),
+    PullRequested,
+    Pulled(Result<Vec<BoardItem>, BoardError>),
+    SecretUpdated,
+}
+
+/// Component allowing to exchange poll items via a bulletin board.
+#[derive(Debug)]
+pub struct BoardSync {
+    poll_manager: PollManager,
+    url_input_ref: NodeRef,
+    url_err: Option<String>,
+    in_progress: bool,
+    sync_err: Option<String>,
+    reports: Vec<ItemReport>,
+    _app_props_handle: ContextHandle<AppProperties>,
+}
+
+impl BoardSync {
+    const COLLAPSE_ID: &'static str = "board-sync";
+
+    fn poll_state(&self, ctx: &Context<Self>) -> PollState {
+        self.poll_manager
+            .poll(&ctx.props().poll_id)
+            .expect_throw("poll is not found")
+    }
+
+    fn set_url(&mut self, ctx: &Context<Self>, url: Option<String>) {
+        let mut state = self.poll_state(ctx);
+        state.set_board_url(url);
+        self.poll_manager.update_poll(&ctx.props().poll_id, &state);
+        self.reports.clear();
+        self.sync_err = None;
+        ctx.props().onupdate.emit(());
+    }
+
+    fn submit_url(&mut self, ctx: &Context<Self>) {
+        let input = self
+            .url_input_ref
+            .cast::<HtmlInputElement>()
+            .expect_throw("failed downcasting URL input");
+        let url = input.value().trim().to_owned();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            self.url_err = Some("URL must start with https:// or http://".to_owned());
+            return;
+        }
+        self.url_err = None;
+        self.set_url(ctx, Some(url));
+    }
+
+    fn push(&mut self, ctx: &Context<Self>) {
+        let state = self.poll_state(ctx);
+        let Some(url) = state.board_url() else {
+            return;
+        };
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let Some(our_key) = secrets.public_key_for_poll(&ctx.props().poll_id) else {
+            return;
+        };
+        let item = match BoardItem::ours(&state, &our_key) {
+            Ok(item) => item,
+            Err(err) => {
+                self.sync_err = Some(format!("Nothing to submit: {err}"));
+                return;
+            }
+        };
+
+        let client = BoardClient::http(url);
+        let spec = state.spec().clone();
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            let result = client.push(&spec, item).await;
+            link.send_message(BoardSyncMessage::Pushed(result));
+        });
+        self.in_progress = true;
+        self.sync_err = None;
+    }
+
+    fn pull(&mut self, ctx: &Context<Self>) {
+        let state = self.poll_state(ctx);
+        let Some(url) = state.board_url() else {
+            return;
+        };
+        let client = BoardClient::http(url);
+        let poll_id = ctx.props().poll_id;
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            let result = client.pull(&poll_id).await;
+            link.send_message(BoardSyncMessage::Pulled(result));
+        });
+        self.in_progress = true;
+        self.sync_err = None;
+    }
+
+    fn apply_items(&mut self, ctx: &Context<Self>, items: Vec<BoardItem>) {
+        let poll_id = &ctx.props().poll_id;
+        let mut state = self.poll_state(ctx);
+        let was_finished = state.results().is_some();
+        self.reports = BoardClient::apply_items(poll_id, &mut state, items.clone());
+        let added_items: Vec<_> = items
+            .into_iter()
+            .zip(&self.reports)
+            .filter_map(|(item, report)| (report.status == ItemStatus::Added).then_some(item))
+            .collect();
+        if added_items.is_empty() {
+            return;
+        }
+
+        self.poll_manager.update_poll(poll_id, &state);
+        let props = AppProperties::from_ctx(ctx);
+        for item in added_items {
+            props.notify_item_inserted(poll_id, item);
+        }
+        if !was_finished && state.results().is_some() {
+            props.notify_stage_changed(poll_id, &state);
+        }
+        ctx.props().onupdate.emit(());
+    }
+
+    fn view_url_form(&self, ctx: &Context<Self>) -> Html {
+        let mut control_classes = classes!["form-control"];
+        if self.url_err.is_some() {
+            control_classes.push("is-invalid");
+        }
+        let link = ctx.link();
+
+        html! {
+            <form onsubmit={link.callback(|evt: SubmitEvent| {
+                evt.prevent_default();
+                BoardSyncMessage::UrlSubmitted
+            })}>
+                <p class="small">
+                    { "Participants can exchange applications, votes and tallier shares \
+                       via a bulletin board server instead of copying them manually. \
+                       Items pulled from the board are verified in the same way \
+                       as manually added ones." }
+                </p>
+                <div class="input-group has-validation">
+                    <input
+                        ref={self.url_input_ref.clone()}
+                        type="url"
+                        class={control_classes}
+                        placeholder="https://board.example.com"
+                        aria-label="Bulletin board URL" />
+                    <button type="submit" class="btn btn-primary">{ "Save" }</button>
+                    { if let Some(err) = &self.url_err {
+                        view_err(err)
+                    } else {
+                        html!{}
+                    }}
+                </div>
+            </form>
+        }
+    }
+
+    fn view_sync(&self, url: &str, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let can_push = secrets.public_key_for_poll(&ctx.props().poll_id).is_some();
+
+        html! {
+            <>
+                <p class="small text-truncate mb-2">
+                    <strong>{ "Board: " }</strong>
+                    <span class="font-monospace">{ url }</span>
+                    { " " }
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-link p-0 align-baseline"
+                        disabled={self.in_progress}
+                        onclick={link.callback(|_| BoardSyncMessage::UrlReset)}>
+                        { "Change" }
+                    </button>
+                </p>
+                <div class="mb-2">
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-outline-primary me-2"
+                        title={ if can_push { "" } else { "Unlock the secret to submit our item" } }
+                        disabled={self.in_progress || !can_push}
+                        onclick={link.callback(|_| BoardSyncMessage::PushRequested)}>
+                        { Icon::Upload.view() }{ " Submit ours" }
+                    </button>
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-outline-primary"
+                        disabled={self.in_progress}
+                        onclick={link.callback(|_| BoardSyncMessage::PullRequested)}>
+                        { Icon::Download.view() }{ " Pull items" }
+                    </button>
+                </div>
+                { if let Some(err) = &self.sync_err {
+                    html! { <p class="small text-danger mb-2">{ err }</p> }
+                } else {
+                    html! {}
+                }}
+                { self.view_reports() }
+            </>
+        }
+    }
+
+    fn view_reports(&self) -> Html {
+        if self.reports.is_empty() {
+            return html! {};
+        }
+        let reports: Html = self.reports.iter().map(Self::view_report).collect();
+        html! { <ul class="list-group list-group-flush small">{ reports }</ul> }
+    }
+
+    fn view_report(report: &ItemReport) -> Html {
+        let (badge_class, status) = match &report.status {
+            ItemStatus::Submitted => ("text-bg-success", "submitted".to_owned()),
+            ItemStatus::Added => ("text-bg-success", "added".to_owned()),
+            ItemStatus::AlreadyPresent => ("text-bg-secondary", "already present".to_owned()),
+            ItemStatus::Superseded => ("text-bg-secondary", "superseded".to_owned()),
+            ItemStatus::Skipped(reason) => ("text-bg-warning", format!("skipped: {reason}")),
+            ItemStatus::Rejected(err) => ("text-bg-danger", format!("rejected: {err}")),
+        };
+        html! {
+            <li class="list-group-item px-0 d-flex justify-content-between gap-2">
+                <span class="text-truncate">
+                    { report.kind.as_human_string() }{ " from " }
+                    <span class="font-monospace">{ report.public_key.encode() }</span>
+                </span>
+                <span class={classes!("badge", badge_class, "text-wrap")}>{ status }</span>
+            </li>
+        }
+    }
+}
+
+impl Component for BoardSync {
+    type Message = BoardSyncMessage;
+    type Properties = BoardSyncProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            poll_manager: PollManager::default(),
+            url_input_ref: NodeRef::default(),
+            url_err: None,
+            in_progress: false,
+            sync_err: None,
+            reports: vec![],
+            _app_props_handle: AppProperties::subscribe(ctx, || BoardSyncMessage::SecretUpdated),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            BoardSyncMessage::UrlSubmitted => self.submit_url(ctx),
+            BoardSyncMessage::UrlReset => self.set_url(ctx, None),
+            BoardSyncMessage::PushRequested => self.push(ctx),
+            BoardSyncMessage::PullRequested => self.pull(ctx),
+
+            BoardSyncMessage::Pushed(result) => {
+                self.in_progress = false;
+                match result {
+                    Ok(report) => self.reports = vec![report],
+                    Err(err) => self.sync_err = Some(err.to_string()),
+                }
+            }
+            BoardSyncMessage::Pulled(result) => {
+                self.in_progress = false;
+                match result {
+                    Ok(items) => self.apply_items(ctx, items),
+                    Err(err) => self.sync_err = Some(err.to_string()),
+                }
+            }
+            BoardSyncMessage::SecretUpdated => { /* just re-render */ }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let state = self.poll_state(ctx);
+        html! {
+            <div class="mb-3">
+                <button
+                    type="button"
+                    class="btn btn-sm btn-outline-secondary"
+                    data-bs-toggle="collapse"
+                    data-bs-target={format!("#{}", Self::COLLAPSE_ID)}
+                    aria-expanded="false"
+                    aria-controls={Self::COLLAPSE_ID}>
+                    { Icon::Link.view() }{ " Bulletin board…" }
+                </button>
+                <div class="collapse mt-2" id={Self::COLLAPSE_ID}>
+                    <div class="card card-body">
+                        { if let Some(url) = state.board_url() {
+                            self.view_sync(url, ctx)
+                        } else {
+                            self.view_url_form(ctx)
+                        }}
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
@@ -1,6 +1,14 @@
 //! Non-page components.
 
+mod auto_lock;
+mod board;
+mod poll_key;
+mod profiles;
+mod qr;
 mod rollback;
 mod secrets;
 
-pub use self::{rollback::Rollback, secrets::Secrets};
+pub use self::{
+    auto_lock::AutoLock, board::BoardSync, poll_key::PollKeyTransfer, profiles::ProfileSwitcher,
+    qr::QrCode, rollback::Rollback, secrets::Secrets,
+};
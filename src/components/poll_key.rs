@@ -0,0 +1,310 @@
+//! Transfer of keys for a single poll to another device.
+
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlTextAreaElement, SubmitEvent};
+use yew::{
+    classes, context::ContextHandle, html, Callback, Component, Context, Html, NodeRef, Properties,
+};
+use zeroize::Zeroizing;
+
+use super::Secrets;
+use crate::{
+    js::{yield_to_event_loop, ExportFormat, ExportedData, ExportedDataType},
+    layout::{view_err, Icon},
+    pages::AppProperties,
+    poll::{ExportedPollKey, PollId, SecretManagerStatus},
+};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct PollKeyTransferProperties {
+    pub poll_id: PollId,
+    /// Called after keys for the poll are imported.
+    pub onimport: Callback<()>,
+}
+
+#[derive(Debug)]
+pub enum PollKeyTransferMessage {
+    ExportRequested,
+    Exported(Option<(String, Zeroizing<String>)>),
+    Copied,
+    ImportSubmitted,
+    Imported(Result<(), String>),
+    SecretUpdated,
+}
+
+/// Component allowing to export keys for a poll sealed with a one-time passphrase,
+/// and to import keys exported on another device.
+#[derive(Debug)]
+pub struct PollKeyTransfer {
+    /// Exported keys (as JSON) together with the passphrase.
+    exported: Option<(String, Zeroizing<String>)>,
+    copy_button_ref: NodeRef,
+    key_input_ref: NodeRef,
+    passphrase_input_ref: NodeRef,
+    import_err: Option<String>,
+    imported: bool,
+    /// Set while the key is being sealed or opened. Key derivation for the one-time
+    /// passphrase is CPU-heavy and runs on the main thread, so the busy state
+    /// is rendered before it starts.
+    in_progress: bool,
+    _app_props_handle: ContextHandle<AppProperties>,
+}
+
+impl PollKeyTransfer {
+    const COLLAPSE_ID: &'static str = "poll-key-transfer";
+
+    fn view_export(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let Some((exported, passphrase)) = &self.exported else {
+            return html! {
+                <button
+                    type="button"
+                    class="btn btn-sm btn-outline-primary mb-3"
+                    disabled={self.in_progress}
+                    onclick={link.callback(|_| PollKeyTransferMessage::ExportRequested)}>
+                    { Icon::Export.view() }
+                    { if self.in_progress { " Sealing key…" } else { " Export key" } }
+                </button>
+            };
+        };
+
+        html! {
+            <>
+                <textarea
+                    id="exported-poll-key"
+                    class="form-control font-monospace small mb-2"
+                    rows="4"
+                    readonly=true
+                    value={exported.clone()} />
+                <div class="mb-2 text-end">
+                    <button
+                        ref={self.copy_button_ref.clone()}
+                        type="button"
+                        class="btn btn-sm btn-secondary"
+                        onclick={link.callback(|_| PollKeyTransferMessage::Copied)}>
+                        { Icon::Export.view() }{ " Copy" }
+                    </button>
+                </div>
+                <div class="alert alert-info py-2" role="alert">
+                    { "One-time passphrase: " }
+                    <strong class="font-monospace">{ passphrase.as_str() }</strong>
+                    <br/>
+                    { "Enter it on the other device together with the exported key. \
+                       The passphrase is not stored and will not be shown again." }
+                </div>
+            </>
+        }
+    }
+
+    fn view_import(&self, ctx: &Context<Self>) -> Html {
+        let mut control_classes = classes!["form-control"];
+        if self.import_err.is_some() {
+            control_classes.push("is-invalid");
+        }
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let link = ctx.link();
+
+        html! {
+            <form onsubmit={link.callback(|evt: SubmitEvent| {
+                evt.prevent_default();
+                PollKeyTransferMessage::ImportSubmitted
+            })}>
+                { if secrets.has_imported_key(&ctx.props().poll_id) {
+                    html! {
+                        <p class="small text-muted">
+                            { "This poll uses a key imported from another device." }
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }}
+                <textarea
+                    ref={self.key_input_ref.clone()}
+                    id="imported-poll-key"
+                    class="form-control font-monospace small mb-2"
+                    rows="4"
+                    placeholder="Exported key" />
+                <div class="input-group has-validation mb-2">
+                    <input
+                        ref={self.passphrase_input_ref.clone()}
+                        type="password"
+                        class={control_classes}
+                        placeholder="One-time passphrase"
+                        aria-label="One-time passphrase"
+                        disabled={self.in_progress} />
+                    <button type="submit" class="btn btn-primary" disabled={self.in_progress}>
+                        { if self.in_progress { "Importing…" } else { "Import" } }
+                    </button>
+                    { if let Some(err) = &self.import_err {
+                        view_err(err)
+                    } else {
+                        html!{}
+                    }}
+                </div>
+                { if self.imported {
+                    html! {
+                        <p class="small text-success mb-0">
+                            { "The key was imported and will be used for this poll." }
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }}
+            </form>
+        }
+    }
+
+    fn key_input(&self) -> HtmlTextAreaElement {
+        self.key_input_ref
+            .cast::<HtmlTextAreaElement>()
+            .expect_throw("failed downcasting key input")
+    }
+
+    fn export(&mut self, ctx: &Context<Self>) {
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let poll_id = ctx.props().poll_id;
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            yield_to_event_loop().await;
+            let exported = secrets
+                .export_poll_key(&poll_id)
+                .map(|(exported, passphrase)| {
+                    let exported = serde_json::to_string_pretty(&exported)
+                        .expect_throw("cannot serialize exported key");
+                    (exported, passphrase)
+                });
+            link.send_message(PollKeyTransferMessage::Exported(exported));
+        });
+        self.in_progress = true;
+    }
+
+    fn import(&mut self, ctx: &Context<Self>) {
+        let passphrase = Secrets::take_password(&self.passphrase_input_ref);
+        let key_value = self.key_input().value();
+        let exported = match serde_json::from_str::<ExportedPollKey>(key_value.trim()) {
+            Ok(exported) => exported,
+            Err(err) => {
+                self.import_err = Some(format!("Error parsing key: {err}"));
+                return;
+            }
+        };
+
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let poll_id = ctx.props().poll_id;
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            yield_to_event_loop().await;
+            let result = secrets
+                .import_poll_key(&poll_id, &exported, &passphrase)
+                .map_err(|err| String::from(err.message()));
+            link.send_message(PollKeyTransferMessage::Imported(result));
+        });
+        self.in_progress = true;
+    }
+}
+
+impl Component for PollKeyTransfer {
+    type Message = PollKeyTransferMessage;
+    type Properties = PollKeyTransferProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            exported: None,
+            copy_button_ref: NodeRef::default(),
+            key_input_ref: NodeRef::default(),
+            passphrase_input_ref: NodeRef::default(),
+            import_err: None,
+            imported: false,
+            in_progress: false,
+            _app_props_handle: AppProperties::subscribe(ctx, || {
+                PollKeyTransferMessage::SecretUpdated
+            }),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let global_props = AppProperties::from_ctx(ctx);
+        match msg {
+            PollKeyTransferMessage::ExportRequested => {
+                if self.in_progress {
+                    return false;
+                }
+                self.export(ctx);
+            }
+            PollKeyTransferMessage::Exported(exported) => {
+                self.in_progress = false;
+                self.exported = exported;
+            }
+            PollKeyTransferMessage::Copied => {
+                if let Some((exported, _)) = &self.exported {
+                    let data = ExportedData {
+                        ty: ExportedDataType::PollKey,
+                        data: exported.clone(),
+                    };
+                    global_props.export(data, ExportFormat::Json, &self.copy_button_ref);
+                }
+                return false;
+            }
+            PollKeyTransferMessage::ImportSubmitted => {
+                if self.in_progress {
+                    return false;
+                }
+                self.import(ctx);
+            }
+            PollKeyTransferMessage::Imported(result) => {
+                self.in_progress = false;
+                if let Err(err) = result {
+                    self.import_err = Some(err);
+                } else {
+                    self.key_input().set_value("");
+                    self.import_err = None;
+                    self.imported = true;
+                    ctx.props().onimport.emit(());
+                }
+            }
+            PollKeyTransferMessage::SecretUpdated => {
+                // Do not keep the passphrase around after the secret is locked.
+                if global_props.secrets.status() != Some(SecretManagerStatus::Unlocked) {
+                    self.exported = None;
+                }
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        if secrets.status() != Some(SecretManagerStatus::Unlocked) {
+            return html! {};
+        }
+
+        html! {
+            <div class="mb-3">
+                <button
+                    type="button"
+                    class="btn btn-sm btn-outline-secondary"
+                    data-bs-toggle="collapse"
+                    data-bs-target={format!("#{}", Self::COLLAPSE_ID)}
+                    aria-expanded="false"
+                    aria-controls={Self::COLLAPSE_ID}>
+                    { "Transfer key for this poll…" }
+                </button>
+                <div class="collapse mt-2" id={Self::COLLAPSE_ID}>
+                    <div class="card card-body">
+                        <h6>{ "Export" }</h6>
+                        <p class="small">
+                            { "Exports your key for this poll sealed with a one-time passphrase. \
+                               This allows continuing the poll on another device \
+                               (e.g., tallying from a phone) without exporting the entire secret. \
+                               Import the poll on the other device first." }
+                        </p>
+                        { self.view_export(ctx) }
+                        <h6>{ "Import" }</h6>
+                        { self.view_import(ctx) }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
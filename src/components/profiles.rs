@@ -0,0 +1,171 @@
+//! Profile switcher.
+
+use web_sys::{Event, HtmlInputElement, SubmitEvent};
+use yew::{classes, html, Callback, Component, Context, Html, NodeRef, Properties};
+
+use std::rc::Rc;
+
+use crate::{layout::view_err, poll::SecretManager, utils::value_from_select_event};
+
+#[derive(Debug, Clone, Properties)]
+pub struct ProfileSwitcherProperties {
+    /// Secrets manager.
+    pub secrets: Rc<SecretManager>,
+    /// Called after the active profile is switched.
+    pub onswitch: Callback<()>,
+}
+
+impl PartialEq for ProfileSwitcherProperties {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.secrets, &other.secrets) && self.onswitch == other.onswitch
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileSwitcherMessage {
+    Selected(String),
+    Added,
+    Cancelled,
+}
+
+impl ProfileSwitcherMessage {
+    fn selected(event: &Event) -> Self {
+        Self::Selected(value_from_select_event(event))
+    }
+}
+
+/// Component allowing to choose the active profile or to create a new one.
+#[derive(Debug)]
+pub struct ProfileSwitcher {
+    is_adding: bool,
+    name_input: NodeRef,
+    error_message: Option<String>,
+}
+
+impl ProfileSwitcher {
+    fn view_select(ctx: &Context<Self>) -> Html {
+        let secrets = &ctx.props().secrets;
+        let active_profile = secrets.active_profile();
+        let options: Html = secrets
+            .profiles()
+            .into_iter()
+            .map(|profile| {
+                let is_selected = profile == active_profile;
+                html! {
+                    <option value={profile.clone()} selected={is_selected}>{ profile }</option>
+                }
+            })
+            .collect();
+
+        let link = ctx.link();
+        html! {
+            <select
+                class="form-select form-select-sm"
+                title="Active profile"
+                aria-label="Active profile"
+                onchange={link.callback(|evt| ProfileSwitcherMessage::selected(&evt))}>
+                { options }
+                // Profile names cannot be empty, so an empty value is unambiguous.
+                <option value="">{ "New profile…" }</option>
+            </select>
+        }
+    }
+
+    fn view_new_profile_form(&self, ctx: &Context<Self>) -> Html {
+        let mut control_classes = classes!["form-control"];
+        if self.error_message.is_some() {
+            control_classes.push("is-invalid");
+        }
+
+        let link = ctx.link();
+        html! {
+            <form
+                class="input-group input-group-sm has-validation"
+                onsubmit={link.callback(|evt: SubmitEvent| {
+                    evt.prevent_default();
+                    ProfileSwitcherMessage::Added
+                })}>
+                <input
+                    ref={self.name_input.clone()}
+                    type="text"
+                    class={control_classes}
+                    placeholder="Profile name"
+                    aria-label="New profile name" />
+                <button type="submit" class="btn btn-primary">{ "Add" }</button>
+                <button
+                    type="button"
+                    class="btn btn-outline-secondary"
+                    onclick={link.callback(|_| ProfileSwitcherMessage::Cancelled)}>
+                    { "Cancel" }
+                </button>
+                { if let Some(err) = &self.error_message {
+                    view_err(err)
+                } else {
+                    html!{}
+                }}
+            </form>
+        }
+    }
+
+    fn switch(ctx: &Context<Self>, name: &str) {
+        let props = ctx.props();
+        if props.secrets.active_profile() != name {
+            props.secrets.switch_profile(name);
+            props.onswitch.emit(());
+        }
+    }
+}
+
+impl Component for ProfileSwitcher {
+    type Message = ProfileSwitcherMessage;
+    type Properties = ProfileSwitcherProperties;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            is_adding: false,
+            name_input: NodeRef::default(),
+            error_message: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ProfileSwitcherMessage::Selected(name) => {
+                if name.is_empty() {
+                    self.is_adding = true;
+                } else {
+                    Self::switch(ctx, &name);
+                }
+            }
+            ProfileSwitcherMessage::Added => {
+                let input = self.name_input.cast::<HtmlInputElement>();
+                let name = input.map(|input| input.value()).unwrap_or_default();
+                let name = name.trim();
+                if let Err(err) = SecretManager::check_profile_name(name) {
+                    self.error_message = Some(err);
+                } else {
+                    Self::switch(ctx, name);
+                    self.is_adding = false;
+                    self.error_message = None;
+                }
+            }
+            ProfileSwitcherMessage::Cancelled => {
+                self.is_adding = false;
+                self.error_message = None;
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="profile-switcher">
+                { if self.is_adding {
+                    self.view_new_profile_form(ctx)
+                } else {
+                    Self::view_select(ctx)
+                }}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! QR code export modal.
+
+use yew::{html, Component, Context, Html, Properties};
+
+use crate::{
+    js::{ExportedData, ExportedDataType},
+    qr::{split_into_chunks, QrImage},
+};
+
+/// Number of blank modules around the QR code.
+const QUIET_ZONE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct QrCodeProperties {
+    /// Data to display. The data is expected to be in the compact encoding.
+    pub data: Option<ExportedData>,
+}
+
+#[derive(Debug)]
+pub struct QrCode {
+    images: Result<Vec<QrImage>, String>,
+}
+
+impl QrCode {
+    pub const MODAL_ID: &'static str = "qr-code-modal";
+
+    fn render_images(data: Option<&ExportedData>) -> Result<Vec<QrImage>, String> {
+        let Some(data) = data else {
+            return Ok(vec![]);
+        };
+        let chunks = split_into_chunks(&data.data).map_err(|err| {
+            format!("Cannot export as QR code: {err}. Use another export method.")
+        })?;
+        chunks
+            .iter()
+            .map(|chunk| QrImage::new(chunk))
+            .collect::<Result<_, _>>()
+            .map_err(|err| format!("Error rendering QR code: {err}"))
+    }
+
+    fn data_description(ty: ExportedDataType) -> &'static str {
+        match ty {
+            ExportedDataType::PollSpec => "poll parameters",
+            ExportedDataType::PollState => "poll",
+            ExportedDataType::Application => "participant application",
+            ExportedDataType::Vote => "vote",
+            ExportedDataType::TallierShare => "tallier share",
+            ExportedDataType::InvitationLink => "invitation link",
+            ExportedDataType::ResultsLink => "results link",
+            ExportedDataType::SecretBackup => "secret backup",
+            ExportedDataType::SecretShare => "secret share",
+            ExportedDataType::PollKey => "poll key",
+        }
+    }
+
+    pub(super) fn view_image(image: &QrImage, idx: usize, count: usize) -> Html {
+        let full_width = image.width + 2 * QUIET_ZONE;
+        let view_box = format!("-{QUIET_ZONE} -{QUIET_ZONE} {full_width} {full_width}");
+        html! {
+            <figure class="figure d-block text-center">
+                <svg
+                    xmlns="http://www.w3.org/2000/svg"
+                    class="figure-img img-fluid w-100"
+                    viewBox={view_box}
+                    shape-rendering="crispEdges">
+                    <rect
+                        x={format!("-{QUIET_ZONE}")}
+                        y={format!("-{QUIET_ZONE}")}
+                        width={full_width.to_string()}
+                        height={full_width.to_string()}
+                        fill="#fff" />
+                    <path d={image.path.clone()} fill="#000" />
+                </svg>
+                { if count > 1 {
+                    html! {
+                        <figcaption class="figure-caption">
+                            { format!("Code {} of {count}", idx + 1) }
+                        </figcaption>
+                    }
+                } else {
+                    html!{}
+                }}
+            </figure>
+        }
+    }
+
+    fn view_body(&self, ctx: &Context<Self>) -> Html {
+        let images = match &self.images {
+            Ok(images) => images,
+            Err(err) => {
+                return html! { <div class="alert alert-danger" role="alert">{ err }</div> };
+            }
+        };
+        let Some(data) = &ctx.props().data else {
+            return html! {};
+        };
+
+        let description = Self::data_description(data.ty);
+        let hint = if images.len() > 1 {
+            format!(
+                "The {description} is split into {} QR codes. Scan all of them and paste \
+                 the scanned text into the import field (in any order, separated by whitespace).",
+                images.len()
+            )
+        } else {
+            format!("Scan the code and paste the scanned text into the {description} import field.")
+        };
+        html! {
+            <>
+                <p class="small text-muted">{ hint }</p>
+                { for images
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, image)| Self::view_image(image, idx, images.len())) }
+            </>
+        }
+    }
+}
+
+impl Component for QrCode {
+    type Message = ();
+    type Properties = QrCodeProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            images: Self::render_images(ctx.props().data.as_ref()),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.images = Self::render_images(ctx.props().data.as_ref());
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div id={Self::MODAL_ID}
+                class="modal"
+                tabindex="-1"
+                aria-labelledby="qr-code-modal-label"
+                aria-hidden="true">
+
+                <div class="modal-dialog modal-dialog-scrollable">
+                    <div class="modal-content">
+                        <div class="modal-header">
+                            <h5 id="qr-code-modal-label" class="modal-title">
+                                { "Export as QR code" }
+                            </h5>
+                            <button
+                                type="button"
+                                class="btn-close"
+                                data-bs-dismiss="modal"
+                                aria-label="Close">
+                            </button>
+                        </div>
+                        <div class="modal-body">{ self.view_body(ctx) }</div>
+                        <div class="modal-footer">
+                            <button type="button" class="btn btn-secondary" data-bs-dismiss="modal">
+                                { "Close" }
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
@@ -1,23 +1,93 @@
 //! Secrets dialog.
 
-use js_sys::Error;
+use js_sys::{encode_uri_component, Error};
 use wasm_bindgen::UnwrapThrowExt;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{HtmlInputElement, SubmitEvent};
-use yew::{classes, html, Callback, Component, Context, Html, NodeRef, Properties};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, SubmitEvent};
+use yew::{
+    classes, context::ContextHandle, html, Callback, Component, Context, Html, NodeRef, Properties,
+};
+use zeroize::Zeroizing;
 
+use super::QrCode;
 use crate::{
-    layout::view_err,
+    js::{ExportFormat, ExportedData, ExportedDataType},
+    layout::{view_err, FingerprintView, Icon},
     pages::AppProperties,
-    poll::{SecretManager, SecretManagerStatus},
+    poll::{
+        Fingerprint, LockPolicy, PollId, PollManager, PollState, SecretManager,
+        SecretManagerStatus, SecretShare, MAX_SHARES,
+    },
+    qr::QrImage,
 };
 
+/// Tab in the secrets dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretsTab {
+    /// Unlocking or creating the secret.
+    Password,
+    /// Exporting the password-encrypted secret.
+    Backup,
+    /// Restoring the secret from a backup.
+    Restore,
+    /// Locking the secret and configuring automatic locking.
+    Lock,
+}
+
+impl SecretsTab {
+    fn caption(self) -> &'static str {
+        match self {
+            Self::Password => "Password",
+            Self::Backup => "Back up",
+            Self::Restore => "Restore",
+            Self::Lock => "Lock",
+        }
+    }
+}
+
+/// Source of the secret restoration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreSource {
+    /// Password-encrypted backup.
+    Backup,
+    /// Mnemonic recovery phrase.
+    Phrase,
+    /// Shamir secret shares.
+    Shares,
+}
+
+impl RestoreSource {
+    fn caption(self) -> &'static str {
+        match self {
+            Self::Backup => "Encrypted backup",
+            Self::Phrase => "Recovery phrase",
+            Self::Shares => "Recovery shares",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum SecretsMessage {
+    TabSelected(SecretsTab),
     Created,
     Unlocked,
     ErrorUnlocking(Error),
     Submitted { new_secret: bool },
+    PasswordChangeSubmitted,
+    PasswordChanged,
+    ErrorChangingPassword(Error),
+    BackupExported,
+    PhraseToggled,
+    SharesRequested,
+    ShareExported(usize),
+    ShareQrToggled(usize),
+    RestoreSourceSelected(RestoreSource),
+    RestoreSubmitted { confirmed: bool },
+    Restored,
+    ErrorRestoring(Error),
+    LockPolicySubmitted,
+    LockRequested,
+    Locked,
 }
 
 #[derive(Debug, Clone, PartialEq, Properties)]
@@ -28,18 +98,77 @@ pub struct SecretsProperties {
 
 #[derive(Debug)]
 pub struct Secrets {
+    tab: SecretsTab,
     input_ref: NodeRef,
     in_progress: bool,
     new_secret: bool,
     err: Option<String>,
+    current_password_ref: NodeRef,
+    new_password_ref: NodeRef,
+    confirmed_password_ref: NodeRef,
+    password_change_err: Option<String>,
+    backup_button_ref: NodeRef,
+    show_phrase: bool,
+    share_threshold_ref: NodeRef,
+    share_count_ref: NodeRef,
+    shares: Vec<(SecretShare, NodeRef)>,
+    shown_share_qr: Option<usize>,
+    shares_err: Option<String>,
+    restore_source: RestoreSource,
+    restore_password_ref: NodeRef,
+    restore_box_ref: NodeRef,
+    /// Titles of polls that may be orphaned by the restoration; present if the restoration
+    /// awaits confirmation.
+    restore_confirmation: Option<Vec<String>>,
+    restore_err: Option<String>,
+    idle_timeout_ref: NodeRef,
+    lock_on_hide_ref: NodeRef,
+    lock_policy_saved: bool,
+    _app_props_handle: ContextHandle<AppProperties>,
 }
 
 impl Secrets {
-    fn password(&self) -> String {
-        self.input_ref
+    pub const MODAL_ID: &'static str = "unlock-secrets-modal";
+
+    /// Takes the password from the input, clearing the input so that the password
+    /// does not linger in the page.
+    pub(super) fn take_password(node_ref: &NodeRef) -> Zeroizing<String> {
+        let input = node_ref
             .cast::<HtmlInputElement>()
-            .expect_throw("failed downcasting password input")
-            .value()
+            .expect_throw("failed downcasting password input");
+        let password = Zeroizing::new(input.value());
+        input.set_value("");
+        password
+    }
+
+    /// Clears inputs that may contain secrets (passwords, recovery phrases and shares).
+    fn clear_secret_inputs(&self) {
+        let password_refs = [
+            &self.input_ref,
+            &self.current_password_ref,
+            &self.new_password_ref,
+            &self.confirmed_password_ref,
+            &self.restore_password_ref,
+        ];
+        for node_ref in password_refs {
+            if let Some(input) = node_ref.cast::<HtmlInputElement>() {
+                input.set_value("");
+            }
+        }
+        if let Some(input) = self.restore_box_ref.cast::<HtmlTextAreaElement>() {
+            input.set_value("");
+        }
+    }
+
+    /// Returns the password and the backup / recovery phrase.
+    fn restore_inputs(&self) -> (Zeroizing<String>, Zeroizing<String>) {
+        let password = Self::take_password(&self.restore_password_ref);
+        let input = self
+            .restore_box_ref
+            .cast::<HtmlTextAreaElement>()
+            .expect_throw("failed downcasting backup input")
+            .value();
+        (password, Zeroizing::new(input))
     }
 
     pub fn view_alert(secrets: &SecretManager, item: &str) -> Html {
@@ -71,6 +200,86 @@ impl Secrets {
         }
     }
 
+    /// Warns if the poll was joined using a profile other than the active one.
+    pub fn view_profile_alert(secrets: &SecretManager, state: &PollState) -> Html {
+        let Some(participation) = state.participation() else {
+            return html! {};
+        };
+        let active_profile = secrets.active_profile();
+        if participation.profile == active_profile {
+            return html! {};
+        }
+
+        html! {
+            <div class="alert alert-warning py-2" role="alert">
+                { "You have joined this poll using the " }
+                <strong>{ &participation.profile }</strong>
+                { " profile, but the active profile is " }
+                <strong>{ active_profile }</strong>
+                { ". Switch the profile in the page header to act as this poll participant." }
+            </div>
+        }
+    }
+
+    /// Shows a warning if our key for the poll differs from the key used to join it.
+    pub fn view_key_mismatch_alert(
+        secrets: &SecretManager,
+        poll_id: &PollId,
+        state: &PollState,
+    ) -> Html {
+        if !secrets.has_key_mismatch(poll_id, state) {
+            return html! {};
+        }
+        let joined_key = state
+            .participation()
+            .and_then(|participation| participation.public_key.as_ref())
+            .expect_throw("no key in participation");
+
+        html! {
+            <div class="alert alert-danger py-2" role="alert">
+                { "You have joined this poll with a key that does not correspond to the current \
+                   secret; the secret has probably been recreated or replaced since then. \
+                   Restore the old secret (from a backup, recovery phrase or shares) \
+                   to act as this poll participant. The fingerprint of the key is " }
+                { Fingerprint::from(joined_key).view() }
+                { "." }
+            </div>
+        }
+    }
+
+    fn view_tabs(&self, ctx: &Context<Self>) -> Html {
+        let has_secret = AppProperties::from_ctx(ctx).secrets.status().is_some();
+        let tabs = [
+            SecretsTab::Password,
+            SecretsTab::Backup,
+            SecretsTab::Restore,
+            SecretsTab::Lock,
+        ];
+        let tabs = tabs
+            .into_iter()
+            .filter(|&tab| has_secret || !matches!(tab, SecretsTab::Backup | SecretsTab::Lock))
+            .map(|tab| {
+                let mut classes = classes!["nav-link"];
+                if tab == self.tab {
+                    classes.push("active");
+                }
+                html! {
+                    <li class="nav-item">
+                        <button
+                            type="button"
+                            class={classes}
+                            disabled={self.in_progress}
+                            onclick={ctx.link().callback(move |_| SecretsMessage::TabSelected(tab))}>
+                            { tab.caption() }
+                        </button>
+                    </li>
+                }
+            });
+        html! {
+            <ul class="nav nav-tabs px-3 pt-2">{ for tabs }</ul>
+        }
+    }
+
     fn view_form(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
         let new_secret = self.new_secret;
@@ -115,6 +324,610 @@ impl Secrets {
             </form>
         }
     }
+
+    fn view_password_change_form(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let password_input = |id: &'static str, node_ref: &NodeRef, label: &'static str| {
+            html! {
+                <div class="mb-2">
+                    <label for={id} class="form-label">{ label }</label>
+                    <input
+                        ref={node_ref.clone()}
+                        type="password"
+                        id={id}
+                        class="form-control"
+                        disabled={self.in_progress} />
+                </div>
+            }
+        };
+
+        html! {
+            <form onsubmit={link.callback(|evt: SubmitEvent| {
+                evt.prevent_default();
+                SecretsMessage::PasswordChangeSubmitted
+            })}>
+                <div class="modal-body">
+                    <p class="small text-muted">{ "The secret will be re-encrypted with the new \
+                        password; keys for all polls will remain the same. Previously exported \
+                        backups can still be opened with the old password." }</p>
+                    { password_input(
+                        "current-password-input",
+                        &self.current_password_ref,
+                        "Current password",
+                    ) }
+                    { password_input("new-password-input", &self.new_password_ref, "New password") }
+                    { password_input(
+                        "confirmed-password-input",
+                        &self.confirmed_password_ref,
+                        "Repeat new password",
+                    ) }
+                    { if let Some(err) = &self.password_change_err {
+                        html! {
+                            <div class="alert alert-danger mt-3 mb-0" role="alert">{ err }</div>
+                        }
+                    } else {
+                        html!{}
+                    }}
+                </div>
+                <div class="modal-footer">
+                    <button
+                        type="submit"
+                        class="btn btn-primary"
+                        disabled={self.in_progress}>
+                        { "Change password" }
+                    </button>
+                </div>
+            </form>
+        }
+    }
+
+    fn change_password(&mut self, ctx: &Context<Self>) -> bool {
+        let current_password = Self::take_password(&self.current_password_ref);
+        let new_password = Self::take_password(&self.new_password_ref);
+        if new_password != Self::take_password(&self.confirmed_password_ref) {
+            self.password_change_err = Some("New passwords do not match".to_owned());
+            return true;
+        }
+        if new_password.is_empty() {
+            self.password_change_err = Some("New password cannot be empty".to_owned());
+            return true;
+        }
+
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let task = secrets.change_password(&current_password, &new_password);
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            match task.await {
+                Ok(()) => link.send_message(SecretsMessage::PasswordChanged),
+                Err(err) => link.send_message(SecretsMessage::ErrorChangingPassword(err)),
+            }
+        });
+        self.password_change_err = None;
+        self.in_progress = true;
+        true
+    }
+
+    fn view_backup(&self, ctx: &Context<Self>) -> Html {
+        let Some(backup) = AppProperties::from_ctx(ctx).secrets.backup() else {
+            return html! {
+                <div class="modal-body">
+                    <p class="mb-0">{ "There is no secret to back up." }</p>
+                </div>
+            };
+        };
+        let download_url = format!(
+            "data:application/json;charset=utf-8,{}",
+            String::from(encode_uri_component(&backup))
+        );
+
+        html! {
+            <div class="modal-body">
+                <h6>{ "Encrypted backup" }</h6>
+                <p>{ "The backup contains the secret encrypted with your password. \
+                    Keys for all polls are derived from this secret, so it can be used \
+                    to continue polls on another device or after clearing browser data. \
+                    You will need the password to restore the secret." }</p>
+                <textarea
+                    id="secret-backup"
+                    class="form-control font-monospace small mb-2"
+                    rows="6"
+                    readonly=true
+                    value={backup} />
+                <div class="mb-3 text-end">
+                    <a
+                        class="btn btn-sm btn-secondary"
+                        href={download_url}
+                        download="elastic-poll-secret.json">
+                        { Icon::Download.view() }{ " Download" }
+                    </a>
+                    <button
+                        ref={self.backup_button_ref.clone()}
+                        type="button"
+                        class="btn btn-sm btn-secondary ms-2"
+                        onclick={ctx.link().callback(|_| SecretsMessage::BackupExported)}>
+                        { Icon::Export.view() }{ " Copy" }
+                    </button>
+                </div>
+                { self.view_recovery_phrase(ctx) }
+                { self.view_shares(ctx) }
+            </div>
+        }
+    }
+
+    fn view_recovery_phrase(&self, ctx: &Context<Self>) -> Html {
+        let phrase = AppProperties::from_ctx(ctx).secrets.mnemonic();
+        let Some(phrase) = phrase else {
+            return html! {
+                <>
+                    <h6>{ "Recovery phrase" }</h6>
+                    <p class="mb-0 text-muted">
+                        { "Unlock the secret to view its recovery phrase." }
+                    </p>
+                </>
+            };
+        };
+
+        let link = ctx.link();
+        let words = if self.show_phrase {
+            let words = phrase.split(' ').enumerate().map(|(i, word)| {
+                html! { <div class="col">{ format!("{}. {word}", i + 1) }</div> }
+            });
+            html! {
+                <div class="row row-cols-3 g-1 mb-2 font-monospace small">{ for words }</div>
+            }
+        } else {
+            html! {}
+        };
+        html! {
+            <>
+                <h6>{ "Recovery phrase" }</h6>
+                <p>{ "The recovery phrase encodes the secret itself; it does not depend \
+                    on the password. Write it down and keep it in a safe place. \
+                    Anyone knowing the phrase can vote on your behalf." }</p>
+                { words }
+                <button
+                    type="button"
+                    class="btn btn-sm btn-outline-danger"
+                    onclick={link.callback(|_| SecretsMessage::PhraseToggled)}>
+                    { if self.show_phrase { "Hide phrase" } else { "Show phrase" } }
+                </button>
+            </>
+        }
+    }
+
+    fn view_shares(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        if secrets.status() != Some(SecretManagerStatus::Unlocked) {
+            return html! {
+                <>
+                    <h6 class="mt-3">{ "Social recovery" }</h6>
+                    <p class="mb-0 text-muted">
+                        { "Unlock the secret to split it into recovery shares." }
+                    </p>
+                </>
+            };
+        }
+
+        let number_input = |id: &'static str, node_ref: &NodeRef, label, value: u8| {
+            html! {
+                <div class="col-6">
+                    <label for={id} class="form-label small">{ label }</label>
+                    <input
+                        ref={node_ref.clone()}
+                        type="number"
+                        id={id}
+                        class="form-control form-control-sm"
+                        min="2"
+                        max={MAX_SHARES.to_string()}
+                        value={value.to_string()} />
+                </div>
+            }
+        };
+        html! {
+            <>
+                <h6 class="mt-3">{ "Social recovery" }</h6>
+                <p>{ "The secret can be split into shares distributed among trusted people. \
+                    Any sufficient number of shares (the threshold) recovers the secret; \
+                    fewer shares reveal nothing about it. Each split produces a new, \
+                    incompatible set of shares." }</p>
+                <div class="row g-2 mb-2">
+                    { number_input(
+                        "share-threshold-input",
+                        &self.share_threshold_ref,
+                        "Threshold",
+                        2,
+                    ) }
+                    { number_input("share-count-input", &self.share_count_ref, "Shares", 3) }
+                </div>
+                { if let Some(err) = &self.shares_err {
+                    html! { <div class="alert alert-danger py-2" role="alert">{ err }</div> }
+                } else {
+                    html!{}
+                }}
+                <button
+                    type="button"
+                    class="btn btn-sm btn-outline-danger mb-2"
+                    onclick={link.callback(|_| SecretsMessage::SharesRequested)}>
+                    { "Split secret" }
+                </button>
+                { for self.shares.iter().enumerate().map(|(idx, (share, button_ref))| {
+                    self.view_share(idx, share, button_ref, ctx)
+                }) }
+            </>
+        }
+    }
+
+    fn view_share(
+        &self,
+        idx: usize,
+        share: &SecretShare,
+        button_ref: &NodeRef,
+        ctx: &Context<Self>,
+    ) -> Html {
+        let link = ctx.link();
+        let share_string = share.to_string();
+        let qr = if self.shown_share_qr == Some(idx) {
+            match QrImage::new(&share_string) {
+                Ok(image) => QrCode::view_image(&image, 0, 1),
+                Err(err) => html! {
+                    <div class="alert alert-danger" role="alert">
+                        { format!("Error rendering QR code: {err}") }
+                    </div>
+                },
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <div class="card mb-2">
+                <div class="card-body p-2">
+                    <p class="card-text small mb-1">
+                        <strong>
+                            { format!("Share #{} of {}", share.index(), self.shares.len()) }
+                        </strong>
+                        { format!(" (threshold {})", share.threshold()) }
+                    </p>
+                    <p class="card-text font-monospace small text-break mb-1">
+                        { &share_string }
+                    </p>
+                    { qr }
+                    <button
+                        ref={button_ref.clone()}
+                        type="button"
+                        class="btn btn-sm btn-secondary me-2"
+                        onclick={link.callback(move |_| SecretsMessage::ShareExported(idx))}>
+                        { Icon::Export.view() }{ " Copy" }
+                    </button>
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-secondary"
+                        onclick={link.callback(move |_| SecretsMessage::ShareQrToggled(idx))}>
+                        { Icon::Qr.view() }{ " QR" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    fn split_secret(&mut self, ctx: &Context<Self>) {
+        let parse_input = |node_ref: &NodeRef| {
+            node_ref
+                .cast::<HtmlInputElement>()
+                .expect_throw("failed downcasting number input")
+                .value()
+                .parse::<u8>()
+                .ok()
+        };
+        let threshold = parse_input(&self.share_threshold_ref);
+        let count = parse_input(&self.share_count_ref);
+        let (threshold, count) = match (threshold, count) {
+            (Some(threshold), Some(count))
+                if (2..=count).contains(&threshold) && count <= MAX_SHARES =>
+            {
+                (threshold, count)
+            }
+            _ => {
+                self.shares_err = Some(format!(
+                    "Threshold must be at least 2 and cannot exceed the number of shares, \
+                     which must not exceed {MAX_SHARES}"
+                ));
+                return;
+            }
+        };
+
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let shares = secrets.split_secret(threshold, count).unwrap_or_default();
+        self.shares = shares
+            .into_iter()
+            .map(|share| (share, NodeRef::default()))
+            .collect();
+        self.shown_share_qr = None;
+        self.shares_err = None;
+    }
+
+    fn view_restore_sources(&self, ctx: &Context<Self>) -> Html {
+        let sources = [
+            RestoreSource::Backup,
+            RestoreSource::Phrase,
+            RestoreSource::Shares,
+        ];
+        let sources = sources.into_iter().map(|source| {
+            let id = match source {
+                RestoreSource::Backup => "restore-source-backup",
+                RestoreSource::Phrase => "restore-source-phrase",
+                RestoreSource::Shares => "restore-source-shares",
+            };
+            html! {
+                <>
+                    <input
+                        type="radio"
+                        class="btn-check"
+                        name="restore-source"
+                        id={id}
+                        autocomplete="off"
+                        checked={self.restore_source == source}
+                        disabled={self.in_progress}
+                        onchange={ctx.link().callback(move |_| {
+                            SecretsMessage::RestoreSourceSelected(source)
+                        })} />
+                    <label class="btn btn-sm btn-outline-secondary" for={id}>
+                        { source.caption() }
+                    </label>
+                </>
+            }
+        });
+        html! {
+            <div class="btn-group mb-3" role="group" aria-label="Restoration source">
+                { for sources }
+            </div>
+        }
+    }
+
+    fn view_restore(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let mut input_classes = classes!["form-control"];
+        if self.restore_err.is_some() {
+            input_classes.push("is-invalid");
+        }
+        let (hint, input_placeholder, password_label, password_placeholder) =
+            match self.restore_source {
+                RestoreSource::Backup => (
+                    "Restoring a secret replaces the current one. The backup is checked \
+                     with the password before replacement.",
+                    "Secret backup",
+                    "Password",
+                    "Password used for the backup",
+                ),
+                RestoreSource::Phrase => (
+                    "Restoring a secret replaces the current one. The recovered secret \
+                     is encrypted with a new password.",
+                    "Recovery phrase (24 words separated by spaces)",
+                    "New password",
+                    "Password to encrypt the recovered secret",
+                ),
+                RestoreSource::Shares => (
+                    "Restoring a secret replaces the current one. Any sufficient number \
+                     of shares can be used in any order. The recovered secret is encrypted \
+                     with a new password.",
+                    "Recovery shares (separated by whitespace)",
+                    "New password",
+                    "Password to encrypt the recovered secret",
+                ),
+            };
+
+        html! {
+            <form onsubmit={link.callback(|evt: SubmitEvent| {
+                evt.prevent_default();
+                SecretsMessage::RestoreSubmitted { confirmed: false }
+            })}>
+                <div class="modal-body">
+                    { self.view_restore_sources(ctx) }
+                    <p class="small text-muted">{ hint }</p>
+                    <label for="restore-backup-input" class="form-label">
+                        { self.restore_source.caption() }
+                    </label>
+                    <textarea
+                        ref={self.restore_box_ref.clone()}
+                        id="restore-backup-input"
+                        class="form-control font-monospace small mb-2"
+                        rows="4"
+                        placeholder={input_placeholder}
+                        disabled={self.in_progress} />
+                    <label for="restore-password-input" class="form-label">
+                        { password_label }
+                    </label>
+                    <input
+                        ref={self.restore_password_ref.clone()}
+                        type="password"
+                        id="restore-password-input"
+                        class={input_classes}
+                        placeholder={password_placeholder}
+                        disabled={self.in_progress} />
+                    { if let Some(err) = &self.restore_err {
+                        view_err(err)
+                    } else {
+                        html!{}
+                    }}
+                    { self.view_restore_confirmation(ctx) }
+                </div>
+                <div class="modal-footer">
+                    <button
+                        type="submit"
+                        class="btn btn-primary"
+                        disabled={self.in_progress || self.restore_confirmation.is_some()}>
+                        { "Restore" }
+                    </button>
+                </div>
+            </form>
+        }
+    }
+
+    fn view_restore_confirmation(&self, ctx: &Context<Self>) -> Html {
+        let Some(poll_titles) = &self.restore_confirmation else {
+            return html! {};
+        };
+        html! {
+            <div class="alert alert-warning mt-3 mb-0" role="alert">
+                <p>{ "The following unfinished polls use keys derived from the current secret. \
+                    If the restored secret is different, you will be unable to vote in \
+                    or tally these polls." }</p>
+                <ul>
+                    { for poll_titles.iter().map(|title| html! { <li>{ title }</li> }) }
+                </ul>
+                <button
+                    type="button"
+                    class="btn btn-sm btn-danger"
+                    disabled={self.in_progress}
+                    onclick={ctx.link().callback(|_| {
+                        SecretsMessage::RestoreSubmitted { confirmed: true }
+                    })}>
+                    { "Restore anyway" }
+                </button>
+            </div>
+        }
+    }
+
+    fn restore(&mut self, ctx: &Context<Self>, confirmed: bool) -> bool {
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        if !confirmed {
+            let polls = PollManager::default().polls();
+            let polls_at_risk = secrets.polls_at_risk(&polls);
+            if !polls_at_risk.is_empty() {
+                let titles = polls.iter().filter_map(|(id, state)| {
+                    polls_at_risk
+                        .contains(id)
+                        .then(|| state.spec().title.clone())
+                });
+                self.restore_confirmation = Some(titles.collect());
+                return true;
+            }
+        }
+
+        let (password, input) = self.restore_inputs();
+        let link = ctx.link().clone();
+        let on_completion = move |res| match res {
+            Ok(()) => link.send_message(SecretsMessage::Restored),
+            Err(err) => link.send_message(SecretsMessage::ErrorRestoring(err)),
+        };
+        match self.restore_source {
+            RestoreSource::Backup => {
+                let task = secrets.restore(&password, &input);
+                spawn_local(async move { on_completion(task.await) });
+            }
+            RestoreSource::Phrase => {
+                let task = secrets.restore_from_mnemonic(&input, &password);
+                spawn_local(async move { on_completion(task.await) });
+            }
+            RestoreSource::Shares => {
+                let task = secrets.restore_from_shares(&input, &password);
+                spawn_local(async move { on_completion(task.await) });
+            }
+        }
+        self.in_progress = true;
+        true
+    }
+
+    fn view_lock(&self, ctx: &Context<Self>) -> Html {
+        let secrets = AppProperties::from_ctx(ctx).secrets;
+        let is_unlocked = secrets.status() == Some(SecretManagerStatus::Unlocked);
+        let policy = secrets.lock_policy();
+        let link = ctx.link();
+
+        let timeout_options = LockPolicy::IDLE_TIMEOUT_OPTIONS.map(|minutes| {
+            let is_selected = policy.idle_timeout_minutes == Some(minutes);
+            html! {
+                <option value={minutes.to_string()} selected={is_selected}>
+                    { format!("After {minutes} minutes") }
+                </option>
+            }
+        });
+
+        html! {
+            <form onsubmit={link.callback(|evt: SubmitEvent| {
+                evt.prevent_default();
+                SecretsMessage::LockPolicySubmitted
+            })}>
+                <div class="modal-body">
+                    <p class="small text-muted">
+                        { "Locking erases the secret from memory; the password will be required \
+                           to unlock it again." }
+                    </p>
+                    <label for="idle-timeout-select" class="form-label">
+                        { "Lock on inactivity" }
+                    </label>
+                    <select
+                        ref={self.idle_timeout_ref.clone()}
+                        id="idle-timeout-select"
+                        class="form-select mb-2">
+                        { for timeout_options }
+                        <option value="" selected={policy.idle_timeout_minutes.is_none()}>
+                            { "Never" }
+                        </option>
+                    </select>
+                    <div class="form-check">
+                        <input
+                            ref={self.lock_on_hide_ref.clone()}
+                            type="checkbox"
+                            id="lock-on-hide-input"
+                            class="form-check-input"
+                            checked={policy.lock_on_hide} />
+                        <label for="lock-on-hide-input" class="form-check-label">
+                            { "Lock when the page is hidden (e.g., another browser tab \
+                               is selected)" }
+                        </label>
+                    </div>
+                    { if self.lock_policy_saved {
+                        html! { <p class="small text-success mt-2 mb-0">{ "Settings saved." }</p> }
+                    } else {
+                        html!{}
+                    }}
+                </div>
+                <div class="modal-footer">
+                    <button
+                        type="button"
+                        class="btn btn-outline-danger"
+                        disabled={!is_unlocked}
+                        onclick={link.callback(|_| SecretsMessage::LockRequested)}>
+                        { "Lock now" }
+                    </button>
+                    <button type="submit" class="btn btn-primary">{ "Save settings" }</button>
+                </div>
+            </form>
+        }
+    }
+
+    fn save_lock_policy(&self, secrets: &SecretManager) {
+        let idle_timeout = self
+            .idle_timeout_ref
+            .cast::<HtmlSelectElement>()
+            .expect_throw("failed downcasting idle timeout select")
+            .value();
+        let lock_on_hide = self
+            .lock_on_hide_ref
+            .cast::<HtmlInputElement>()
+            .expect_throw("failed downcasting lock-on-hide input")
+            .checked();
+        secrets.set_lock_policy(LockPolicy {
+            idle_timeout_minutes: idle_timeout.parse().ok(),
+            lock_on_hide,
+        });
+    }
+
+    fn title(&self, ctx: &Context<Self>) -> &'static str {
+        let status = AppProperties::from_ctx(ctx).secrets.status();
+        match self.tab {
+            SecretsTab::Password if status == Some(SecretManagerStatus::Unlocked) => {
+                "Change password"
+            }
+            SecretsTab::Password if self.new_secret => "Create secret",
+            SecretsTab::Password => "Unlock secret",
+            SecretsTab::Backup => "Back up secret",
+            SecretsTab::Restore => "Restore secret",
+            SecretsTab::Lock => "Lock secret",
+        }
+    }
 }
 
 impl Component for Secrets {
@@ -136,18 +949,47 @@ impl Component for Secrets {
         }
 
         Self {
+            tab: SecretsTab::Password,
             input_ref: NodeRef::default(),
             new_secret,
             in_progress: false,
             err: None,
+            current_password_ref: NodeRef::default(),
+            new_password_ref: NodeRef::default(),
+            confirmed_password_ref: NodeRef::default(),
+            password_change_err: None,
+            backup_button_ref: NodeRef::default(),
+            show_phrase: false,
+            share_threshold_ref: NodeRef::default(),
+            share_count_ref: NodeRef::default(),
+            shares: vec![],
+            shown_share_qr: None,
+            shares_err: None,
+            restore_source: RestoreSource::Backup,
+            restore_password_ref: NodeRef::default(),
+            restore_box_ref: NodeRef::default(),
+            restore_confirmation: None,
+            restore_err: None,
+            idle_timeout_ref: NodeRef::default(),
+            lock_on_hide_ref: NodeRef::default(),
+            lock_policy_saved: false,
+            _app_props_handle: AppProperties::subscribe(ctx, || SecretsMessage::Locked),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         let global_props = AppProperties::from_ctx(ctx);
         match msg {
+            SecretsMessage::TabSelected(tab) => {
+                self.tab = tab;
+                self.show_phrase = false;
+                self.shares.clear();
+                self.shown_share_qr = None;
+                self.restore_confirmation = None;
+                self.lock_policy_saved = false;
+            }
             SecretsMessage::Submitted { new_secret } => {
-                let password = self.password();
+                let password = Self::take_password(&self.input_ref);
                 let link = ctx.link().clone();
                 let secrets = &global_props.secrets;
                 if new_secret {
@@ -170,23 +1012,109 @@ impl Component for Secrets {
                 self.in_progress = true;
                 return false;
             }
-            SecretsMessage::Created | SecretsMessage::Unlocked => {
+            SecretsMessage::PasswordChangeSubmitted => {
+                return self.change_password(ctx);
+            }
+            SecretsMessage::ErrorChangingPassword(err) => {
+                self.in_progress = false;
+                self.password_change_err = Some(err.message().into());
+            }
+
+            SecretsMessage::Created
+            | SecretsMessage::Unlocked
+            | SecretsMessage::Restored
+            | SecretsMessage::PasswordChanged => {
                 self.in_progress = false;
                 self.err = None;
-                global_props.modals.hide_modal("unlock-secrets-modal");
+                self.password_change_err = None;
+                self.restore_err = None;
+                self.restore_confirmation = None;
+                self.show_phrase = false;
+                self.shares.clear();
+                self.new_secret = false;
+                self.clear_secret_inputs();
+                global_props.modals.hide_modal(Self::MODAL_ID);
                 ctx.props().ondone.emit(());
             }
             SecretsMessage::ErrorUnlocking(err) => {
                 self.in_progress = false;
                 self.err = Some(err.message().into());
             }
+
+            SecretsMessage::BackupExported => {
+                if let Some(backup) = global_props.secrets.backup() {
+                    let data = ExportedData {
+                        ty: ExportedDataType::SecretBackup,
+                        data: backup,
+                    };
+                    global_props.export(data, ExportFormat::Json, &self.backup_button_ref);
+                }
+                return false;
+            }
+            SecretsMessage::PhraseToggled => {
+                self.show_phrase = !self.show_phrase;
+            }
+            SecretsMessage::SharesRequested => {
+                self.split_secret(ctx);
+            }
+            SecretsMessage::ShareExported(idx) => {
+                if let Some((share, button_ref)) = self.shares.get(idx) {
+                    let data = ExportedData {
+                        ty: ExportedDataType::SecretShare,
+                        data: share.to_string(),
+                    };
+                    global_props.export(data, ExportFormat::Json, button_ref);
+                }
+                return false;
+            }
+            SecretsMessage::ShareQrToggled(idx) => {
+                self.shown_share_qr = if self.shown_share_qr == Some(idx) {
+                    None
+                } else {
+                    Some(idx)
+                };
+            }
+            SecretsMessage::RestoreSourceSelected(source) => {
+                self.restore_source = source;
+                self.restore_err = None;
+                self.restore_confirmation = None;
+            }
+            SecretsMessage::RestoreSubmitted { confirmed } => {
+                self.restore_err = None;
+                return self.restore(ctx, confirmed);
+            }
+            SecretsMessage::ErrorRestoring(err) => {
+                self.in_progress = false;
+                self.restore_confirmation = None;
+                self.restore_err = Some(err.message().into());
+            }
+
+            SecretsMessage::LockPolicySubmitted => {
+                self.save_lock_policy(&global_props.secrets);
+                self.lock_policy_saved = true;
+            }
+            SecretsMessage::LockRequested => {
+                global_props.modals.hide_modal(Self::MODAL_ID);
+                global_props.secrets.lock();
+                return false; // `Locked` will be received via the subscription
+            }
+            SecretsMessage::Locked => {
+                // Drop all secret-derived data.
+                self.show_phrase = false;
+                self.shares.clear();
+                self.shown_share_qr = None;
+                self.new_secret = global_props.secrets.status().is_none();
+                self.clear_secret_inputs();
+            }
         }
         true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let status = AppProperties::from_ctx(ctx).secrets.status();
+        let is_unlocked = status == Some(SecretManagerStatus::Unlocked);
         html! {
-            <div id="unlock-secrets-modal"
+            <div id={Self::MODAL_ID}
                 class="modal"
                 tabindex="-1"
                 aria-labelledby="unlock-secrets-modal-label"
@@ -196,7 +1124,7 @@ impl Component for Secrets {
                     <div class="modal-content">
                         <div class="modal-header">
                             <h5 id="unlock-secrets-modal-label" class="modal-title">
-                                { "Unlock secret" }
+                                { self.title(ctx) }
                             </h5>
                             <button
                                 type="button"
@@ -205,7 +1133,16 @@ impl Component for Secrets {
                                 aria-label="Close">
                             </button>
                         </div>
-                        { self.view_form(ctx) }
+                        { self.view_tabs(ctx) }
+                        { match self.tab {
+                            SecretsTab::Password if is_unlocked => {
+                                self.view_password_change_form(ctx)
+                            }
+                            SecretsTab::Password => self.view_form(ctx),
+                            SecretsTab::Backup => self.view_backup(ctx),
+                            SecretsTab::Restore => self.view_restore(ctx),
+                            SecretsTab::Lock => self.view_lock(ctx),
+                        }}
                     </div>
                 </div>
             </div>
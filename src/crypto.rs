@@ -0,0 +1,86 @@
+//! [`PasswordBasedCrypto`] implementation in pure Rust, wrapping [`elasticpoll_core::crypto`]
+//! (see the latter for the box format).
+
+use js_sys::{Error as JsError, Promise, Uint8Array};
+use rand_core::OsRng;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use zeroize::Zeroizing;
+
+use std::{cell::RefCell, collections::HashMap};
+
+pub use elasticpoll_core::crypto::{BoxError, Kdf, PasswordBox};
+
+use crate::js::PasswordBasedCrypto;
+
+/// [`PasswordBasedCrypto`] implementation in pure Rust.
+///
+/// Unlike the host implementation, opened secrets are cached in the WASM memory only,
+/// so they do not survive page reloads.
+///
+/// By default, new boxes are sealed with PBKDF2, so that they can be opened by the host
+/// implementation as well. Argon2id needs to be enabled explicitly via [`Self::new()`].
+#[derive(Debug)]
+pub struct RustCrypto {
+    kdf: Kdf,
+    cache: RefCell<HashMap<String, Zeroizing<Vec<u8>>>>,
+}
+
+impl RustCrypto {
+    /// Creates an implementation sealing new boxes with the specified `kdf`.
+    pub fn new(kdf: Kdf) -> Self {
+        Self {
+            kdf,
+            cache: RefCell::default(),
+        }
+    }
+
+    fn reject(err: &BoxError) -> Promise {
+        Promise::reject(&JsError::new(&err.to_string()).into())
+    }
+}
+
+impl Default for RustCrypto {
+    fn default() -> Self {
+        Self::new(Kdf::PBKDF2)
+    }
+}
+
+impl PasswordBasedCrypto for RustCrypto {
+    fn seal(&self, password: &str, secret_bytes: &[u8], cache_key: &str) -> Promise {
+        match PasswordBox::seal(password, secret_bytes, self.kdf, &mut OsRng) {
+            Ok(sealed) => {
+                let sealed = serde_json::to_string(&sealed).expect_throw("cannot serialize box");
+                let secret = Zeroizing::new(secret_bytes.to_vec());
+                self.cache.borrow_mut().insert(cache_key.to_owned(), secret);
+                Promise::resolve(&sealed.into())
+            }
+            Err(err) => Self::reject(&err),
+        }
+    }
+
+    fn cached(&self, cache_key: &str) -> Promise {
+        let cache = self.cache.borrow();
+        let value = cache.get(cache_key).map_or(JsValue::NULL, |secret| {
+            Uint8Array::from(secret.as_slice()).into()
+        });
+        Promise::resolve(&value)
+    }
+
+    fn open(&self, password: &str, encrypted: &str, cache_key: &str) -> Promise {
+        let secret = serde_json::from_str::<PasswordBox>(encrypted)
+            .map_err(BoxError::Json)
+            .and_then(|sealed| sealed.open(password));
+        match secret {
+            Ok(secret) => {
+                let value = Uint8Array::from(secret.as_slice());
+                self.cache.borrow_mut().insert(cache_key.to_owned(), secret);
+                Promise::resolve(&value.into())
+            }
+            Err(err) => Self::reject(&err),
+        }
+    }
+
+    fn forget(&self, cache_key: &str) {
+        self.cache.borrow_mut().remove(cache_key);
+    }
+}
@@ -1,23 +1,28 @@
 //! Types involved in interaction with the JS host.
 
-use js_sys::Promise;
+use js_sys::{Array, Function, Promise};
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::{prelude::*, UnwrapThrowExt};
-use web_sys::Element;
+use wasm_bindgen::{convert::FromWasmAbi, prelude::*, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Element, Event, EventTarget, MessageEvent, Window};
 use yew::Callback;
 
 use std::{fmt, rc::Rc};
 
-use crate::{pages::AppProperties, poll::SecretManager};
+use crate::{
+    crypto::{Kdf, RustCrypto},
+    pages::AppProperties,
+    poll::{BoardItem, CompactEncoding, PollId, PollSpec, PollStage, PollState, SecretManager},
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExportedData {
     #[serde(rename = "type")]
     pub ty: ExportedDataType,
     pub data: String,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ExportedDataType {
     PollSpec,
@@ -25,25 +30,252 @@ pub enum ExportedDataType {
     Application,
     Vote,
     TallierShare,
+    InvitationLink,
+    ResultsLink,
+    SecretBackup,
+    SecretShare,
+    PollKey,
+}
+
+/// Format of exported data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Pretty-printed JSON.
+    Json,
+    /// Compact binary encoding (see [`CompactEncoding`]).
+    Compact,
+    /// QR code(s) with the compact encoding.
+    Qr,
+}
+
+impl ExportedData {
+    pub(crate) fn new<T: CompactEncoding>(
+        ty: ExportedDataType,
+        value: &T,
+        format: ExportFormat,
+    ) -> Self {
+        let data = match format {
+            ExportFormat::Json => {
+                serde_json::to_string_pretty(value).expect_throw("failed serializing data")
+            }
+            ExportFormat::Compact | ExportFormat::Qr => value.to_compact(),
+        };
+        Self { ty, data }
+    }
+}
+
+/// Event in the poll lifecycle reported to the host. Each event is passed to the corresponding
+/// optional host callback (e.g., `onPollCreated`) serialized as an object with the event fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AppEvent {
+    /// Poll was created locally, either as a new poll or by joining an existing one.
+    PollCreated { poll_id: PollId, spec: PollSpec },
+    /// Poll has transitioned to another stage.
+    StageChanged {
+        poll_id: PollId,
+        /// Stage name as returned by [`PollStage::name()`].
+        stage: &'static str,
+    },
+    /// Application, vote or tallier share was inserted into the poll.
+    ItemInserted { poll_id: PollId, item: BoardItem },
+    /// Poll results were computed.
+    Results { poll_id: PollId, results: Vec<u64> },
+}
+
+impl AppEvent {
+    /// Creates an event for the poll transitioning to the specified stage.
+    pub fn stage_changed(poll_id: PollId, stage: PollStage) -> Self {
+        Self::StageChanged {
+            poll_id,
+            stage: stage.name(),
+        }
+    }
+
+    /// Creates an event with poll results, or returns `None` if the poll is not finished.
+    pub fn results(poll_id: PollId, state: &PollState) -> Option<Self> {
+        let results = state.results()?.to_vec();
+        Some(Self::Results { poll_id, results })
+    }
+}
+
+/// Request sent by the parent page to the app embedded in an iframe via `postMessage`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EmbedRequest {
+    /// Verifies and inserts an application, vote or tallier share into the embedded poll.
+    InsertItem { item: BoardItem },
+    /// Exports data of the specified type. Supported types are the poll spec and state,
+    /// invitation and results links, and our application, vote or tallier share.
+    Export { data_type: ExportedDataType },
+}
+
+/// Message sent by the embedded app to the parent page via `postMessage`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EmbedMessage {
+    /// Embedded poll is loaded.
+    Ready {
+        poll_id: PollId,
+        stage: &'static str,
+    },
+    /// Poll has transitioned to another stage.
+    StageChanged {
+        poll_id: PollId,
+        stage: &'static str,
+    },
+    /// Application, vote or tallier share was inserted into the poll.
+    ItemInserted { poll_id: PollId, item: BoardItem },
+    /// Poll results were computed.
+    Results { poll_id: PollId, results: Vec<u64> },
+    /// Response to an [`EmbedRequest::Export`] request.
+    Exported { data: ExportedData },
+    /// Error processing a request.
+    Error { message: String },
+}
+
+impl EmbedMessage {
+    /// Creates a message signalling that the embedded poll is loaded.
+    pub fn ready(poll_id: PollId, stage: PollStage) -> Self {
+        Self::Ready {
+            poll_id,
+            stage: stage.name(),
+        }
+    }
+
+    /// Converts an app event into a message for the parent page. Returns `None` if the event
+    /// is not relevant for the embedded poll.
+    pub fn from_event(event: AppEvent) -> Option<Self> {
+        match event {
+            AppEvent::PollCreated { .. } => None,
+            AppEvent::StageChanged { poll_id, stage } => {
+                Some(Self::StageChanged { poll_id, stage })
+            }
+            AppEvent::ItemInserted { poll_id, item } => Some(Self::ItemInserted { poll_id, item }),
+            AppEvent::Results { poll_id, results } => Some(Self::Results { poll_id, results }),
+        }
+    }
+}
+
+/// Channel for exchanging messages with the parent page when the app is embedded in an iframe.
+/// Only the parent window with one of the allow-listed origins can communicate with the app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbedChannel {
+    origins: Rc<[String]>,
+}
+
+impl EmbedChannel {
+    pub fn new(origins: Rc<[String]>) -> Self {
+        Self { origins }
+    }
+
+    /// Returns the parent window, or `None` if the app is not embedded.
+    fn parent() -> Option<Window> {
+        let window = web_sys::window().expect_throw("no Window");
+        let parent = window.parent().ok()??;
+        let is_embedded = JsValue::from(window) != JsValue::from(parent.clone());
+        is_embedded.then_some(parent)
+    }
+
+    /// Parses a request from the message `event`. Returns `None` if the message is not sent
+    /// by the parent window with an allow-listed origin.
+    pub fn parse_request(&self, event: &MessageEvent) -> Option<Result<EmbedRequest, String>> {
+        let origin = event.origin();
+        if !self.origins.contains(&origin) {
+            return None;
+        }
+        let parent = Self::parent()?;
+        if event.source().map(JsValue::from) != Some(parent.into()) {
+            return None;
+        }
+        Some(serde_wasm_bindgen::from_value(event.data()).map_err(|err| err.to_string()))
+    }
+
+    /// Sends `message` to the parent window. The message is only delivered if the parent
+    /// has one of the allow-listed origins.
+    pub fn post(&self, message: &EmbedMessage) {
+        let Some(parent) = Self::parent() else {
+            return;
+        };
+        let message =
+            serde_wasm_bindgen::to_value(message).expect_throw("cannot serialize `EmbedMessage`");
+        for origin in self.origins.iter() {
+            // The message is silently dropped if the origin does not match.
+            parent.post_message(&message, origin).ok();
+        }
+    }
+}
+
+/// Yields to the browser event loop, so that pending DOM updates (e.g., a busy state)
+/// are rendered before CPU-heavy work on the main thread.
+pub async fn yield_to_event_loop() {
+    let promise = Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .expect_throw("no Window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, 0)
+            .expect_throw("cannot set timeout");
+    });
+    JsFuture::from(promise).await.ok();
+}
+
+/// Event listener that is removed on drop. `E` is the type of events passed to the callback
+/// (e.g., [`MessageEvent`] for `message` events).
+#[derive(Debug)]
+pub struct EventListener<E = Event> {
+    target: EventTarget,
+    event_type: &'static str,
+    closure: Closure<dyn FnMut(E)>,
+}
+
+impl<E: FromWasmAbi + 'static> EventListener<E> {
+    pub fn new(target: EventTarget, event_type: &'static str, callback: Callback<E>) -> Self {
+        let closure = Closure::<dyn FnMut(E)>::new(move |event| callback.emit(event));
+        target
+            .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())
+            .expect_throw("cannot add event listener");
+        Self {
+            target,
+            event_type,
+            closure,
+        }
+    }
+}
+
+impl<E> Drop for EventListener<E> {
+    fn drop(&mut self) {
+        self.target
+            .remove_event_listener_with_callback(
+                self.event_type,
+                self.closure.as_ref().unchecked_ref(),
+            )
+            .ok();
+    }
 }
 
 /// Encapsulates host-side password-based encryption operations.
+///
+/// The host is expected to cache opened and sealed secrets in memory, so that they are
+/// available across page reloads. The cache is split into slots identified by `cache_key`
+/// (e.g., to support multiple profiles).
 pub trait PasswordBasedCrypto {
-    /// Seals `secret_bytes` with `password` encryption.
+    /// Seals `secret_bytes` with `password` encryption and caches the secret.
     ///
     /// The promise must return a string (a password-encrypted box).
-    fn seal(&self, password: &str, secret_bytes: &[u8]) -> Promise;
+    fn seal(&self, password: &str, secret_bytes: &[u8], cache_key: &str) -> Promise;
 
     /// Returns the cached value of the secret, or `null` if it is not cached yet.
     ///
     /// The promise must return a [`Uint8Array`] or `null`.
-    fn cached(&self) -> Promise;
+    fn cached(&self, cache_key: &str) -> Promise;
 
-    /// Opens a previously sealed box with the specified `password`.
+    /// Opens a previously sealed box with the specified `password` and caches the secret.
     ///
     /// The promise must return a [`Uint8Array`], or throw an error if decryption
     /// is not successful.
-    fn open(&self, password: &str, encrypted: &str) -> Promise;
+    fn open(&self, password: &str, encrypted: &str, cache_key: &str) -> Promise;
+
+    /// Removes the secret from the cache, e.g., when the secret is locked.
+    fn forget(&self, cache_key: &str);
 }
 
 impl fmt::Debug for dyn PasswordBasedCrypto {
@@ -74,30 +306,69 @@ extern "C" {
     #[wasm_bindgen(structural, method, js_name = hideModal)]
     fn hide_modal(this: &JsAppProperties, element_id: &str);
 
+    /// Password-based crypto implementation: `"host"` (the default), `"rust"`
+    /// (see [`RustCrypto`]) or `"rust-argon2id"` (same, but sealing new boxes with Argon2id,
+    /// which cannot be opened by the host implementation).
+    #[wasm_bindgen(structural, method, getter, js_name = passwordCrypto)]
+    fn password_crypto(this: &JsAppProperties) -> Option<String>;
+
     #[wasm_bindgen(structural, method)]
     fn onexport(this: &JsAppProperties, data: JsValue, target: Element);
 
+    /// Origins of parent pages allowed to communicate with the app embedded in an iframe.
+    #[wasm_bindgen(structural, method, getter, js_name = embedOrigins)]
+    fn embed_origins(this: &JsAppProperties) -> Option<Array>;
+
+    #[wasm_bindgen(structural, method, getter, js_name = onPollCreated)]
+    fn on_poll_created(this: &JsAppProperties) -> Option<Function>;
+
+    #[wasm_bindgen(structural, method, getter, js_name = onStageChanged)]
+    fn on_stage_changed(this: &JsAppProperties) -> Option<Function>;
+
+    #[wasm_bindgen(structural, method, getter, js_name = onItemInserted)]
+    fn on_item_inserted(this: &JsAppProperties) -> Option<Function>;
+
+    #[wasm_bindgen(structural, method, getter, js_name = onResults)]
+    fn on_results(this: &JsAppProperties) -> Option<Function>;
+
     #[wasm_bindgen(structural, method, js_name = getCachedBox)]
-    fn cached_box(this: &JsAppProperties) -> Promise;
+    fn cached_box(this: &JsAppProperties, cache_key: &str) -> Promise;
+
+    #[wasm_bindgen(structural, method, js_name = forgetCachedBox)]
+    fn forget_cached_box(this: &JsAppProperties, cache_key: &str);
 
     #[wasm_bindgen(structural, method, js_name = openBox)]
-    fn open_box(this: &JsAppProperties, password: &str, encrypted: &str) -> Promise;
+    fn open_box(
+        this: &JsAppProperties,
+        password: &str,
+        encrypted: &str,
+        cache_key: &str,
+    ) -> Promise;
 
     #[wasm_bindgen(structural, method, js_name = sealBox)]
-    fn seal_box(this: &JsAppProperties, password: &str, secret_bytes: &[u8]) -> Promise;
+    fn seal_box(
+        this: &JsAppProperties,
+        password: &str,
+        secret_bytes: &[u8],
+        cache_key: &str,
+    ) -> Promise;
 }
 
 impl PasswordBasedCrypto for JsAppProperties {
-    fn seal(&self, password: &str, secret_bytes: &[u8]) -> Promise {
-        self.seal_box(password, secret_bytes)
+    fn seal(&self, password: &str, secret_bytes: &[u8], cache_key: &str) -> Promise {
+        self.seal_box(password, secret_bytes, cache_key)
+    }
+
+    fn cached(&self, cache_key: &str) -> Promise {
+        self.cached_box(cache_key)
     }
 
-    fn cached(&self) -> Promise {
-        self.cached_box()
+    fn open(&self, password: &str, encrypted: &str, cache_key: &str) -> Promise {
+        self.open_box(password, encrypted, cache_key)
     }
 
-    fn open(&self, password: &str, encrypted: &str) -> Promise {
-        self.open_box(password, encrypted)
+    fn forget(&self, cache_key: &str) {
+        self.forget_cached_box(cache_key);
     }
 }
 
@@ -111,10 +382,39 @@ impl ManageModals for JsAppProperties {
     }
 }
 
+impl JsAppProperties {
+    fn notify(&self, event: &AppEvent) {
+        let callback = match event {
+            AppEvent::PollCreated { .. } => self.on_poll_created(),
+            AppEvent::StageChanged { .. } => self.on_stage_changed(),
+            AppEvent::ItemInserted { .. } => self.on_item_inserted(),
+            AppEvent::Results { .. } => self.on_results(),
+        };
+        if let Some(callback) = callback {
+            let event =
+                serde_wasm_bindgen::to_value(event).expect_throw("cannot serialize `AppEvent`");
+            // Errors in host callbacks should not break the app, so we ignore them.
+            callback.call1(self, &event).ok();
+        }
+    }
+}
+
 impl From<JsAppProperties> for AppProperties {
     fn from(props: JsAppProperties) -> Self {
         let props = Rc::new(props);
         let onexport_props = Rc::clone(&props);
+        let onevent_props = Rc::clone(&props);
+        let embed_origins = props.embed_origins().map_or_else(Rc::default, |origins| {
+            origins
+                .iter()
+                .filter_map(|origin| origin.as_string())
+                .collect()
+        });
+        let crypto: Rc<dyn PasswordBasedCrypto> = match props.password_crypto().as_deref() {
+            Some("rust") => Rc::new(RustCrypto::default()),
+            Some("rust-argon2id") => Rc::new(RustCrypto::new(Kdf::ARGON2)),
+            _ => Rc::clone(&props) as Rc<dyn PasswordBasedCrypto>,
+        };
 
         Self {
             onexport: Callback::from(move |(data, target)| {
@@ -122,8 +422,14 @@ impl From<JsAppProperties> for AppProperties {
                     .expect_throw("cannot serialize `ExportedData`");
                 onexport_props.onexport(data, target);
             }),
-            modals: Rc::clone(&props) as Rc<dyn ManageModals>,
-            secrets: Rc::new(SecretManager::new(props)),
+            onevent: Callback::from(move |event| onevent_props.notify(&event)),
+            // Overridden by the app itself.
+            onqrexport: Callback::noop(),
+            embed_origins,
+            is_embedded: false, // set by the app itself
+            modals: props as Rc<dyn ManageModals>,
+            secrets: Rc::new(SecretManager::new(crypto)),
+            lock_epoch: 0,
         }
     }
 }
@@ -6,8 +6,9 @@ use web_sys::{Element, Event};
 use yew::{classes, html, html::Scope, Callback, Component, Html, MouseEvent, NodeRef};
 
 use crate::{
-    js::{ExportedData, ExportedDataType},
-    poll::{PollSpec, PollType, VoteChoice},
+    js::{ExportFormat, ExportedData, ExportedDataType},
+    pages::{AppProperties, Route},
+    poll::{Fingerprint, PollId, PollSpec, PollType, PublicKey, VoteChoice},
 };
 
 fn view_local_timestamp(timestamp: f64) -> Html {
@@ -28,6 +29,15 @@ pub fn view_data_row(label: Html, value: Html) -> Html {
     }
 }
 
+/// Renders the fingerprint of a participant key as a card line.
+pub fn view_key_fingerprint(key: &PublicKey) -> Html {
+    html! {
+        <p class="card-text small text-muted mb-0">
+            { "Fingerprint: " }{ Fingerprint::from(key).view() }
+        </p>
+    }
+}
+
 pub fn view_err(message: &str) -> Html {
     html! {
         <p class="invalid-feedback mb-1">{ message }</p>
@@ -114,6 +124,44 @@ impl Card {
         self
     }
 
+    /// Adds buttons exporting the card value in all supported formats.
+    pub fn with_export_buttons<C, F>(mut self, link: &Scope<C>, create_message: F) -> Self
+    where
+        C: Component,
+        F: Fn(ExportFormat, NodeRef) -> C::Message + Clone + 'static,
+    {
+        let formats = [
+            (
+                ExportFormat::Json,
+                Icon::Export,
+                " Export",
+                "Copy JSON to clipboard",
+            ),
+            (
+                ExportFormat::Compact,
+                Icon::Compact,
+                " Compact",
+                "Copy compact encoding suitable for messengers to clipboard",
+            ),
+            (ExportFormat::Qr, Icon::Qr, " QR", "Show as QR code(s)"),
+        ];
+        for (format, icon, caption, title) in formats {
+            let button_ref = NodeRef::default();
+            let create_message = create_message.clone();
+            self.buttons.push(html! {
+                <button
+                    ref={button_ref.clone()}
+                    type="button"
+                    class="btn btn-sm btn-secondary me-2"
+                    title={title}
+                    onclick={link.callback(move |_| create_message(format, button_ref.clone()))}>
+                    { icon.view() }{ caption }
+                </button>
+            });
+        }
+        self
+    }
+
     pub fn view(self) -> Html {
         let mut card_classes = classes!["card", "h-100"];
         if self.dotted_border {
@@ -172,6 +220,11 @@ pub enum Icon {
     Edit,
     Import,
     Export,
+    Compact,
+    Qr,
+    Link,
+    Download,
+    Upload,
     Reset,
     Check,
 }
@@ -186,6 +239,11 @@ impl Icon {
             Self::Edit => "bi-pencil",
             Self::Import => "bi-code-slash",
             Self::Export => "bi-clipboard",
+            Self::Compact => "bi-file-zip",
+            Self::Qr => "bi-qr-code",
+            Self::Link => "bi-link-45deg",
+            Self::Download => "bi-download",
+            Self::Upload => "bi-upload",
             Self::Reset => "bi-backspace",
             Self::Check => "bi-check-lg",
         }
@@ -196,22 +254,66 @@ impl Icon {
     }
 }
 
+/// Rendering of [`Fingerprint`]s.
+pub trait FingerprintView {
+    fn view(&self) -> Html;
+}
+
+impl FingerprintView for Fingerprint {
+    fn view(&self) -> Html {
+        html! {
+            <span
+                class="font-monospace"
+                title="Compare this fingerprint with other participants via a reliable channel">
+                { self.to_string() }
+            </span>
+        }
+    }
+}
+
 type OptionChangeCallback = Callback<(usize, Event)>;
 
-impl PollSpec {
-    pub fn view_summary_card(&self, onexport: &Callback<(ExportedData, Element)>) -> Html {
-        let exported_data = ExportedData {
-            ty: ExportedDataType::PollSpec,
-            data: serde_json::to_string_pretty(self).expect_throw("cannot serialize `PollSpec`"),
-        };
+/// Rendering of [`PollSpec`]s.
+pub trait PollSpecView {
+    fn view_summary_card(&self, props: &AppProperties) -> Html;
+
+    fn view_summary(&self) -> Html;
+
+    /// Renders poll results (cumulative votes for each option).
+    fn view_results(&self, results: &[u64]) -> Html;
+
+    fn view_as_form(&self, choice: &VoteChoice, onchange: &OptionChangeCallback) -> Html;
+}
+
+impl PollSpecView for PollSpec {
+    fn view_summary_card(&self, props: &AppProperties) -> Html {
+        let exported_data = ExportedData::new(ExportedDataType::PollSpec, self, ExportFormat::Json);
         let export_button_ref = NodeRef::default();
         let export_button_ref_ = export_button_ref.clone();
-        let onexport = onexport.reform(move |evt: MouseEvent| {
+        let onexport = props.onexport.reform(move |evt: MouseEvent| {
             evt.stop_propagation();
             evt.prevent_default();
             let target = export_button_ref_.cast::<Element>().unwrap_throw();
             (exported_data.clone(), target)
         });
+        let invitation_data = ExportedData {
+            ty: ExportedDataType::InvitationLink,
+            data: invitation_link(self),
+        };
+        let invite_button_ref = NodeRef::default();
+        let invite_button_ref_ = invite_button_ref.clone();
+        let oninvite = props.onexport.reform(move |evt: MouseEvent| {
+            evt.stop_propagation();
+            evt.prevent_default();
+            let target = invite_button_ref_.cast::<Element>().unwrap_throw();
+            (invitation_data.clone(), target)
+        });
+        let qr_data = ExportedData::new(ExportedDataType::PollSpec, self, ExportFormat::Qr);
+        let onqrexport = props.onqrexport.reform(move |evt: MouseEvent| {
+            evt.stop_propagation();
+            evt.prevent_default();
+            qr_data.clone()
+        });
 
         html! {
             <div class="accordion mb-3" id="accordion-poll-summary">
@@ -240,7 +342,23 @@ impl PollSpec {
                                 onclick={onexport}>
                                 { Icon::Export.view() }{ " Export" }
                             </button>
+                            <button
+                                type="button"
+                                class="btn btn-sm btn-secondary ms-3 mb-2 float-end"
+                                title="Show as QR code(s)"
+                                onclick={onqrexport}>
+                                { Icon::Qr.view() }{ " QR" }
+                            </button>
+                            <button
+                                ref={invite_button_ref}
+                                type="button"
+                                class="btn btn-sm btn-secondary ms-3 mb-2 float-end"
+                                title="Copy a link inviting others to this poll"
+                                onclick={oninvite}>
+                                { Icon::Link.view() }{ " Invite" }
+                            </button>
                             { self.view_summary() }
+                            { view_poll_id(&PollId::for_spec(self)) }
                         </div>
                     </div>
                 </div>
@@ -252,67 +370,136 @@ impl PollSpec {
         html! {
             <>
                 <h5>{ &self.title }</h5>
-                { self.view(None, None) }
+                { view_poll_spec(self, None, None) }
             </>
         }
     }
 
-    pub fn view_as_form(&self, choice: &VoteChoice, onchange: &OptionChangeCallback) -> Html {
-        self.view(Some(choice), Some(onchange))
-    }
-
-    fn view(&self, choice: Option<&VoteChoice>, onchange: Option<&OptionChangeCallback>) -> Html {
-        let ty = self.poll_type;
-        let options = self
-            .options
-            .iter()
-            .enumerate()
-            .map(|(idx, option)| {
-                let is_selected = choice.map(|choice| choice.is_selected(idx));
-                Self::view_option(idx, option, ty, is_selected, onchange.cloned())
-            })
-            .collect::<Html>();
+    fn view_results(&self, results: &[u64]) -> Html {
+        let total_votes = results.iter().copied().sum::<u64>();
+        let options = self.options.iter().zip(results);
+        let results: Html = options
+            .map(|(option, &votes)| view_option_result(option, votes, total_votes))
+            .collect();
         html! {
             <>
-                {if self.description.trim().is_empty() {
-                    html! { }
+                <h4>{ "Vote results" }</h4>
+                <h5 class="text-muted">{ &self.title }</h5>
+                { if self.description.trim().is_empty() {
+                    html!{}
                 } else {
-                    html! { <p class="mb-2">{ &self.description }</p> }
+                    html! { <p>{ &self.description }</p> }
                 }}
-                <div>{ options }</div>
+                { results }
             </>
         }
     }
 
-    fn view_option(
-        idx: usize,
-        option: &str,
-        ty: PollType,
-        is_selected: Option<bool>,
-        onchange: Option<OptionChangeCallback>,
-    ) -> Html {
-        let control_id = format!("poll-option{idx}");
-        let (control_type, control_name) = match ty {
-            PollType::SingleChoice => ("radio", "poll-options".to_owned()),
-            PollType::MultiChoice => ("checkbox", control_id.clone()),
-        };
-        let is_disabled = is_selected.is_none();
-        let is_checked = is_selected.unwrap_or(false);
-        let onchange = onchange.map(|callback| callback.reform(move |evt| (idx, evt)));
+    fn view_as_form(&self, choice: &VoteChoice, onchange: &OptionChangeCallback) -> Html {
+        view_poll_spec(self, Some(choice), Some(onchange))
+    }
+}
+
+/// Returns an invitation link for a poll with the spec embedded in the URL fragment.
+pub fn invitation_link(spec: &PollSpec) -> String {
+    Route::JoinPoll.to_absolute_url(&spec.to_link_fragment())
+}
 
+fn view_poll_id(id: &PollId) -> Html {
+    html! {
+        <div class="small mt-2">
+            <p class="mb-0 text-truncate">
+                <strong>{ "Poll ID:" }</strong>{ " " }{ id.to_string() }
+            </p>
+            <p class="mb-0 text-muted">
+                { "Fingerprint: " }{ Fingerprint::from(id).view() }
+            </p>
+        </div>
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn view_option_result(option: &str, votes: u64, total_votes: u64) -> Html {
+    let progress_percent = if total_votes == 0 {
+        0.0
+    } else {
+        votes as f64 * 100.0 / total_votes as f64
+    };
+    view_data_row(
+        html! { <strong>{ option }</strong> },
         html! {
-            <div class="form-check">
-                <input
-                    class="form-check-input"
-                    type={control_type}
-                    name={control_name}
-                    id={control_id.clone()}
-                    value={idx.to_string()}
-                    checked={is_checked}
-                    disabled={is_disabled}
-                    onchange={onchange} />
-                <label class="form-check-label" for={control_id}>{ option }</label>
-            </div>
-        }
+            <>
+                <p class="mb-1">{ format!("{votes} votes ({progress_percent:.0}%)") }</p>
+                <div class="progress">
+                    <div
+                        class="progress-bar"
+                        role="progressbar"
+                        style={format!("width: {progress_percent:.2}%")}
+                        aria-valuenow={progress_percent.to_string()}
+                        aria-valuemin="0"
+                        aria-valuemax="100">
+                    </div>
+                </div>
+            </>
+        },
+    )
+}
+
+fn view_poll_spec(
+    spec: &PollSpec,
+    choice: Option<&VoteChoice>,
+    onchange: Option<&OptionChangeCallback>,
+) -> Html {
+    let ty = spec.poll_type;
+    let options = spec
+        .options
+        .iter()
+        .enumerate()
+        .map(|(idx, option)| {
+            let is_selected = choice.map(|choice| choice.is_selected(idx));
+            view_option(idx, option, ty, is_selected, onchange.cloned())
+        })
+        .collect::<Html>();
+    html! {
+        <>
+            {if spec.description.trim().is_empty() {
+                html! { }
+            } else {
+                html! { <p class="mb-2">{ &spec.description }</p> }
+            }}
+            <div>{ options }</div>
+        </>
+    }
+}
+
+fn view_option(
+    idx: usize,
+    option: &str,
+    ty: PollType,
+    is_selected: Option<bool>,
+    onchange: Option<OptionChangeCallback>,
+) -> Html {
+    let control_id = format!("poll-option{idx}");
+    let (control_type, control_name) = match ty {
+        PollType::SingleChoice => ("radio", "poll-options".to_owned()),
+        PollType::MultiChoice => ("checkbox", control_id.clone()),
+    };
+    let is_disabled = is_selected.is_none();
+    let is_checked = is_selected.unwrap_or(false);
+    let onchange = onchange.map(|callback| callback.reform(move |evt| (idx, evt)));
+
+    html! {
+        <div class="form-check">
+            <input
+                class="form-check-input"
+                type={control_type}
+                name={control_name}
+                id={control_id.clone()}
+                value={idx.to_string()}
+                checked={is_checked}
+                disabled={is_disabled}
+                onchange={onchange} />
+            <label class="form-check-label" for={control_id}>{ option }</label>
+        </div>
     }
 }
@@ -15,11 +15,14 @@
 use wasm_bindgen::{prelude::*, UnwrapThrowExt};
 use yew::Renderer;
 
+pub mod api;
 mod components;
+pub mod crypto;
 pub mod js;
 mod layout;
 pub mod pages;
 pub mod poll;
+pub mod qr;
 mod rng;
 #[cfg(feature = "testing")]
 pub mod testing;
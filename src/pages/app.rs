@@ -2,18 +2,23 @@
 
 use wasm_bindgen::UnwrapThrowExt;
 use web_sys::Element;
-use yew::{html, html::Scope, Callback, Component, Context, ContextProvider, Html, Properties};
+use yew::{
+    context::ContextHandle, html, html::Scope, Callback, Component, Context, ContextProvider, Html,
+    NodeRef, Properties,
+};
 use yew_router::prelude::*;
 
 use std::rc::Rc;
 
 use super::{
-    about::About, home::Home, implementation::Implementation, new_poll::NewPoll,
-    participants::Participants, tallying::Tallying, voting::Voting, NotFound, Route,
+    about::About, embed::Embed, home::Home, implementation::Implementation, join_poll::JoinPoll,
+    new_poll::NewPoll, participants::Participants, results::Results, tallying::Tallying,
+    voting::Voting, NotFound, Route,
 };
 use crate::{
-    js::{ExportedData, ManageModals},
-    poll::{PollId, PollManager, PollSpec, PollState, SecretManager, TallierShare},
+    components::{AutoLock, ProfileSwitcher, QrCode},
+    js::{AppEvent, EmbedChannel, EmbedMessage, ExportFormat, ExportedData, ManageModals},
+    poll::{BoardItem, PollId, PollManager, PollSpec, PollState, SecretManager, TallierShare},
 };
 
 #[derive(Debug, Clone, Properties)]
@@ -25,11 +30,33 @@ pub struct AppProperties {
     /// Callback when a value gets exported.
     #[prop_or_default]
     pub onexport: Callback<(ExportedData, Element)>,
+    /// Callback when a value is requested to be exported as QR code(s).
+    #[prop_or_default]
+    pub onqrexport: Callback<ExportedData>,
+    /// Callback for poll lifecycle events reported to the host.
+    #[prop_or_default]
+    pub onevent: Callback<AppEvent>,
+    /// Origins of parent pages allowed to communicate with the app embedded in an iframe.
+    #[prop_or_default]
+    pub embed_origins: Rc<[String]>,
+    /// Whether the app is embedded in an iframe (i.e., renders [`Route::Embed`]).
+    #[prop_or_default]
+    pub is_embedded: bool,
+    /// Incremented each time the secret manager gets locked. Changing this value notifies
+    /// components subscribed via [`Self::subscribe()`].
+    #[prop_or_default]
+    pub lock_epoch: u32,
 }
 
 impl PartialEq for AppProperties {
     fn eq(&self, other: &Self) -> bool {
-        self.onexport == other.onexport && Rc::ptr_eq(&self.secrets, &other.secrets)
+        self.onexport == other.onexport
+            && self.onqrexport == other.onqrexport
+            && self.onevent == other.onevent
+            && self.embed_origins == other.embed_origins
+            && self.is_embedded == other.is_embedded
+            && self.lock_epoch == other.lock_epoch
+            && Rc::ptr_eq(&self.secrets, &other.secrets)
     }
 }
 
@@ -41,34 +68,110 @@ impl AppProperties {
             .expect_throw("no `AppProperties` context");
         this
     }
+
+    /// Subscribes a component to the changes in the app-wide properties, such as the secret
+    /// manager getting locked. The returned handle must be stored in the component.
+    pub fn subscribe<C: Component>(
+        ctx: &Context<C>,
+        message: impl Fn() -> C::Message + 'static,
+    ) -> ContextHandle<Self> {
+        let callback = ctx.link().callback(move |_: Self| message());
+        let (_, handle) = ctx
+            .link()
+            .context::<Self>(callback)
+            .expect_throw("no `AppProperties` context");
+        handle
+    }
+
+    /// Exports `data` either via the host callback, or by showing QR code(s).
+    pub(crate) fn export(&self, data: ExportedData, format: ExportFormat, target: &NodeRef) {
+        if format == ExportFormat::Qr {
+            self.onqrexport.emit(data);
+        } else {
+            let target = target.cast().unwrap_throw();
+            self.onexport.emit((data, target));
+        }
+    }
+
+    /// Notifies the host that an item was inserted into the poll.
+    pub(crate) fn notify_item_inserted(&self, poll_id: &PollId, item: BoardItem) {
+        self.onevent.emit(AppEvent::ItemInserted {
+            poll_id: *poll_id,
+            item,
+        });
+    }
+
+    /// Notifies the host that the poll has transitioned to its current stage. If the poll
+    /// is finished, the host is notified about the results as well.
+    pub(crate) fn notify_stage_changed(&self, poll_id: &PollId, state: &PollState) {
+        self.onevent
+            .emit(AppEvent::stage_changed(*poll_id, state.stage()));
+        if let Some(event) = AppEvent::results(*poll_id, state) {
+            self.onevent.emit(event);
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum AppMessage {
     PollCreated(PollSpec),
+    PollJoined(PollSpec),
     ParticipantsFinalized(PollId, Box<PollState>),
     RolledBackToParticipants(PollId, Box<PollState>),
     VotesFinalized(PollId, Box<PollState>),
     RolledBackToVoting(PollId, Box<PollState>),
+    QrExportRequested(ExportedData),
+}
+
+#[derive(Debug)]
+pub enum RootMessage {
+    ProfileSwitched,
+    SecretLocked,
 }
 
 /// Root application component.
 #[derive(Debug)]
-pub struct App;
+pub struct App {
+    /// Incremented each time the active profile is switched. Used as a key for the main view,
+    /// so that pages are re-created with the new profile.
+    profile_epoch: u32,
+    /// Incremented each time the secret manager gets locked.
+    lock_epoch: u32,
+    /// Whether the app is embedded in an iframe.
+    is_embedded: bool,
+    /// Callback for poll lifecycle events, which are additionally sent to the parent page
+    /// if the app is embedded.
+    onevent: Callback<AppEvent>,
+}
 
 impl App {
-    fn header() -> Html {
+    fn is_embedded() -> bool {
+        let window = web_sys::window().expect_throw("no Window");
+        let path = window.location().pathname().unwrap_or_default();
+        matches!(Route::recognize(&path), Some(Route::Embed { .. }))
+    }
+
+    fn header(ctx: &Context<Self>) -> Html {
         html! {
             <header class="body-header">
                 <div class="container">
-                    <div>
-                        <h1 class="display-4 mb-0">
-                            <Link<Route>
-                                to={ Route::Home }
-                                classes="d-block">{ "Elastic Poll" }</Link<Route>>
-                        </h1>
-                        <div class="text-muted">
-                            { "Cryptographically secure polling app" }
+                    <div class="d-flex align-items-end">
+                        <div>
+                            <h1 class="display-4 mb-0">
+                                <Link<Route>
+                                    to={ Route::Home }
+                                    classes="d-block">{ "Elastic Poll" }</Link<Route>>
+                            </h1>
+                            <div class="text-muted">
+                                { "Cryptographically secure polling app" }
+                            </div>
+                        </div>
+                        <div class="ms-auto">
+                            <ProfileSwitcher
+                                secrets={Rc::clone(&ctx.props().secrets)}
+                                onswitch={ctx.link().callback(|()| {
+                                    RootMessage::ProfileSwitched
+                                })} />
                         </div>
                     </div>
                 </div>
@@ -126,26 +229,81 @@ impl App {
 }
 
 impl Component for App {
-    type Message = ();
+    type Message = RootMessage;
     type Properties = AppProperties;
 
-    fn create(_: &Context<Self>) -> Self {
-        Self
+    fn create(ctx: &Context<Self>) -> Self {
+        let onlock = ctx.link().callback(|()| RootMessage::SecretLocked);
+        ctx.props().secrets.set_onlock(onlock);
+
+        let is_embedded = Self::is_embedded();
+        let host_onevent = ctx.props().onevent.clone();
+        let onevent = if is_embedded {
+            let channel = EmbedChannel::new(Rc::clone(&ctx.props().embed_origins));
+            Callback::from(move |event: AppEvent| {
+                if let Some(message) = EmbedMessage::from_event(event.clone()) {
+                    channel.post(&message);
+                }
+                host_onevent.emit(event);
+            })
+        } else {
+            host_onevent
+        };
+
+        Self {
+            profile_epoch: 0,
+            lock_epoch: 0,
+            is_embedded,
+            onevent,
+        }
+    }
+
+    fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RootMessage::ProfileSwitched => {
+                self.profile_epoch += 1;
+            }
+            RootMessage::SecretLocked => {
+                self.lock_epoch += 1;
+            }
+        }
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let main = html! {
+            <Main
+                key={self.profile_epoch}
+                lock_epoch={self.lock_epoch}
+                secrets={Rc::clone(&props.secrets)}
+                modals={Rc::clone(&props.modals)}
+                onexport={props.onexport.clone()}
+                onevent={self.onevent.clone()}
+                embed_origins={Rc::clone(&props.embed_origins)}
+                is_embedded={self.is_embedded} />
+        };
+
+        if self.is_embedded {
+            // Embedded app only renders a single poll without the header and footer.
+            return html! {
+                <BrowserRouter>
+                    <div class="container-fluid">
+                        <main>{ main }</main>
+                    </div>
+                    <AutoLock secrets={Rc::clone(&props.secrets)} />
+                </BrowserRouter>
+            };
+        }
+
         html! {
             <BrowserRouter>
-                { Self::header() }
+                { Self::header(ctx) }
                 <div class="container">
-                    <main>
-                        <Main
-                            secrets={Rc::clone(&ctx.props().secrets)}
-                            modals={Rc::clone(&ctx.props().modals)}
-                            onexport={ctx.props().onexport.clone()} />
-                    </main>
+                    <main>{ main }</main>
                     { Self::footer() }
                 </div>
+                <AutoLock secrets={Rc::clone(&props.secrets)} />
             </BrowserRouter>
         }
     }
@@ -158,6 +316,8 @@ impl Component for App {
 #[derive(Debug)]
 struct Main {
     poll_manager: PollManager,
+    qr_export: Option<ExportedData>,
+    onqrexport: Callback<ExportedData>,
 }
 
 impl Main {
@@ -166,11 +326,15 @@ impl Main {
             Route::Home => html! { <Home /> },
             Route::About => html! { <About /> },
             Route::Implementation => html! { <Implementation /> },
+            Route::Results => html! { <Results /> },
             Route::NotFound => html! { <NotFound /> },
 
             Route::NewPoll => html! {
                 <NewPoll ondone={link.callback(AppMessage::PollCreated)} />
             },
+            Route::JoinPoll => html! {
+                <JoinPoll ondone={link.callback(AppMessage::PollJoined)} />
+            },
             Route::PollParticipants { id } => {
                 let id = *id;
                 html! {
@@ -204,6 +368,21 @@ impl Main {
                         })} />
                 }
             }
+            Route::Embed { id } => {
+                let stage = PollManager::default().poll(id).map(|state| state.stage());
+                let page = stage.map_or_else(Html::default, |stage| {
+                    Self::render_route(&Route::for_poll(*id, stage), link)
+                });
+                html! { <Embed id={*id}>{ page }</Embed> }
+            }
+        }
+    }
+
+    /// Navigates to the page of a poll stage after a stage transition. The embedded app
+    /// stays on the embed route, which renders the current poll stage.
+    fn push_stage_route(navigator: &Navigator, props: &AppProperties, route: Route) {
+        if !props.is_embedded {
+            navigator.push(&route);
         }
     }
 }
@@ -212,45 +391,79 @@ impl Component for Main {
     type Message = AppMessage;
     type Properties = AppProperties;
 
-    fn create(_: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
         Self {
             poll_manager: PollManager::default(),
+            qr_export: None,
+            onqrexport: ctx.link().callback(AppMessage::QrExportRequested),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         let navigator = ctx.link().navigator().expect_throw("cannot get history");
+        let props = ctx.props();
         match msg {
             AppMessage::PollCreated(spec) => {
-                let id = self.poll_manager.create_poll(spec);
+                let id = self.poll_manager.create_poll(spec.clone());
+                props
+                    .onevent
+                    .emit(AppEvent::PollCreated { poll_id: id, spec });
                 navigator.replace(&Route::PollParticipants { id });
             }
+            AppMessage::PollJoined(spec) => {
+                // Unlike with `PollCreated`, the poll may already exist; we don't want
+                // to overwrite its state in this case.
+                let id = PollId::for_spec(&spec);
+                let route = if let Some(state) = self.poll_manager.poll(&id) {
+                    Route::for_poll(id, state.stage())
+                } else {
+                    self.poll_manager.create_poll(spec.clone());
+                    props
+                        .onevent
+                        .emit(AppEvent::PollCreated { poll_id: id, spec });
+                    Route::PollParticipants { id }
+                };
+                navigator.replace(&route);
+            }
             AppMessage::ParticipantsFinalized(id, mut state) => {
                 state.finalize_participants();
                 self.poll_manager.update_poll(&id, &state);
-                navigator.push(&Route::Voting { id });
+                props.notify_stage_changed(&id, &state);
+                Self::push_stage_route(&navigator, props, Route::Voting { id });
             }
             AppMessage::RolledBackToParticipants(id, mut state) => {
                 state.rollback_to_participants_selection();
                 self.poll_manager.update_poll(&id, &state);
-                navigator.push(&Route::PollParticipants { id });
+                props.notify_stage_changed(&id, &state);
+                Self::push_stage_route(&navigator, props, Route::PollParticipants { id });
             }
             AppMessage::VotesFinalized(id, mut state) => {
                 state.finalize_votes();
-                let our_keys = ctx.props().secrets.keys_for_poll(&id);
+                let our_keys = props.secrets.keys_for_poll(&id);
+                let mut our_share = None;
                 if let Some(our_keys) = our_keys {
                     if state.has_participant(our_keys.public()) {
                         let share = TallierShare::new(&our_keys, &id, &state);
-                        state.insert_unchecked_tallier_share(share);
+                        state.insert_unchecked_tallier_share(share.clone());
+                        our_share = Some(share);
                     }
                 }
                 self.poll_manager.update_poll(&id, &state);
-                navigator.push(&Route::Tallying { id });
+                if let Some(share) = our_share {
+                    props.notify_item_inserted(&id, BoardItem::TallierShare(share));
+                }
+                props.notify_stage_changed(&id, &state);
+                Self::push_stage_route(&navigator, props, Route::Tallying { id });
             }
             AppMessage::RolledBackToVoting(id, mut state) => {
                 state.rollback_to_voting();
                 self.poll_manager.update_poll(&id, &state);
-                navigator.push(&Route::Voting { id });
+                props.notify_stage_changed(&id, &state);
+                Self::push_stage_route(&navigator, props, Route::Voting { id });
+            }
+            AppMessage::QrExportRequested(data) => {
+                self.qr_export = Some(data);
+                props.modals.show_modal(QrCode::MODAL_ID);
             }
         }
         true
@@ -260,9 +473,15 @@ impl Component for Main {
         let link = ctx.link().clone();
         let render = move |route| Self::render_route(&route, &link);
 
+        let context = AppProperties {
+            onqrexport: self.onqrexport.clone(),
+            ..ctx.props().clone()
+        };
+
         html! {
-            <ContextProvider<AppProperties> context={ctx.props().clone()}>
+            <ContextProvider<AppProperties> context={context}>
                 <Switch<Route> render={render} />
+                <QrCode data={self.qr_export.clone()} />
             </ContextProvider<AppProperties>>
         }
     }
@@ -0,0 +1,225 @@
+//! Embedding of a single poll into an iframe.
+
+use wasm_bindgen::UnwrapThrowExt;
+use web_sys::MessageEvent;
+use yew::{context::ContextHandle, html, Callback, Component, Context, Html, Properties};
+
+use super::AppProperties;
+use crate::{
+    js::{
+        EmbedChannel, EmbedMessage, EmbedRequest, EventListener, ExportFormat, ExportedData,
+        ExportedDataType,
+    },
+    layout::invitation_link,
+    pages::Route,
+    poll::{BoardClient, BoardItem, ItemStatus, PollId, PollManager, PollState},
+};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct EmbedProperties {
+    pub id: PollId,
+    /// Page for the current poll stage.
+    pub children: Html,
+}
+
+#[derive(Debug)]
+pub enum EmbedPageMessage {
+    Request(Result<EmbedRequest, String>),
+    SecretUpdated,
+}
+
+/// Wrapper for a poll stage page embedded into an iframe. Processes requests
+/// from the parent page.
+#[derive(Debug)]
+pub struct Embed {
+    poll_manager: PollManager,
+    channel: EmbedChannel,
+    /// Incremented each time the poll is updated by a request, so that the stage page
+    /// is re-created with the updated poll.
+    revision: u32,
+    _listener: EventListener<MessageEvent>,
+    _app_props_handle: ContextHandle<AppProperties>,
+}
+
+impl Embed {
+    fn poll_state(&self, ctx: &Context<Self>) -> Result<PollState, String> {
+        self.poll_manager
+            .poll(&ctx.props().id)
+            .ok_or_else(|| "poll is not found".to_owned())
+    }
+
+    fn insert_item(&mut self, ctx: &Context<Self>, item: BoardItem) -> Result<(), String> {
+        let poll_id = &ctx.props().id;
+        let mut state = self.poll_state(ctx)?;
+        let was_finished = state.results().is_some();
+        let mut reports = BoardClient::apply_items(poll_id, &mut state, vec![item.clone()]);
+        let report = reports.pop().expect_throw("no item report");
+
+        match report.status {
+            ItemStatus::Added => {
+                self.poll_manager.update_poll(poll_id, &state);
+                self.revision += 1;
+                let props = AppProperties::from_ctx(ctx);
+                props.notify_item_inserted(poll_id, item);
+                if !was_finished && state.results().is_some() {
+                    props.notify_stage_changed(poll_id, &state);
+                }
+                Ok(())
+            }
+            ItemStatus::AlreadyPresent => Err("item is already present in the poll".to_owned()),
+            ItemStatus::Skipped(reason) => Err(format!("item cannot be inserted: {reason}")),
+            ItemStatus::Rejected(err) => Err(format!("item is rejected: {err}")),
+            ItemStatus::Submitted | ItemStatus::Superseded => {
+                unreachable!("unexpected status for a single pulled item")
+            }
+        }
+    }
+
+    fn export(&self, ctx: &Context<Self>, ty: ExportedDataType) -> Result<ExportedData, String> {
+        let state = self.poll_state(ctx)?;
+        let data = match ty {
+            ExportedDataType::PollSpec => ExportedData::new(ty, state.spec(), ExportFormat::Json),
+            ExportedDataType::PollState => {
+                ExportedData::new(ty, &state.export(), ExportFormat::Json)
+            }
+            ExportedDataType::InvitationLink => ExportedData {
+                ty,
+                data: invitation_link(state.spec()),
+            },
+            ExportedDataType::ResultsLink => {
+                if state.results().is_none() {
+                    return Err("poll is not finished".to_owned());
+                }
+                let fragment = state.export().to_link_fragment();
+                ExportedData {
+                    ty,
+                    data: Route::Results.to_absolute_url(&fragment),
+                }
+            }
+            ExportedDataType::Application
+            | ExportedDataType::Vote
+            | ExportedDataType::TallierShare => Self::export_our_item(ctx, &state, ty)?,
+            _ => return Err("data type cannot be exported".to_owned()),
+        };
+        Ok(data)
+    }
+
+    fn export_our_item(
+        ctx: &Context<Self>,
+        state: &PollState,
+        ty: ExportedDataType,
+    ) -> Result<ExportedData, String> {
+        let our_key = AppProperties::from_ctx(ctx)
+            .secrets
+            .public_key_for_poll(&ctx.props().id)
+            .ok_or("secret is locked")?;
+        let participant = state
+            .participants()
+            .iter()
+            .find(|p| *p.public_key() == our_key)
+            .ok_or("we are not a poll participant")?;
+
+        let format = ExportFormat::Json;
+        let data = match ty {
+            ExportedDataType::Application => {
+                ExportedData::new(ty, &participant.application, format)
+            }
+            ExportedDataType::Vote => {
+                let vote = participant.vote.as_ref().ok_or("we have not voted")?;
+                ExportedData::new(ty, &vote.inner, format)
+            }
+            ExportedDataType::TallierShare => {
+                let share = participant
+                    .tallier_share
+                    .as_ref()
+                    .ok_or("we have not submitted a tallier share")?;
+                ExportedData::new(ty, &share.inner, format)
+            }
+            _ => unreachable!(),
+        };
+        Ok(data)
+    }
+
+    /// Processes a request from the parent page and returns a response to it, if any.
+    /// The parent is notified about an inserted item via an app event, so successful
+    /// insertions do not have a separate response.
+    fn process_request(
+        &mut self,
+        ctx: &Context<Self>,
+        request: EmbedRequest,
+    ) -> Option<EmbedMessage> {
+        let result = match request {
+            EmbedRequest::InsertItem { item } => self.insert_item(ctx, item).map(|()| None),
+            EmbedRequest::Export { data_type } => self
+                .export(ctx, data_type)
+                .map(|data| Some(EmbedMessage::Exported { data })),
+        };
+        result.unwrap_or_else(|message| Some(EmbedMessage::Error { message }))
+    }
+}
+
+impl Component for Embed {
+    type Message = EmbedPageMessage;
+    type Properties = EmbedProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = AppProperties::from_ctx(ctx);
+        let channel = EmbedChannel::new(props.embed_origins);
+        let window = web_sys::window().expect_throw("no Window");
+        let link = ctx.link().clone();
+        let listener_channel = channel.clone();
+        let callback = Callback::from(move |event: MessageEvent| {
+            if let Some(request) = listener_channel.parse_request(&event) {
+                link.send_message(EmbedPageMessage::Request(request));
+            }
+        });
+        let listener = EventListener::new(window.into(), "message", callback);
+
+        let this = Self {
+            poll_manager: PollManager::default(),
+            channel,
+            revision: 0,
+            _listener: listener,
+            _app_props_handle: AppProperties::subscribe(ctx, || EmbedPageMessage::SecretUpdated),
+        };
+        let message = match this.poll_state(ctx) {
+            Ok(state) => EmbedMessage::ready(ctx.props().id, state.stage()),
+            Err(message) => EmbedMessage::Error { message },
+        };
+        this.channel.post(&message);
+        this
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            EmbedPageMessage::Request(Ok(request)) => {
+                let revision = self.revision;
+                if let Some(response) = self.process_request(ctx, request) {
+                    self.channel.post(&response);
+                }
+                revision != self.revision
+            }
+            EmbedPageMessage::Request(Err(err)) => {
+                let message = format!("invalid request: {err}");
+                self.channel.post(&EmbedMessage::Error { message });
+                false
+            }
+            EmbedPageMessage::SecretUpdated => true,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if self.poll_manager.poll(&ctx.props().id).is_none() {
+            return html! {
+                <div class="alert alert-warning mt-3" role="alert">
+                    { "This poll is not found in the browser. Join the poll using \
+                       its invitation link first." }
+                </div>
+            };
+        }
+
+        html! {
+            <div key={self.revision}>{ ctx.props().children.clone() }</div>
+        }
+    }
+}
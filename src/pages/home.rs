@@ -1,25 +1,28 @@
 //! Home page.
 
-use wasm_bindgen::UnwrapThrowExt;
 use web_sys::Event;
-use yew::{classes, html, Component, Context, Html, NodeRef};
+use yew::{classes, context::ContextHandle, html, Component, Context, Html, NodeRef};
 use yew_router::prelude::*;
 
 use std::{cmp::Ordering, collections::HashSet};
 
 use crate::{
-    js::{ExportedData, ExportedDataType},
+    components::Secrets,
+    js::{ExportFormat, ExportedData, ExportedDataType},
     layout::{view_err, Card, Icon, RemovalMessage},
     pages::{AppProperties, PageMetadata, Route},
-    poll::{ExportedPoll, PollId, PollManager, PollStage, PollState},
+    poll::{ExportedPoll, PollId, PollManager, PollStage, PollState, SecretManagerStatus},
+    qr::decode_pasted,
     utils::{value_from_event, ValidatedValue},
 };
 
 #[derive(Debug)]
 pub enum HomeMessage {
     PollSet(String),
-    ExportRequested(PollId, NodeRef),
+    ExportRequested(PollId, ExportFormat, NodeRef),
     Removal(RemovalMessage<PollId>),
+    SecretUpdated,
+    LockRequested,
 }
 
 impl HomeMessage {
@@ -41,11 +44,12 @@ pub struct Home {
     metadata: PageMetadata,
     new_poll: ValidatedValue,
     pending_removals: HashSet<PollId>,
+    _app_props_handle: ContextHandle<AppProperties>,
 }
 
 impl Home {
     fn set_poll(&mut self, poll: String) {
-        let parsed_poll = match serde_json::from_str::<ExportedPoll>(&poll) {
+        let parsed_poll = match decode_pasted::<ExportedPoll>(&poll) {
             Ok(poll) => poll,
             Err(err) => {
                 self.new_poll = ValidatedValue {
@@ -56,7 +60,7 @@ impl Home {
             }
         };
 
-        let (poll_id, imported_poll) = match PollState::import(parsed_poll) {
+        let (poll_id, mut imported_poll) = match PollState::import(parsed_poll) {
             Ok(value) => value,
             Err(err) => {
                 self.new_poll = ValidatedValue {
@@ -66,6 +70,10 @@ impl Home {
                 return;
             }
         };
+        // Participation is local info; it is not exported and must survive the import.
+        if let Some(existing_poll) = self.poll_manager.poll(&poll_id) {
+            imported_poll.set_participation(existing_poll.participation().cloned());
+        }
         self.poll_manager.update_poll(&poll_id, &imported_poll);
         self.new_poll = ValidatedValue::default();
     }
@@ -78,14 +86,22 @@ impl Home {
                 .unwrap_or(Ordering::Equal)
         });
 
+        let mismatched_polls = AppProperties::from_ctx(ctx)
+            .secrets
+            .polls_with_key_mismatch(&polls);
+        let mismatch_alert = Self::view_key_mismatch_alert(&polls, &mismatched_polls);
+
         let polls: Html = polls
             .into_iter()
             .map(|(id, state)| {
-                html! { <div class="col-lg-6">{ self.view_poll(id, &state, ctx) }</div> }
+                let has_key_mismatch = mismatched_polls.contains(&id);
+                let poll = self.view_poll(id, &state, has_key_mismatch, ctx);
+                html! { <div class="col-lg-6">{ poll }</div> }
             })
             .collect();
         html! {
             <>
+                { mismatch_alert }
                 <div class="row g-2 mb-2">
                     { polls }
                 </div>
@@ -102,8 +118,41 @@ impl Home {
         }
     }
 
+    fn view_key_mismatch_alert(polls: &[(PollId, PollState)], mismatched: &[PollId]) -> Html {
+        if mismatched.is_empty() {
+            return html! {};
+        }
+        let titles: Html = polls
+            .iter()
+            .filter(|(id, _)| mismatched.contains(id))
+            .map(|(_, state)| html! { <li>{ &state.spec().title }</li> })
+            .collect();
+
+        html! {
+            <div class="alert alert-danger" role="alert">
+                <p class="mb-1">
+                    { "The current secret does not correspond to the keys you have used \
+                       to join the following polls:" }
+                </p>
+                <ul class="mb-1">{ titles }</ul>
+                <p class="mb-0">
+                    { "The secret has probably been recreated or replaced. Restore the old secret \
+                       (from a backup, recovery phrase or shares) via " }
+                    <strong>{ "Manage secret" }</strong>
+                    { " to continue these polls." }
+                </p>
+            </div>
+        }
+    }
+
     #[allow(clippy::cast_precision_loss)]
-    fn view_poll(&self, id: PollId, state: &PollState, ctx: &Context<Self>) -> Html {
+    fn view_poll(
+        &self,
+        id: PollId,
+        state: &PollState,
+        has_key_mismatch: bool,
+        ctx: &Context<Self>,
+    ) -> Html {
         let poll_stage = state.stage();
         let progress_percent = (poll_stage.index() as f64 / PollStage::MAX_INDEX as f64) * 100.0;
         let is_pending_removal = self.pending_removals.contains(&id);
@@ -113,6 +162,24 @@ impl Home {
             html! {
                 <>
                     <p class="card-text mb-1">{ Self::view_poll_stage(poll_stage) }</p>
+                    { if let Some(participation) = state.participation() {
+                        html! {
+                            <p class="card-text small text-muted mb-1">
+                                { "Joined as: " }{ &participation.profile }
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    { if has_key_mismatch {
+                        html! {
+                            <p class="card-text small text-danger mb-1">
+                                { "Key mismatch: restore the old secret to continue" }
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
                     <div class="progress mb-2" style="height: 2px;">
                         <div
                             class="progress-bar"
@@ -139,7 +206,6 @@ impl Home {
 
         let mut card = card.with_timestamp(state.created_at);
         if !is_pending_removal {
-            let export_button_ref = NodeRef::default();
             card = card
                 .with_button(html! {
                     <Link<Route>
@@ -148,16 +214,8 @@ impl Home {
                         { continue_text }
                     </Link<Route>>
                 })
-                .with_button(html! {
-                    <button
-                        ref={export_button_ref.clone()}
-                        type="button"
-                        class="btn btn-sm btn-secondary me-2"
-                        onclick={link.callback(move |_| {
-                            HomeMessage::ExportRequested(id, export_button_ref.clone())
-                        })}>
-                        { Icon::Export.view() }{ " Export" }
-                    </button>
+                .with_export_buttons(link, move |format, target| {
+                    HomeMessage::ExportRequested(id, format, target)
                 })
                 .with_button(html! {
                     <button
@@ -210,6 +268,47 @@ impl Home {
         }
     }
 
+    fn view_secret(ctx: &Context<Self>) -> Html {
+        let secrets_status = AppProperties::from_ctx(ctx).secrets.status();
+        let status = match secrets_status {
+            None => "No secret has been created yet.",
+            Some(SecretManagerStatus::Locked) => "The secret is locked.",
+            Some(SecretManagerStatus::Unlocked) => "The secret is unlocked.",
+        };
+        let link = ctx.link();
+        html! {
+            <>
+                <p>
+                    { "Keys for all polls are derived from a single secret, which is stored \
+                       in the browser encrypted with a password. Back up the secret to continue \
+                       polls on another device. " }
+                    { status }
+                </p>
+                <button
+                    type="button"
+                    class="btn btn-outline-primary mb-3"
+                    data-bs-toggle="modal"
+                    data-bs-target="#unlock-secrets-modal">
+                    { "Manage secret" }
+                </button>
+                { if secrets_status == Some(SecretManagerStatus::Unlocked) {
+                    html! {
+                        <button
+                            type="button"
+                            class="btn btn-outline-danger mb-3 ms-2"
+                            title="Erase the secret from memory until it is unlocked again"
+                            onclick={link.callback(|_| HomeMessage::LockRequested)}>
+                            { "Lock" }
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                <Secrets ondone={link.callback(|()| HomeMessage::SecretUpdated)} />
+            </>
+        }
+    }
+
     fn view_poll_import_form(&self, ctx: &Context<Self>) -> Html {
         let mut control_classes = classes!["form-control", "font-monospace", "small", "mb-1"];
         if self.new_poll.error_message.is_some() {
@@ -222,7 +321,7 @@ impl Home {
                 <textarea
                     id="encoded-poll"
                     class={control_classes}
-                    placeholder="JSON-encoded poll state"
+                    placeholder="JSON or compact poll state"
                     value={self.new_poll.value.clone()}
                     onchange={link.callback(|evt| HomeMessage::poll_set(&evt))}>
                 </textarea>
@@ -240,7 +339,9 @@ impl Component for Home {
     type Message = HomeMessage;
     type Properties = ();
 
-    fn create(_: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let poll_manager = PollManager::default();
+        poll_manager.remember_participation(&AppProperties::from_ctx(ctx).secrets);
         Self {
             metadata: PageMetadata {
                 title: "Welcome".to_owned(),
@@ -249,9 +350,10 @@ impl Component for Home {
                     .to_owned(),
                 is_root: true,
             },
-            poll_manager: PollManager::default(),
+            poll_manager,
             new_poll: ValidatedValue::default(),
             pending_removals: HashSet::new(),
+            _app_props_handle: AppProperties::subscribe(ctx, || HomeMessage::SecretUpdated),
         }
     }
 
@@ -266,22 +368,29 @@ impl Component for Home {
             }
             HomeMessage::Removal(RemovalMessage::Confirmed(id)) => {
                 self.poll_manager.remove_poll(&id);
+                AppProperties::from_ctx(ctx)
+                    .secrets
+                    .remove_imported_keys(&id);
                 self.pending_removals.remove(&id);
             }
             HomeMessage::Removal(RemovalMessage::Cancelled(id)) => {
                 self.pending_removals.remove(&id);
             }
 
-            HomeMessage::ExportRequested(id, target) => {
+            HomeMessage::SecretUpdated => {
+                let secrets = AppProperties::from_ctx(ctx).secrets;
+                self.poll_manager.remember_participation(&secrets);
+            }
+            HomeMessage::LockRequested => {
+                AppProperties::from_ctx(ctx).secrets.lock();
+                return false; // The page will be re-rendered via the subscription.
+            }
+
+            HomeMessage::ExportRequested(id, format, target) => {
                 if let Some(poll) = self.poll_manager.poll(&id) {
-                    let data = serde_json::to_string_pretty(&poll.export())
-                        .expect_throw("Cannot serialize `ExportedPoll`");
-                    let data = ExportedData {
-                        ty: ExportedDataType::PollState,
-                        data,
-                    };
-                    let target = target.cast().unwrap_throw();
-                    AppProperties::from_ctx(ctx).onexport.emit((data, target));
+                    let data =
+                        ExportedData::new(ExportedDataType::PollState, &poll.export(), format);
+                    AppProperties::from_ctx(ctx).export(data, format, &target);
                     return false;
                 }
             }
@@ -312,6 +421,9 @@ impl Component for Home {
 
                 <h4>{ "Polls" }</h4>
                 { self.view_polls(ctx) }
+
+                <h4>{ "Secret" }</h4>
+                { Self::view_secret(ctx) }
             </>
         }
     }
@@ -0,0 +1,148 @@
+//! Page for joining a poll via an invitation link.
+
+use wasm_bindgen::UnwrapThrowExt;
+use yew::{html, Callback, Component, Context, Html, Properties};
+use yew_router::prelude::*;
+
+use crate::{
+    layout::{view_data_row, FingerprintView, Icon, PollSpecView},
+    pages::{PageMetadata, Route},
+    poll::{Fingerprint, PollId, PollManager, PollSpec, PollStage},
+};
+
+#[derive(Debug)]
+pub enum JoinPollMessage {
+    Done,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Properties)]
+pub struct JoinPollProperties {
+    #[prop_or_default]
+    pub ondone: Callback<PollSpec>,
+}
+
+/// Page for joining a poll with the spec embedded in the URL fragment.
+#[derive(Debug)]
+pub struct JoinPoll {
+    metadata: PageMetadata,
+    spec: Result<(PollId, PollSpec), String>,
+    existing_stage: Option<PollStage>,
+}
+
+impl JoinPoll {
+    fn parse_fragment() -> Result<(PollId, PollSpec), String> {
+        let window = web_sys::window().expect_throw("no Window");
+        let fragment = window
+            .location()
+            .hash()
+            .expect_throw("cannot get URL fragment");
+        let fragment = fragment.trim_start_matches('#');
+        if fragment.is_empty() {
+            return Err("Invitation link does not contain poll parameters".to_owned());
+        }
+
+        let spec = PollSpec::from_link_fragment(fragment)
+            .map_err(|err| format!("Error parsing invitation link: {err}"))?;
+        Ok((PollId::for_spec(&spec), spec))
+    }
+
+    fn view_spec(&self, id: &PollId, spec: &PollSpec, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        html! {
+            <>
+                <p class="lead">{ "You were invited to participate in a poll." }</p>
+                <p>{ "Check the poll parameters below. Before joining, compare the poll ID \
+                    (or its fingerprint) with the one known to the poll organizer via a reliable \
+                    channel." }</p>
+
+                <div class="card mb-3">
+                    <div class="card-body">{ spec.view_summary() }</div>
+                </div>
+                { view_data_row(
+                    html! { <label for="poll-id"><strong>{ "Poll ID" }</strong></label> },
+                    html! {
+                        <>
+                            <p id="poll-id" class="mb-1 text-truncate">{ id.to_string() }</p>
+                            <p class="mb-1 small text-muted">
+                                { "Fingerprint: " }{ Fingerprint::from(id).view() }
+                            </p>
+                        </>
+                    },
+                ) }
+
+                { if let Some(stage) = self.existing_stage {
+                    html! {
+                        <div class="mt-4 text-center">
+                            <p>{ "This poll is already stored locally." }</p>
+                            <Link<Route>
+                                to={Route::for_poll(*id, stage)}
+                                classes="btn btn-primary">
+                                { "Go to poll" }
+                            </Link<Route>>
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <div class="mt-4 text-center">
+                            <button
+                                type="button"
+                                class="btn btn-primary"
+                                onclick={link.callback(|_| JoinPollMessage::Done)}>
+                                { Icon::Check.view() }{ " Join poll" }
+                            </button>
+                        </div>
+                    }
+                }}
+            </>
+        }
+    }
+}
+
+impl Component for JoinPoll {
+    type Message = JoinPollMessage;
+    type Properties = JoinPollProperties;
+
+    fn create(_: &Context<Self>) -> Self {
+        let spec = Self::parse_fragment();
+        let existing_stage = spec.as_ref().ok().and_then(|(id, _)| {
+            let poll = PollManager::default().poll(id)?;
+            Some(poll.stage())
+        });
+
+        Self {
+            metadata: PageMetadata {
+                title: "Joining poll".to_owned(),
+                description: "Join a poll using an invitation link with embedded poll parameters."
+                    .to_owned(),
+                is_root: false,
+            },
+            spec,
+            existing_stage,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            JoinPollMessage::Done => {
+                if let Ok((_, spec)) = &self.spec {
+                    ctx.props().ondone.emit(spec.clone());
+                }
+                false // There will be a redirect; no need to re-render this page.
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { self.metadata.view() }
+                { match &self.spec {
+                    Ok((id, spec)) => self.view_spec(id, spec, ctx),
+                    Err(err) => html! {
+                        <div class="alert alert-danger" role="alert">{ err }</div>
+                    },
+                }}
+            </>
+        }
+    }
+}
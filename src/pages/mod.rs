@@ -6,10 +6,13 @@ use yew_router::prelude::*;
 
 mod about;
 mod app;
+mod embed;
 mod home;
 mod implementation;
+mod join_poll;
 mod new_poll;
 mod participants;
+mod results;
 mod tallying;
 mod voting;
 
@@ -32,12 +35,20 @@ pub enum Route {
 
     #[at("/polls/new")]
     NewPoll,
+    #[at("/polls/join")]
+    JoinPoll,
     #[at("/polls/:id/participants")]
     PollParticipants { id: PollId },
     #[at("/polls/:id/vote")]
     Voting { id: PollId },
     #[at("/polls/:id/tally")]
     Tallying { id: PollId },
+    #[at("/results")]
+    Results,
+    /// Current stage of a single poll without the header and footer, for embedding
+    /// in an iframe.
+    #[at("/embed/:id")]
+    Embed { id: PollId },
 
     #[not_found]
     #[at("/404")]
@@ -52,30 +63,45 @@ impl Route {
             PollStage::Tallying { .. } | PollStage::Finished => Self::Tallying { id },
         }
     }
+
+    /// Returns an absolute URL for this route with the specified fragment.
+    pub fn to_absolute_url(&self, fragment: &str) -> String {
+        let window = web_sys::window().expect_throw("no Window");
+        let origin = window.location().origin().expect_throw("cannot get origin");
+        format!("{origin}{}#{fragment}", self.to_path())
+    }
 }
 
-impl PollStage {
+/// Rendering of navigation among [`PollStage`]s.
+pub trait PollStageView {
     /// Renders navigation for poll stages with the current stage selected.
-    pub fn view_nav(&self, active_idx: usize, id: PollId) -> Html {
+    fn view_nav(&self, active_idx: usize, id: PollId) -> Html;
+}
+
+impl PollStageView for PollStage {
+    fn view_nav(&self, active_idx: usize, id: PollId) -> Html {
         debug_assert!(self.index() >= active_idx);
         html! {
             <ul class="nav mb-3 nav-pills flex-column flex-md-row justify-content-md-center">
                 <li class="nav-item">
                     <a class="nav-link disabled">{ "1. Specification" }</a>
                 </li>
-                { self.view_nav_item(
+                { view_nav_item(
+                    self,
                     1,
                     active_idx,
                     Route::PollParticipants { id },
                     "2. Participants",
                 ) }
-                { self.view_nav_item(
+                { view_nav_item(
+                    self,
                     2,
                     active_idx,
                     Route::Voting { id },
                     "3. Voting",
                 ) }
-                { self.view_nav_item(
+                { view_nav_item(
+                    self,
                     3,
                     active_idx,
                     Route::Tallying { id },
@@ -84,23 +110,29 @@ impl PollStage {
             </ul>
         }
     }
+}
 
-    fn view_nav_item(&self, idx: usize, active_idx: usize, route: Route, name: &str) -> Html {
-        html! {
-            <li class="nav-item">
-                { if self.index() >= idx {
-                    let mut link_classes = classes!["nav-link"];
-                    if active_idx == idx {
-                        link_classes.push("active");
-                    }
-                    html! {
-                        <Link<Route> to={route} classes={link_classes}>{ name }</Link<Route>>
-                    }
-                } else {
-                    html! { <a class="nav-link disabled">{ name }</a> }
-                }}
-            </li>
-        }
+fn view_nav_item(
+    stage: &PollStage,
+    idx: usize,
+    active_idx: usize,
+    route: Route,
+    name: &str,
+) -> Html {
+    html! {
+        <li class="nav-item">
+            { if stage.index() >= idx {
+                let mut link_classes = classes!["nav-link"];
+                if active_idx == idx {
+                    link_classes.push("active");
+                }
+                html! {
+                    <Link<Route> to={route} classes={link_classes}>{ name }</Link<Route>>
+                }
+            } else {
+                html! { <a class="nav-link disabled">{ name }</a> }
+            }}
+        </li>
     }
 }
 
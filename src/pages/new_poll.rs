@@ -12,6 +12,7 @@ use crate::{
     layout::{view_data_row, view_err, Icon},
     pages::{AppProperties, PageMetadata},
     poll::{PollSpec, PollType, MAX_OPTIONS},
+    qr::decode_pasted,
     utils::{value_from_event, value_from_input_event, ValidatedValue},
 };
 
@@ -88,8 +89,8 @@ pub struct NewPoll {
 }
 
 impl NewPoll {
-    const MAX_FIELD_LEN: usize = 128;
-    const MAX_DESCRIPTION_LEN: usize = 1_024;
+    pub(crate) const MAX_FIELD_LEN: usize = 128;
+    pub(crate) const MAX_DESCRIPTION_LEN: usize = 1_024;
 
     fn view_title(&self, ctx: &Context<Self>) -> Html {
         let mut control_classes = classes!["form-control", "mb-1"];
@@ -458,7 +459,7 @@ impl NewPoll {
                     <textarea
                         id="poll-spec"
                         class={control_classes}
-                        placeholder="JSON-encoded poll spec"
+                        placeholder="JSON or compact poll spec"
                         value={spec}
                         onchange={link.callback(|evt| NewPollMessage::spec_set(&evt))}>
                     </textarea>
@@ -514,7 +515,7 @@ impl NewPoll {
     }
 
     fn set_spec(&mut self, spec_string: String) {
-        let spec = match serde_json::from_str::<PollSpec>(&spec_string) {
+        let spec = match decode_pasted::<PollSpec>(&spec_string) {
             Ok(spec) => spec,
             Err(err) => {
                 self.spec = ValidatedValue {
@@ -2,20 +2,24 @@
 
 use wasm_bindgen::UnwrapThrowExt;
 use web_sys::Event;
-use yew::{classes, html, Component, Context, Html, NodeRef};
+use yew::{classes, context::ContextHandle, html, Component, Context, Html, NodeRef};
 use yew_router::prelude::*;
 
 use std::collections::HashSet;
 
 use crate::{
-    components::Secrets,
-    js::{ExportedData, ExportedDataType},
-    layout::{view_data_row, view_err, Card, Icon, RemovalMessage},
-    pages::{AppProperties, PageMetadata, PollStageProperties, Route},
+    components::{BoardSync, Secrets},
+    js::{ExportFormat, ExportedData, ExportedDataType},
+    layout::{
+        view_data_row, view_err, view_key_fingerprint, Card, FingerprintView, Icon, PollSpecView,
+        RemovalMessage,
+    },
+    pages::{AppProperties, PageMetadata, PollStageProperties, PollStageView, Route},
     poll::{
-        Participant, ParticipantApplication, PollId, PollManager, PollStage, PollState, PublicKey,
-        PublicKeyBytes, SecretManagerStatus,
+        BoardItem, Fingerprint, Participant, ParticipantApplication, Participation, PollId,
+        PollManager, PollStage, PollState, PublicKey, PublicKeyBytes, SecretManagerStatus,
     },
+    qr::decode_pasted,
     utils::{value_from_event, Encode, ValidatedValue},
 };
 
@@ -24,8 +28,9 @@ pub enum ParticipantsMessage {
     ApplicationSet(String),
     Removal(RemovalMessage<PublicKeyBytes>),
     UsAdded,
-    ExportRequested(usize, NodeRef),
+    ExportRequested(usize, ExportFormat, NodeRef),
     SecretUpdated,
+    BoardUpdated,
     Done,
 }
 
@@ -51,6 +56,7 @@ pub struct Participants {
     new_application: ValidatedValue,
     validated_application: Option<ParticipantApplication>,
     pending_removals: HashSet<PublicKeyBytes>,
+    _app_props_handle: ContextHandle<AppProperties>,
 }
 
 impl Participants {
@@ -62,10 +68,12 @@ impl Participants {
         state.has_participant(&pk)
     }
 
-    fn add_participant(&mut self, participant: ParticipantApplication) {
+    fn add_participant(&mut self, participant: ParticipantApplication, ctx: &Context<Self>) {
         if let Some(state) = &mut self.poll_state {
-            state.insert_participant(participant);
+            state.insert_participant(participant.clone());
             self.poll_manager.update_poll(&self.poll_id, state);
+            AppProperties::from_ctx(ctx)
+                .notify_item_inserted(&self.poll_id, BoardItem::Application(participant));
         }
     }
 
@@ -82,10 +90,10 @@ impl Participants {
         }
     }
 
-    fn set_application(&mut self, application: String) {
+    fn set_application(&mut self, application: String, ctx: &Context<Self>) {
         self.validated_application = None;
 
-        let parsed_application: ParticipantApplication = match serde_json::from_str(&application) {
+        let parsed_application = match decode_pasted::<ParticipantApplication>(&application) {
             Ok(application) => application,
             Err(err) => {
                 self.new_application = ValidatedValue {
@@ -102,7 +110,7 @@ impl Participants {
                 Some(format!("Error validating application: {err}"));
             return;
         }
-        self.add_participant(parsed_application);
+        self.add_participant(parsed_application, ctx);
         self.new_application = ValidatedValue::default();
     }
 
@@ -128,10 +136,13 @@ impl Participants {
                     </Link<Route>>
                 </p>
 
-                { state.spec().view_summary_card(&props.onexport) }
+                { state.spec().view_summary_card(&props) }
 
                 <h4>{ "Participants" }</h4>
+                { Secrets::view_profile_alert(&props.secrets, state) }
+                { Secrets::view_key_mismatch_alert(&props.secrets, &self.poll_id, state) }
                 { self.view_add_us_form(state, ctx) }
+                { self.view_board_sync(ctx) }
                 { self.view_participants(state, ctx) }
                 { Self::view_shared_key(state) }
             </>
@@ -179,11 +190,14 @@ impl Participants {
         let mut card = Card::new(
             html! { title },
             html! {
-                <p class="card-text mb-0 text-truncate">
-                    <strong>{ "Public key:" }</strong>
-                    { " " }
-                    { participant.public_key().encode() }
-                </p>
+                <>
+                    <p class="card-text mb-0 text-truncate">
+                        <strong>{ "Public key:" }</strong>
+                        { " " }
+                        { participant.public_key().encode() }
+                    </p>
+                    { view_key_fingerprint(participant.public_key()) }
+                </>
             },
         );
 
@@ -198,17 +212,8 @@ impl Participants {
         card = card.with_timestamp(participant.created_at);
 
         if !is_pending_removal {
-            let export_button_ref = NodeRef::default();
-            card = card.with_button(html! {
-                <button
-                    ref={export_button_ref.clone()}
-                    type="button"
-                    class="btn btn-sm btn-secondary me-2"
-                    onclick={link.callback(move |_| {
-                        ParticipantsMessage::ExportRequested(idx, export_button_ref.clone())
-                    })}>
-                    { Icon::Export.view() }{ " Export" }
-                </button>
+            card = card.with_export_buttons(link, move |format, target| {
+                ParticipantsMessage::ExportRequested(idx, format, target)
             });
 
             if !self.is_readonly {
@@ -227,6 +232,18 @@ impl Participants {
         card.view()
     }
 
+    fn view_board_sync(&self, ctx: &Context<Self>) -> Html {
+        if self.is_readonly {
+            html! {}
+        } else {
+            html! {
+                <BoardSync
+                    poll_id={self.poll_id}
+                    onupdate={ctx.link().callback(|()| ParticipantsMessage::BoardUpdated)} />
+            }
+        }
+    }
+
     fn view_add_us_form(&self, state: &PollState, ctx: &Context<Self>) -> Html {
         let secrets = AppProperties::from_ctx(ctx).secrets;
         let link = ctx.link();
@@ -273,7 +290,7 @@ impl Participants {
                     <textarea
                         id="participant-application"
                         class={control_classes}
-                        placeholder="JSON-encoded participant application"
+                        placeholder="JSON or compact participant application"
                         value={self.new_application.value.clone()}
                         onchange={link.callback(|evt| {
                             ParticipantsMessage::application_set(&evt)
@@ -302,6 +319,9 @@ impl Participants {
                             <p id="shared-key" class="mb-1 text-truncate">
                                 { shared_key.encode() }
                             </p>
+                            <p class="mb-1 small text-muted">
+                                { "Fingerprint: " }{ Fingerprint::from(&shared_key).view() }
+                            </p>
                             <p class="small text-muted">
                                 { "The order of participants does not matter and can differ for \
                                 different participants. However, this shared public key \
@@ -344,13 +364,14 @@ impl Component for Participants {
             new_application: ValidatedValue::default(),
             validated_application: None,
             pending_removals: HashSet::new(),
+            _app_props_handle: AppProperties::subscribe(ctx, || ParticipantsMessage::SecretUpdated),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             ParticipantsMessage::ApplicationSet(application) => {
-                self.set_application(application);
+                self.set_application(application, ctx);
             }
 
             ParticipantsMessage::Removal(RemovalMessage::Requested(key_bytes)) => {
@@ -366,19 +387,20 @@ impl Component for Participants {
 
             ParticipantsMessage::UsAdded => {
                 let us = self.create_our_participant(ctx);
-                self.add_participant(us);
+                if let Some(state) = &mut self.poll_state {
+                    let profile = AppProperties::from_ctx(ctx).secrets.active_profile();
+                    state.set_participation(Some(Participation {
+                        profile,
+                        public_key: Some(us.public_key.clone()),
+                    }));
+                }
+                self.add_participant(us, ctx);
             }
-            ParticipantsMessage::ExportRequested(idx, target) => {
+            ParticipantsMessage::ExportRequested(idx, format, target) => {
                 if let Some(state) = &self.poll_state {
                     let app = &state.participants()[idx].application;
-                    let app = serde_json::to_string_pretty(app)
-                        .expect_throw("failed serializing `ParticipantApplication`");
-                    let data = ExportedData {
-                        ty: ExportedDataType::Application,
-                        data: app,
-                    };
-                    let target = target.cast().unwrap_throw();
-                    AppProperties::from_ctx(ctx).onexport.emit((data, target));
+                    let data = ExportedData::new(ExportedDataType::Application, app, format);
+                    AppProperties::from_ctx(ctx).export(data, format, &target);
                 }
                 return false;
             }
@@ -386,6 +408,9 @@ impl Component for Participants {
             ParticipantsMessage::SecretUpdated => {
                 // Do nothing specific, just re-render the component.
             }
+            ParticipantsMessage::BoardUpdated => {
+                self.poll_state = self.poll_manager.poll(&self.poll_id);
+            }
             ParticipantsMessage::Done => {
                 let state = self.poll_state.take().expect_throw("no poll state");
                 ctx.props().ondone.emit(state);
@@ -401,7 +426,11 @@ impl Component for Participants {
             html! {
                 <>
                     { self.metadata.view() }
-                    { state.stage().view_nav(PollStage::PARTICIPANTS_IDX, self.poll_id) }
+                    { if AppProperties::from_ctx(ctx).is_embedded {
+                        html!{}
+                    } else {
+                        state.stage().view_nav(PollStage::PARTICIPANTS_IDX, self.poll_id)
+                    }}
                     { self.view_poll(state, ctx) }
 
                     { if self.is_readonly {
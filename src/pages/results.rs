@@ -0,0 +1,159 @@
+//! Read-only page with results of a poll embedded in the URL fragment.
+
+use wasm_bindgen::UnwrapThrowExt;
+use yew::{html, Component, Context, Html};
+
+use crate::{
+    layout::{view_data_row, view_key_fingerprint, Card, FingerprintView, PollSpecView},
+    pages::PageMetadata,
+    poll::{ExportedPoll, Fingerprint, Participant, PollId, PollStage, PollState},
+    utils::Encode,
+};
+
+/// Read-only page displaying poll results. The poll is imported and verified in memory;
+/// nothing is saved to the local storage.
+#[derive(Debug)]
+pub struct Results {
+    metadata: PageMetadata,
+    poll: Result<(PollId, PollState), String>,
+}
+
+impl Results {
+    fn import_from_fragment() -> Result<(PollId, PollState), String> {
+        let window = web_sys::window().expect_throw("no Window");
+        let fragment = window
+            .location()
+            .hash()
+            .expect_throw("cannot get URL fragment");
+        let fragment = fragment.trim_start_matches('#');
+        if fragment.is_empty() {
+            return Err("Link does not contain poll data".to_owned());
+        }
+
+        let exported = ExportedPoll::from_link_fragment(fragment)
+            .map_err(|err| format!("Error parsing poll data: {err}"))?;
+        PollState::import(exported).map_err(|err| format!("Error verifying poll: {err}"))
+    }
+
+    fn view_poll(id: &PollId, state: &PollState) -> Html {
+        html! {
+            <>
+                <p class="lead">{ "These are results of a poll, which were verified in your \
+                    browser." }</p>
+                <p>{ "All participant applications, votes and tallier shares were checked \
+                    using the cryptographic proofs attached to them. Note that this does not \
+                    verify that the participant list is legitimate; compare the poll ID and \
+                    participant keys with ones known from a reliable channel." }</p>
+
+                <div class="card mb-3">
+                    <div class="card-body">{ state.spec().view_summary() }</div>
+                </div>
+                { view_data_row(
+                    html! { <label for="poll-id"><strong>{ "Poll ID" }</strong></label> },
+                    html! {
+                        <>
+                            <p id="poll-id" class="mb-1 text-truncate">{ id.to_string() }</p>
+                            <p class="mb-1 small text-muted">
+                                { "Fingerprint: " }{ Fingerprint::from(id).view() }
+                            </p>
+                        </>
+                    },
+                ) }
+
+                <h4>{ "Participants" }</h4>
+                <div class="row g-2 mb-3">
+                    { for state.participants().iter().enumerate().map(|(idx, participant)| {
+                        html! {
+                            <div class="col-lg-6">{ Self::view_participant(idx, participant) }</div>
+                        }
+                    }) }
+                </div>
+
+                { if let Some(results) = state.results() {
+                    state.spec().view_results(results)
+                } else {
+                    html! {
+                        <div class="alert alert-warning" role="alert">
+                            { Self::describe_unfinished_stage(state.stage()) }
+                        </div>
+                    }
+                }}
+            </>
+        }
+    }
+
+    fn view_participant(idx: usize, participant: &Participant) -> Html {
+        let vote_status = if participant.vote.is_some() {
+            "submitted"
+        } else {
+            "not submitted"
+        };
+        let share_status = if participant.tallier_share.is_some() {
+            html! { <span class="text-success">{ "verified" }</span> }
+        } else {
+            html! { <span class="text-muted">{ "missing" }</span> }
+        };
+
+        Card::new(
+            html! { format!("#{}", idx + 1) },
+            html! {
+                <>
+                    <p class="card-text mb-0 text-truncate">
+                        <strong>{ "Public key:" }</strong>
+                        { " " }
+                        { participant.public_key().encode() }
+                    </p>
+                    { view_key_fingerprint(participant.public_key()) }
+                    <p class="card-text mb-0">
+                        <strong>{ "Vote:" }</strong>{ " " }{ vote_status }
+                    </p>
+                    <p class="card-text mb-0">
+                        <strong>{ "Tallier share:" }</strong>{ " " }{ share_status }
+                    </p>
+                </>
+            },
+        )
+        .view()
+    }
+
+    fn describe_unfinished_stage(stage: PollStage) -> &'static str {
+        match stage {
+            PollStage::Participants { .. } => "The poll is at the participant selection stage.",
+            PollStage::Voting { .. } => "The poll is at the voting stage.",
+            PollStage::Tallying { .. } => {
+                "The poll is at the tallying stage; not all tallier shares are submitted."
+            }
+            PollStage::Finished => "The poll is finished.",
+        }
+    }
+}
+
+impl Component for Results {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            metadata: PageMetadata {
+                title: "Poll results".to_owned(),
+                description: "Read-only view of verified poll results.".to_owned(),
+                is_root: false,
+            },
+            poll: Self::import_from_fragment(),
+        }
+    }
+
+    fn view(&self, _: &Context<Self>) -> Html {
+        html! {
+            <>
+                { self.metadata.view() }
+                { match &self.poll {
+                    Ok((id, state)) => Self::view_poll(id, state),
+                    Err(err) => html! {
+                        <div class="alert alert-danger" role="alert">{ err }</div>
+                    },
+                }}
+            </>
+        }
+    }
+}
@@ -2,26 +2,29 @@
 
 use wasm_bindgen::UnwrapThrowExt;
 use web_sys::Event;
-use yew::{classes, html, Component, Context, Html, NodeRef};
+use yew::{classes, context::ContextHandle, html, Component, Context, Html, NodeRef};
 use yew_router::prelude::*;
 
 use crate::{
-    components::{Rollback, Secrets},
-    js::{ExportedData, ExportedDataType},
-    layout::{view_data_row, view_err, Card, Icon},
-    pages::{AppProperties, PageMetadata, PollStageProperties, Route},
+    components::{BoardSync, PollKeyTransfer, Rollback, Secrets},
+    js::{ExportFormat, ExportedData, ExportedDataType},
+    layout::{view_err, view_key_fingerprint, Card, Icon, PollSpecView},
+    pages::{AppProperties, PageMetadata, PollStageProperties, PollStageView, Route},
     poll::{
-        Participant, PollId, PollManager, PollStage, PollState, PublicKey, SecretManagerStatus,
-        SubmittedTallierShare, TallierShare,
+        BoardItem, Participant, PollId, PollManager, PollStage, PollState, PublicKey,
+        SecretManagerStatus, SubmittedTallierShare, TallierShare,
     },
+    qr::decode_pasted,
     utils::{value_from_event, Encode, ValidatedValue},
 };
 
 #[derive(Debug)]
 pub enum TallyingMessage {
     ShareSet(String),
-    ExportRequested(usize, NodeRef),
+    ExportRequested(usize, ExportFormat, NodeRef),
+    ResultsLinkRequested(NodeRef),
     SecretUpdated,
+    BoardUpdated,
     RollbackRequested,
     Rollback,
 }
@@ -40,6 +43,7 @@ pub struct Tallying {
     poll_state: Option<PollState>,
     is_readonly: bool,
     new_share: ValidatedValue,
+    _app_props_handle: ContextHandle<AppProperties>,
 }
 
 impl Tallying {
@@ -48,8 +52,8 @@ impl Tallying {
         Some(&participants.get(idx)?.tallier_share.as_ref()?.inner)
     }
 
-    fn set_share(&mut self, share: String) {
-        let parsed_share = match serde_json::from_str::<TallierShare>(&share) {
+    fn set_share(&mut self, share: String, ctx: &Context<Self>) {
+        let parsed_share = match decode_pasted::<TallierShare>(&share) {
             Ok(share) => share,
             Err(err) => {
                 self.new_share = ValidatedValue {
@@ -61,7 +65,7 @@ impl Tallying {
         };
 
         if let Some(state) = &mut self.poll_state {
-            if let Err(err) = state.insert_tallier_share(&self.poll_id, parsed_share) {
+            if let Err(err) = state.insert_tallier_share(&self.poll_id, parsed_share.clone()) {
                 self.new_share = ValidatedValue {
                     value: share,
                     error_message: Some(format!("Error verifying share: {err}")),
@@ -70,6 +74,12 @@ impl Tallying {
             }
             self.poll_manager.update_poll(&self.poll_id, state);
             self.is_readonly = state.results().is_some();
+
+            let props = AppProperties::from_ctx(ctx);
+            props.notify_item_inserted(&self.poll_id, BoardItem::TallierShare(parsed_share));
+            if self.is_readonly {
+                props.notify_stage_changed(&self.poll_id, state);
+            }
         }
         self.new_share = ValidatedValue::default();
     }
@@ -84,11 +94,22 @@ impl Tallying {
             .iter()
             .find(|&p| p.public_key() == our_keys.public())?;
 
+        let mut our_share = None;
         if our_participant.tallier_share.is_none() {
             let share = TallierShare::new(&our_keys, &self.poll_id, state);
-            state.insert_unchecked_tallier_share(share);
+            state.insert_unchecked_tallier_share(share.clone());
+            our_share = Some(share);
         }
         self.poll_manager.update_poll(&self.poll_id, state);
+
+        if let Some(share) = our_share {
+            self.is_readonly = state.results().is_some();
+            let props = AppProperties::from_ctx(ctx);
+            props.notify_item_inserted(&self.poll_id, BoardItem::TallierShare(share));
+            if self.is_readonly {
+                props.notify_stage_changed(&self.poll_id, state);
+            }
+        }
         Some(())
     }
 
@@ -108,7 +129,21 @@ impl Tallying {
                 </p>
 
                 <h4>{ "Shares" }</h4>
+                { Secrets::view_profile_alert(&AppProperties::from_ctx(ctx).secrets, state) }
+                { Secrets::view_key_mismatch_alert(&AppProperties::from_ctx(ctx).secrets, &self.poll_id, state) }
                 { Self::view_secrets_alert(ctx) }
+                <PollKeyTransfer
+                    poll_id={self.poll_id}
+                    onimport={ctx.link().callback(|()| TallyingMessage::SecretUpdated)} />
+                { if self.is_readonly {
+                    html!{}
+                } else {
+                    html! {
+                        <BoardSync
+                            poll_id={self.poll_id}
+                            onupdate={ctx.link().callback(|()| TallyingMessage::BoardUpdated)} />
+                    }
+                }}
                 { self.view_shares(state, ctx) }
             </>
         }
@@ -169,11 +204,14 @@ impl Tallying {
         let mut card = Card::new(
             html! { title },
             html! {
-                <p class="card-text mb-0 text-truncate">
-                    <strong>{ "Tallier’s key:" }</strong>
-                    { " " }
-                    { participant.public_key().encode() }
-                </p>
+                <>
+                    <p class="card-text mb-0 text-truncate">
+                        <strong>{ "Tallier’s key:" }</strong>
+                        { " " }
+                        { participant.public_key().encode() }
+                    </p>
+                    { view_key_fingerprint(participant.public_key()) }
+                </>
             },
         );
 
@@ -182,22 +220,35 @@ impl Tallying {
         }
 
         let link = ctx.link();
-        let export_button_ref = NodeRef::default();
         card.with_timestamp(share.submitted_at)
-            .with_button(html! {
-                <button
-                    ref={export_button_ref.clone()}
-                    type="button"
-                    class="btn btn-sm btn-secondary"
-                    onclick={link.callback(move |_| {
-                        TallyingMessage::ExportRequested(idx, export_button_ref.clone())
-                    })}>
-                    { Icon::Export.view() }{ " Export" }
-                </button>
+            .with_export_buttons(link, move |format, target| {
+                TallyingMessage::ExportRequested(idx, format, target)
             })
             .view()
     }
 
+    fn view_results(state: &PollState, results: &[u64], ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let share_button_ref = NodeRef::default();
+        html! {
+            <>
+                { state.spec().view_results(results) }
+                <div class="mt-4 text-center">
+                    <button
+                        ref={share_button_ref.clone()}
+                        type="button"
+                        class="btn btn-outline-primary"
+                        title="Copy a link to a read-only page with poll results"
+                        onclick={link.callback(move |_| {
+                            TallyingMessage::ResultsLinkRequested(share_button_ref.clone())
+                        })}>
+                        { Icon::Link.view() }{ " Share results" }
+                    </button>
+                </div>
+            </>
+        }
+    }
+
     fn view_new_share_form(&self, ctx: &Context<Self>) -> Html {
         let mut control_classes = classes!["form-control", "font-monospace", "small", "mb-1"];
         if self.new_share.error_message.is_some() {
@@ -212,7 +263,7 @@ impl Tallying {
                     <textarea
                         id="encoded-share"
                         class={control_classes}
-                        placeholder="JSON-encoded share"
+                        placeholder="JSON or compact share"
                         value={self.new_share.value.clone()}
                         onchange={link.callback(|evt| TallyingMessage::share_set(&evt))}>
                     </textarea>
@@ -226,53 +277,6 @@ impl Tallying {
         );
         card.with_dotted_border().view()
     }
-
-    fn view_results(state: &PollState, results: &[u64]) -> Html {
-        let total_votes = results.iter().copied().sum::<u64>();
-        let options = state.spec().options.iter().zip(results);
-        let results: Html = options
-            .map(|(option, &votes)| Self::view_option_result(option, votes, total_votes))
-            .collect();
-        html! {
-            <>
-                <h4>{ "Vote results" }</h4>
-                <h5 class="text-muted">{ &state.spec().title }</h5>
-                { if state.spec().description.trim().is_empty() {
-                    html!{}
-                } else {
-                    html! { <p>{ &state.spec().description }</p> }
-                }}
-                { results }
-            </>
-        }
-    }
-
-    #[allow(clippy::cast_precision_loss)]
-    fn view_option_result(option: &str, votes: u64, total_votes: u64) -> Html {
-        let progress_percent = if total_votes == 0 {
-            0.0
-        } else {
-            votes as f64 * 100.0 / total_votes as f64
-        };
-        view_data_row(
-            html! { <strong>{ option }</strong> },
-            html! {
-                <>
-                    <p class="mb-1">{ format!("{votes} votes ({progress_percent:.0}%)") }</p>
-                    <div class="progress">
-                        <div
-                            class="progress-bar"
-                            role="progressbar"
-                            style={format!("width: {progress_percent:.2}%")}
-                            aria-valuenow={progress_percent.to_string()}
-                            aria-valuemin="0"
-                            aria-valuemax="100">
-                        </div>
-                    </div>
-                </>
-            },
-        )
-    }
 }
 
 impl Component for Tallying {
@@ -298,30 +302,43 @@ impl Component for Tallying {
             poll_state,
             is_readonly,
             new_share: ValidatedValue::default(),
+            _app_props_handle: AppProperties::subscribe(ctx, || TallyingMessage::SecretUpdated),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TallyingMessage::ShareSet(share) => {
-                self.set_share(share);
+                self.set_share(share, ctx);
             }
-            TallyingMessage::ExportRequested(idx, target) => {
+            TallyingMessage::ExportRequested(idx, format, target) => {
                 if let Some(share) = self.share(idx) {
-                    let share = serde_json::to_string_pretty(share)
-                        .expect_throw("failed serializing `TallierShare`");
+                    let data = ExportedData::new(ExportedDataType::TallierShare, share, format);
+                    AppProperties::from_ctx(ctx).export(data, format, &target);
+                }
+                return false;
+            }
+            TallyingMessage::ResultsLinkRequested(target) => {
+                if let Some(state) = &self.poll_state {
+                    let fragment = state.export().to_link_fragment();
                     let data = ExportedData {
-                        ty: ExportedDataType::TallierShare,
-                        data: share,
+                        ty: ExportedDataType::ResultsLink,
+                        data: Route::Results.to_absolute_url(&fragment),
                     };
-                    let target = target.cast().unwrap_throw();
-                    AppProperties::from_ctx(ctx).onexport.emit((data, target));
+                    AppProperties::from_ctx(ctx).export(data, ExportFormat::Json, &target);
                 }
                 return false;
             }
             TallyingMessage::SecretUpdated => {
                 self.maybe_submit_our_share(ctx);
             }
+            TallyingMessage::BoardUpdated => {
+                self.poll_state = self.poll_manager.poll(&self.poll_id);
+                self.is_readonly = self
+                    .poll_state
+                    .as_ref()
+                    .map_or(true, |state| state.results().is_some());
+            }
             TallyingMessage::RollbackRequested => {
                 let state = self.poll_state.as_ref().expect_throw("no poll state");
                 let is_safe_to_rollback = state
@@ -354,11 +371,15 @@ impl Component for Tallying {
             html! {
                 <>
                     { self.metadata.view() }
-                    { state.stage().view_nav(PollStage::TALLYING_IDX, self.poll_id) }
+                    { if AppProperties::from_ctx(ctx).is_embedded {
+                        html!{}
+                    } else {
+                        state.stage().view_nav(PollStage::TALLYING_IDX, self.poll_id)
+                    }}
                     { self.view_poll(state, ctx) }
 
                     { if let Some(results) = state.results() {
-                        Self::view_results(state, results)
+                        Self::view_results(state, results, ctx)
                     } else if self.is_readonly {
                         html!{}
                     } else {
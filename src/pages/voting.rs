@@ -2,18 +2,21 @@
 
 use wasm_bindgen::UnwrapThrowExt;
 use web_sys::{Event, HtmlInputElement};
-use yew::{classes, html, Component, Context, Html, NodeRef};
+use yew::{classes, context::ContextHandle, html, Component, Context, Html, NodeRef};
 use yew_router::prelude::*;
 
 use crate::{
-    components::{Rollback, Secrets},
-    js::{ExportedData, ExportedDataType},
-    layout::{view_err, Card, Icon},
-    pages::{AppProperties, PageMetadata, PollStageProperties, Route},
+    components::{BoardSync, PollKeyTransfer, Rollback, Secrets},
+    js::{ExportFormat, ExportedData, ExportedDataType},
+    layout::{
+        view_data_row, view_err, view_key_fingerprint, Card, FingerprintView, Icon, PollSpecView,
+    },
+    pages::{AppProperties, PageMetadata, PollStageProperties, PollStageView, Route},
     poll::{
-        Participant, PollId, PollManager, PollStage, PollState, PublicKey, SecretManagerStatus,
-        SubmittedVote, Vote, VoteChoice,
+        BoardItem, Fingerprint, Participant, PollId, PollManager, PollStage, PollState, PublicKey,
+        SecretManagerStatus, SubmittedVote, Vote, VoteChoice,
     },
+    qr::decode_pasted,
     utils::{get_event_target, value_from_event, Encode, ValidatedValue},
 };
 
@@ -22,8 +25,9 @@ pub enum VotingMessage {
     OptionSelected(usize, bool),
     VoteSet(String),
     OurVoteAdded,
-    ExportRequested(usize, NodeRef),
+    ExportRequested(usize, ExportFormat, NodeRef),
     SecretUpdated,
+    BoardUpdated,
     Done,
     RollbackRequested,
     Rollback,
@@ -50,6 +54,7 @@ pub struct Voting {
     is_readonly: bool,
     our_choice: Option<VoteChoice>,
     new_vote: ValidatedValue,
+    _app_props_handle: ContextHandle<AppProperties>,
 }
 
 impl Voting {
@@ -75,8 +80,8 @@ impl Voting {
         Some(&participants.get(idx)?.vote.as_ref()?.inner)
     }
 
-    fn set_vote(&mut self, vote: String) {
-        let parsed_vote = match serde_json::from_str::<Vote>(&vote) {
+    fn set_vote(&mut self, vote: String, ctx: &Context<Self>) {
+        let parsed_vote = match decode_pasted::<Vote>(&vote) {
             Ok(vote) => vote,
             Err(err) => {
                 self.new_vote = ValidatedValue {
@@ -88,7 +93,7 @@ impl Voting {
         };
 
         if let Some(state) = &mut self.poll_state {
-            if let Err(err) = state.insert_vote(&self.poll_id, parsed_vote) {
+            if let Err(err) = state.insert_vote(&self.poll_id, parsed_vote.clone()) {
                 self.new_vote = ValidatedValue {
                     value: vote,
                     error_message: Some(format!("Error verifying vote: {err}")),
@@ -96,6 +101,8 @@ impl Voting {
                 return;
             }
             self.poll_manager.update_poll(&self.poll_id, state);
+            AppProperties::from_ctx(ctx)
+                .notify_item_inserted(&self.poll_id, BoardItem::Vote(parsed_vote));
         }
         self.new_vote = ValidatedValue::default();
     }
@@ -108,8 +115,10 @@ impl Voting {
                     .keys_for_poll(&self.poll_id)
                     .expect_throw("creating vote with locked secret manager");
                 let vote = Vote::new(&our_keypair, &self.poll_id, state, choice);
-                state.insert_unchecked_vote(vote);
+                state.insert_unchecked_vote(vote.clone());
                 self.poll_manager.update_poll(&self.poll_id, state);
+                AppProperties::from_ctx(ctx)
+                    .notify_item_inserted(&self.poll_id, BoardItem::Vote(vote));
             }
         }
     }
@@ -132,12 +141,44 @@ impl Voting {
                 </p>
 
                 <h4>{ "Votes" }</h4>
+                { Secrets::view_profile_alert(&AppProperties::from_ctx(ctx).secrets, state) }
+                { Secrets::view_key_mismatch_alert(&AppProperties::from_ctx(ctx).secrets, &self.poll_id, state) }
                 { Self::view_secrets_alert(ctx) }
+                <PollKeyTransfer
+                    poll_id={self.poll_id}
+                    onimport={ctx.link().callback(|()| VotingMessage::SecretUpdated)} />
+                { if self.is_readonly {
+                    html!{}
+                } else {
+                    html! {
+                        <BoardSync
+                            poll_id={self.poll_id}
+                            onupdate={ctx.link().callback(|()| VotingMessage::BoardUpdated)} />
+                    }
+                }}
                 { self.view_votes(state, ctx) }
+                { Self::view_state_fingerprint(state) }
             </>
         }
     }
 
+    fn view_state_fingerprint(state: &PollState) -> Html {
+        let fingerprint = Fingerprint::new(&state.digest());
+        view_data_row(
+            html! { <strong>{ "Votes fingerprint" }</strong> },
+            html! {
+                <>
+                    <p class="mb-1">{ fingerprint.view() }</p>
+                    <p class="small text-muted">
+                        { "The fingerprint covers participants and their latest submitted votes. \
+                        It must be the same across all participants before proceeding \
+                        to the next step." }
+                    </p>
+                </>
+            },
+        )
+    }
+
     fn view_secrets_alert(ctx: &Context<Self>) -> Html {
         let secrets = AppProperties::from_ctx(ctx).secrets;
         let link = ctx.link();
@@ -203,6 +244,7 @@ impl Voting {
                         { " " }
                         { participant.public_key().encode() }
                     </p>
+                    { view_key_fingerprint(participant.public_key()) }
                 </>
             },
         );
@@ -212,18 +254,9 @@ impl Voting {
         }
 
         let link = ctx.link();
-        let export_button_ref = NodeRef::default();
         card.with_timestamp(vote.submitted_at)
-            .with_button(html! {
-                <button
-                    ref={export_button_ref.clone()}
-                    type="button"
-                    class="btn btn-sm btn-secondary"
-                    onclick={link.callback(move |_| {
-                        VotingMessage::ExportRequested(idx, export_button_ref.clone())
-                    })}>
-                    { Icon::Export.view() }{ " Export" }
-                </button>
+            .with_export_buttons(link, move |format, target| {
+                VotingMessage::ExportRequested(idx, format, target)
             })
             .view()
     }
@@ -242,7 +275,7 @@ impl Voting {
                     <textarea
                         id="encoded-vote"
                         class={control_classes}
-                        placeholder="JSON-encoded vote"
+                        placeholder="JSON or compact vote"
                         value={self.new_vote.value.clone()}
                         onchange={link.callback(|evt| VotingMessage::vote_set(&evt))}>
                     </textarea>
@@ -282,7 +315,7 @@ impl Voting {
                     <div class="alert alert-warning" role="alert">
                         { "You are not a poll participant and cannot vote in this poll." }
                     </div>
-                    { state.spec().view_summary_card(&props.onexport) }
+                    { state.spec().view_summary_card(&props) }
                 </>
             }
         }
@@ -313,6 +346,7 @@ impl Component for Voting {
             poll_state,
             is_readonly,
             new_vote: ValidatedValue::default(),
+            _app_props_handle: AppProperties::subscribe(ctx, || VotingMessage::SecretUpdated),
         }
     }
 
@@ -324,21 +358,15 @@ impl Component for Voting {
                 }
             }
             VotingMessage::VoteSet(vote) => {
-                self.set_vote(vote);
+                self.set_vote(vote, ctx);
             }
             VotingMessage::OurVoteAdded => {
                 self.insert_our_vote(ctx);
             }
-            VotingMessage::ExportRequested(idx, target) => {
+            VotingMessage::ExportRequested(idx, format, target) => {
                 if let Some(vote) = self.vote(idx) {
-                    let vote = serde_json::to_string_pretty(vote)
-                        .expect_throw("failed serializing `Vote`");
-                    let data = ExportedData {
-                        ty: ExportedDataType::Vote,
-                        data: vote,
-                    };
-                    let target = target.cast().unwrap_throw();
-                    AppProperties::from_ctx(ctx).onexport.emit((data, target));
+                    let data = ExportedData::new(ExportedDataType::Vote, vote, format);
+                    AppProperties::from_ctx(ctx).export(data, format, &target);
                 }
                 return false;
             }
@@ -349,6 +377,9 @@ impl Component for Voting {
                         Self::default_choice(&self.poll_id, self.poll_state.as_ref(), ctx);
                 }
             }
+            VotingMessage::BoardUpdated => {
+                self.poll_state = self.poll_manager.poll(&self.poll_id);
+            }
             VotingMessage::Done => {
                 let state = self.poll_state.take().expect_throw("no poll state");
                 ctx.props().ondone.emit(state);
@@ -384,7 +415,11 @@ impl Component for Voting {
             html! {
                 <>
                     { self.metadata.view() }
-                    { state.stage().view_nav(PollStage::VOTING_IDX, self.poll_id) }
+                    { if AppProperties::from_ctx(ctx).is_embedded {
+                        html!{}
+                    } else {
+                        state.stage().view_nav(PollStage::VOTING_IDX, self.poll_id)
+                    }}
                     { self.view_poll(state, ctx) }
 
                     { if self.is_readonly {
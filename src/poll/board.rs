@@ -0,0 +1,413 @@
+//! Synchronization of polls with a bulletin board, such as the one provided
+//! by the `elasticpoll-board` server.
+
+use js_sys::Error as JsError;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+use std::{collections::HashMap, error::Error as StdError, fmt, future::Future, pin::Pin, rc::Rc};
+
+use super::{
+    ParticipantApplication, PollId, PollSpec, PollStage, PollState, PublicKey, TallierShare, Vote,
+};
+
+/// Item exchanged via a bulletin board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum BoardItem {
+    Application(ParticipantApplication),
+    Vote(Vote),
+    TallierShare(TallierShare),
+}
+
+impl BoardItem {
+    pub fn kind(&self) -> BoardItemKind {
+        match self {
+            Self::Application(_) => BoardItemKind::Application,
+            Self::Vote(_) => BoardItemKind::Vote,
+            Self::TallierShare(_) => BoardItemKind::TallierShare,
+        }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        match self {
+            Self::Application(application) => &application.public_key,
+            Self::Vote(vote) => vote.public_key(),
+            Self::TallierShare(share) => share.public_key(),
+        }
+    }
+
+    /// Returns our item relevant for the current stage of the poll: the application
+    /// during participant selection, the vote during voting, or the tallier share afterwards.
+    pub fn ours(state: &PollState, our_key: &PublicKey) -> Result<Self, &'static str> {
+        let participant = state
+            .participants()
+            .iter()
+            .find(|p| p.public_key() == our_key)
+            .ok_or("we are not a poll participant")?;
+        match state.stage() {
+            PollStage::Participants { .. } => {
+                Ok(Self::Application(participant.application.clone()))
+            }
+            PollStage::Voting { .. } => participant
+                .vote
+                .as_ref()
+                .map(|vote| Self::Vote(vote.inner.clone()))
+                .ok_or("we have not voted yet"),
+            PollStage::Tallying { .. } | PollStage::Finished => participant
+                .tallier_share
+                .as_ref()
+                .map(|share| Self::TallierShare(share.inner.clone()))
+                .ok_or("we have not submitted a tallier share yet"),
+        }
+    }
+
+    fn is_same_as(&self, other: &Self) -> bool {
+        let this = serde_json::to_value(self).expect_throw("cannot serialize `BoardItem`");
+        let other = serde_json::to_value(other).expect_throw("cannot serialize `BoardItem`");
+        this == other
+    }
+}
+
+/// Kind of a [`BoardItem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardItemKind {
+    Application,
+    Vote,
+    TallierShare,
+}
+
+impl BoardItemKind {
+    pub fn as_human_string(self) -> &'static str {
+        match self {
+            Self::Application => "Application",
+            Self::Vote => "Vote",
+            Self::TallierShare => "Tallier share",
+        }
+    }
+}
+
+/// [`BoardItem`] accepted by the board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardEnvelope {
+    /// 0-based index of the item on the board.
+    pub index: usize,
+    /// Unix timestamp (in milliseconds) when the item was accepted by the board.
+    pub received_at: f64,
+    pub item: BoardItem,
+}
+
+/// Errors that can occur when interacting with a bulletin board.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    /// The board cannot be reached.
+    Network(String),
+    /// The board responded with an error.
+    Http { status: u16, message: String },
+    /// The board response cannot be parsed.
+    Response(String),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(message) => write!(formatter, "cannot reach the board: {message}"),
+            Self::Http { status, message } => {
+                write!(formatter, "board responded with error {status}: {message}")
+            }
+            Self::Response(message) => write!(formatter, "invalid board response: {message}"),
+        }
+    }
+}
+
+impl StdError for BoardError {}
+
+impl BoardError {
+    fn network(err: &JsValue) -> Self {
+        let message = err
+            .dyn_ref::<JsError>()
+            .map_or_else(|| format!("{err:?}"), |err| err.message().into());
+        Self::Network(message)
+    }
+}
+
+/// Future returned by [`BoardTransport`] methods.
+pub type BoardFuture<T> = Pin<Box<dyn Future<Output = Result<T, BoardError>>>>;
+
+/// Transport for exchanging poll items via a bulletin board.
+pub trait BoardTransport {
+    /// Registers the poll with the specified spec on the board. Registering an existing poll
+    /// must not be an error.
+    fn register_poll(&self, spec: &PollSpec) -> BoardFuture<()>;
+
+    /// Returns items for the poll starting from the item with the specified index.
+    fn items(&self, poll_id: &PollId, since: usize) -> BoardFuture<Vec<BoardEnvelope>>;
+
+    /// Submits an item for the poll. The board may reject the item with
+    /// a [`BoardError::Http`] error.
+    fn submit(&self, poll_id: &PollId, item: &BoardItem) -> BoardFuture<()>;
+}
+
+impl fmt::Debug for dyn BoardTransport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_tuple("BoardTransport").finish()
+    }
+}
+
+/// [`BoardTransport`] communicating with a board server via its HTTP / JSON API.
+#[derive(Debug)]
+pub struct HttpBoard {
+    url: String,
+}
+
+impl HttpBoard {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    async fn fetch(method: &str, url: &str, body: Option<&str>) -> Result<String, BoardError> {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            error: String,
+        }
+
+        let init = RequestInit::new();
+        init.set_method(method);
+        if let Some(body) = body {
+            init.set_body(&JsValue::from_str(body));
+        }
+        let request =
+            Request::new_with_str_and_init(url, &init).map_err(|err| BoardError::network(&err))?;
+        if body.is_some() {
+            request
+                .headers()
+                .set("Content-Type", "application/json")
+                .map_err(|err| BoardError::network(&err))?;
+        }
+
+        let window = web_sys::window().expect_throw("no Window");
+        let response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|err| BoardError::network(&err))?;
+        let response: Response = response.unchecked_into();
+        let text = response.text().map_err(|err| BoardError::network(&err))?;
+        let text = JsFuture::from(text)
+            .await
+            .map_err(|err| BoardError::network(&err))?
+            .as_string()
+            .unwrap_or_default();
+
+        if response.ok() {
+            Ok(text)
+        } else {
+            let message = serde_json::from_str::<ErrorBody>(&text).map_or(text, |body| body.error);
+            Err(BoardError::Http {
+                status: response.status(),
+                message,
+            })
+        }
+    }
+}
+
+impl BoardTransport for HttpBoard {
+    fn register_poll(&self, spec: &PollSpec) -> BoardFuture<()> {
+        let url = format!("{}/polls", self.url);
+        let body = serde_json::to_string(spec).expect_throw("cannot serialize `PollSpec`");
+        Box::pin(async move {
+            Self::fetch("POST", &url, Some(&body)).await?;
+            Ok(())
+        })
+    }
+
+    fn items(&self, poll_id: &PollId, since: usize) -> BoardFuture<Vec<BoardEnvelope>> {
+        let url = format!("{}/polls/{poll_id}/items?since={since}", self.url);
+        Box::pin(async move {
+            let text = Self::fetch("GET", &url, None).await?;
+            serde_json::from_str(&text).map_err(|err| BoardError::Response(err.to_string()))
+        })
+    }
+
+    fn submit(&self, poll_id: &PollId, item: &BoardItem) -> BoardFuture<()> {
+        let url = format!("{}/polls/{poll_id}/items", self.url);
+        let body = serde_json::to_string(item).expect_throw("cannot serialize `BoardItem`");
+        Box::pin(async move {
+            Self::fetch("POST", &url, Some(&body)).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Status of a single item after syncing it with the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemStatus {
+    /// Our item was submitted to the board.
+    Submitted,
+    /// The item is already present (on the board for submitted items, or in the poll
+    /// for pulled items).
+    AlreadyPresent,
+    /// The pulled item was verified and added to the poll.
+    Added,
+    /// The pulled vote is superseded by a later vote from the same participant.
+    Superseded,
+    /// The pulled item is not applicable at the current poll stage.
+    Skipped(&'static str),
+    /// The item was rejected by the board or by local verification.
+    Rejected(String),
+}
+
+/// Report about syncing a single item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemReport {
+    pub kind: BoardItemKind,
+    pub public_key: PublicKey,
+    pub status: ItemStatus,
+}
+
+impl ItemReport {
+    fn new(item: &BoardItem, status: ItemStatus) -> Self {
+        Self {
+            kind: item.kind(),
+            public_key: item.public_key().clone(),
+            status,
+        }
+    }
+}
+
+/// Client synchronizing polls with a bulletin board.
+#[derive(Debug, Clone)]
+pub struct BoardClient {
+    transport: Rc<dyn BoardTransport>,
+}
+
+impl BoardClient {
+    pub fn new(transport: Rc<dyn BoardTransport>) -> Self {
+        Self { transport }
+    }
+
+    /// Creates a client for the board server at the specified URL.
+    pub fn http(url: &str) -> Self {
+        Self::new(Rc::new(HttpBoard::new(url)))
+    }
+
+    /// Submits our `item` to the board unless it is already there. The poll is registered
+    /// on the board if necessary. Items rejected by the board are reported
+    /// with the [`ItemStatus::Rejected`] status.
+    pub async fn push(&self, spec: &PollSpec, item: BoardItem) -> Result<ItemReport, BoardError> {
+        let poll_id = PollId::for_spec(spec);
+        self.transport.register_poll(spec).await?;
+        let items = self.transport.items(&poll_id, 0).await?;
+        if items.iter().any(|envelope| envelope.item.is_same_as(&item)) {
+            return Ok(ItemReport::new(&item, ItemStatus::AlreadyPresent));
+        }
+
+        let status = match self.transport.submit(&poll_id, &item).await {
+            Ok(()) => ItemStatus::Submitted,
+            Err(BoardError::Http { message, .. }) => ItemStatus::Rejected(message),
+            Err(err) => return Err(err),
+        };
+        Ok(ItemReport::new(&item, status))
+    }
+
+    /// Pulls all items for the poll from the board. The items should be applied to the poll
+    /// with [`Self::apply_items()`].
+    pub async fn pull(&self, poll_id: &PollId) -> Result<Vec<BoardItem>, BoardError> {
+        let items = self.transport.items(poll_id, 0).await?;
+        Ok(items.into_iter().map(|envelope| envelope.item).collect())
+    }
+
+    /// Applies items pulled from the board to the poll, verifying them in the same way
+    /// as items added manually.
+    pub fn apply_items(
+        poll_id: &PollId,
+        state: &mut PollState,
+        items: Vec<BoardItem>,
+    ) -> Vec<ItemReport> {
+        // Only the latest vote from each participant is relevant.
+        let mut latest_votes = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            if let BoardItem::Vote(vote) = item {
+                latest_votes.insert(vote.public_key().as_bytes().to_vec(), i);
+            }
+        }
+
+        let reports = items.into_iter().enumerate().map(|(i, item)| {
+            let status = if let BoardItem::Vote(vote) = &item {
+                if latest_votes[vote.public_key().as_bytes()] == i {
+                    Self::apply_item(poll_id, state, &item)
+                } else {
+                    ItemStatus::Superseded
+                }
+            } else {
+                Self::apply_item(poll_id, state, &item)
+            };
+            ItemReport::new(&item, status)
+        });
+        reports.collect()
+    }
+
+    fn apply_item(poll_id: &PollId, state: &mut PollState, item: &BoardItem) -> ItemStatus {
+        let participant = state
+            .participants()
+            .iter()
+            .find(|p| p.public_key() == item.public_key());
+        let existing_item = participant.and_then(|participant| match item {
+            BoardItem::Application(_) => {
+                Some(BoardItem::Application(participant.application.clone()))
+            }
+            BoardItem::Vote(_) => participant
+                .vote
+                .as_ref()
+                .map(|vote| BoardItem::Vote(vote.inner.clone())),
+            BoardItem::TallierShare(_) => participant
+                .tallier_share
+                .as_ref()
+                .map(|share| BoardItem::TallierShare(share.inner.clone())),
+        });
+        if existing_item.is_some_and(|existing| existing.is_same_as(item)) {
+            return ItemStatus::AlreadyPresent;
+        }
+
+        let stage = state.stage();
+        let result = match item {
+            BoardItem::Application(application) => {
+                if !matches!(stage, PollStage::Participants { .. }) {
+                    return ItemStatus::Rejected("participants are finalized".to_owned());
+                }
+                application.validate(poll_id).map(|()| {
+                    state.insert_participant(application.clone());
+                })
+            }
+            BoardItem::Vote(vote) => match stage {
+                PollStage::Participants { .. } => {
+                    return ItemStatus::Skipped("participants are not finalized");
+                }
+                PollStage::Voting { .. } => state
+                    .insert_vote(poll_id, vote.clone())
+                    .map_err(|err| Box::new(err) as Box<dyn StdError>),
+                PollStage::Tallying { .. } | PollStage::Finished => {
+                    return ItemStatus::Rejected("votes are finalized".to_owned());
+                }
+            },
+            BoardItem::TallierShare(share) => match stage {
+                PollStage::Participants { .. } | PollStage::Voting { .. } => {
+                    return ItemStatus::Skipped("votes are not finalized");
+                }
+                PollStage::Tallying { .. } => state
+                    .insert_tallier_share(poll_id, share.clone())
+                    .map_err(|err| Box::new(err) as Box<dyn StdError>),
+                PollStage::Finished => {
+                    return ItemStatus::Rejected("poll is finished".to_owned());
+                }
+            },
+        };
+
+        match result {
+            Ok(()) => ItemStatus::Added,
+            Err(err) => ItemStatus::Rejected(err.to_string()),
+        }
+    }
+}
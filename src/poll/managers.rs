@@ -1,15 +1,42 @@
 //! [`PollManager`] and [`SecretsManager`].
 
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes128Gcm, Nonce,
+};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use elastic_elgamal::{group::ScalarOps, SecretKey};
+use elasticpoll_core::keys::{derive_poll_keys, wipe_rng};
 use js_sys::{Error, JsString, Uint8Array};
-use rand_core::OsRng;
+use rand_core::{OsRng, RngCore};
 use secret_tree::{SecretTree, Seed};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use wasm_bindgen_futures::JsFuture;
+use yew::Callback;
+use zeroize::Zeroizing;
 
-use std::{cell::RefCell, collections::HashMap, future::Future, pin::Pin, rc::Rc, str::FromStr};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    ops,
+    pin::Pin,
+    rc::Rc,
+    str::FromStr,
+};
 
-use super::{Keypair, PollId, PollSpec, PollState, PublicKey};
-use crate::{js::PasswordBasedCrypto, utils::local_storage};
+use super::{
+    mnemonic::{decode_mnemonic, encode_mnemonic, random_passphrase},
+    shamir::SecretShare,
+    Group, Keypair, Participation, PollId, PollSpec, PollStage, PollState, PublicKey,
+};
+use crate::{
+    crypto::{Kdf, PasswordBox},
+    js::PasswordBasedCrypto,
+    utils::local_storage,
+};
 
 #[derive(Debug)]
 pub struct PollManager {
@@ -99,12 +126,77 @@ impl PollManager {
             .remove_item(&key)
             .expect_throw("cannot remove `PollState` from local storage");
     }
+
+    /// Remembers participation of the active profile of `secrets` for polls lacking this info
+    /// (e.g., joined before participation was tracked). This requires `secrets` to be unlocked.
+    pub fn remember_participation(&self, secrets: &SecretManager) {
+        if secrets.status() != Some(SecretManagerStatus::Unlocked) {
+            return;
+        }
+        let profile = secrets.active_profile();
+        for (id, mut state) in self.polls() {
+            let is_incomplete = state.participation().map_or(true, |participation| {
+                participation.profile == profile && participation.public_key.is_none()
+            });
+            if !is_incomplete {
+                continue;
+            }
+            let Some(public_key) = secrets.public_key_for_poll(&id) else {
+                continue;
+            };
+            if state.has_participant(&public_key) {
+                state.set_participation(Some(Participation {
+                    profile: profile.clone(),
+                    public_key: Some(public_key),
+                }));
+                self.update_poll(&id, &state);
+            }
+        }
+    }
+}
+
+/// Unlocked secret. The seed is zeroized when the secret is dropped.
+#[derive(Debug)]
+struct UnlockedSecret(SecretTree);
+
+impl UnlockedSecret {
+    fn new(tree: SecretTree) -> Self {
+        #[cfg(feature = "testing")]
+        crate::testing::LIVE_SECRETS.with(|count| count.set(count.get() + 1));
+        Self(tree)
+    }
+
+    /// Creates a secret from the bytes supplied by the host, zeroizing the intermediate buffer.
+    fn from_bytes(bytes: &Uint8Array) -> Self {
+        let mut seed = Zeroizing::new([0_u8; 32]);
+        bytes.copy_to(&mut *seed);
+        Self::from_seed(&seed)
+    }
+
+    fn from_seed(seed: &[u8; 32]) -> Self {
+        Self::new(SecretTree::from_seed(Seed::from(seed)))
+    }
+}
+
+impl ops::Deref for UnlockedSecret {
+    type Target = SecretTree;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Drop for UnlockedSecret {
+    fn drop(&mut self) {
+        crate::testing::LIVE_SECRETS.with(|count| count.set(count.get() - 1));
+    }
 }
 
 #[derive(Debug)]
 enum SecretManagerState {
     Locked,
-    Unlocked(SecretTree),
+    Unlocked(UnlockedSecret),
 }
 
 impl Default for SecretManagerState {
@@ -119,39 +211,277 @@ pub enum SecretManagerStatus {
     Unlocked,
 }
 
+/// Policy for automatically locking the secret manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockPolicy {
+    /// Inactivity period (in minutes) after which the secret is locked. `None` disables
+    /// locking on inactivity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_minutes: Option<u32>,
+    /// Whether to lock the secret once the page is hidden (e.g., when switching browser tabs).
+    #[serde(default)]
+    pub lock_on_hide: bool,
+}
+
+impl Default for LockPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout_minutes: Some(15),
+            lock_on_hide: false,
+        }
+    }
+}
+
+impl LockPolicy {
+    /// Idle timeouts (in minutes) offered in the UI.
+    pub const IDLE_TIMEOUT_OPTIONS: [u32; 4] = [5, 15, 30, 60];
+
+    /// Returns the idle timeout in milliseconds.
+    pub fn idle_timeout_ms(&self) -> Option<f64> {
+        self.idle_timeout_minutes
+            .map(|minutes| f64::from(minutes) * 60_000.0)
+    }
+}
+
+/// Number of words in one-time passphrases for exported poll keys.
+pub const POLL_KEY_PASSPHRASE_LEN: usize = 6;
+/// Length of the nonce for imported poll keys wrapped with the profile secret.
+const POLL_KEY_NONCE_LEN: usize = 12;
+
+/// Keys for a single poll sealed with a one-time passphrase. Allows continuing the poll
+/// on another device without exporting the entire secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedPollKey {
+    /// ID of the poll.
+    pub poll_id: PollId,
+    /// Public key of the poll participant.
+    pub public_key: PublicKey,
+    /// Secret key sealed with the passphrase.
+    pub sealed_key: PasswordBox,
+}
+
+/// Name of the default profile. For compatibility, the secret for this profile is stored
+/// under the same key as before profiles were introduced.
+pub const DEFAULT_PROFILE: &str = "default";
+/// Maximum length of a profile name (in chars).
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
 /// Manager of application secrets.
+///
+/// The manager supports multiple named profiles, each with its own password-sealed secret.
+/// Only the active profile can be unlocked and used to derive keys.
 #[derive(Debug)]
 pub struct SecretManager {
-    storage_key: &'static str,
+    storage_key_prefix: &'static str,
+    profile: RefCell<String>,
     state: RefCell<SecretManagerState>,
     pk_cache: RefCell<HashMap<PollId, PublicKey>>,
+    /// Incremented on each lock; used to discard stale cached secrets.
+    lock_generation: Cell<u32>,
+    onlock: RefCell<Callback<()>>,
     crypto: Rc<dyn PasswordBasedCrypto>,
 }
 
 impl SecretManager {
     pub fn new(crypto: Rc<dyn PasswordBasedCrypto>) -> Self {
+        let storage_key_prefix = "elastic_poll";
+        let profile = local_storage()
+            .get_item(&format!("{storage_key_prefix}::profile"))
+            .expect_throw("failed getting active profile")
+            .filter(|profile| Self::check_profile_name(profile).is_ok());
+
         Self {
-            storage_key: "elastic_poll::secret",
+            storage_key_prefix,
+            profile: RefCell::new(profile.unwrap_or_else(|| DEFAULT_PROFILE.to_owned())),
             state: RefCell::default(),
             pk_cache: RefCell::default(),
+            lock_generation: Cell::new(0),
+            onlock: RefCell::default(),
             crypto,
         }
     }
 
-    fn persist(&self, box_json: &str) {
+    fn secret_storage_key(&self, profile: &str) -> String {
+        if profile == DEFAULT_PROFILE {
+            format!("{}::secret", self.storage_key_prefix)
+        } else {
+            format!("{}::secret::{profile}", self.storage_key_prefix)
+        }
+    }
+
+    /// Returns the key for the host-side cache of the profile secret.
+    fn cache_key(profile: &str) -> String {
+        if profile == DEFAULT_PROFILE {
+            "secret_seed".to_owned()
+        } else {
+            format!("secret_seed::{profile}")
+        }
+    }
+
+    fn persist(&self, profile: &str, box_json: &str) {
         local_storage()
-            .set_item(self.storage_key, box_json)
+            .set_item(&self.secret_storage_key(profile), box_json)
             .expect_throw("cannot persist encrypted secret");
     }
 
     fn encrypted_secret(&self) -> Option<String> {
         local_storage()
-            .get_item(self.storage_key)
+            .get_item(&self.secret_storage_key(&self.profile.borrow()))
             .expect_throw("failed getting encrypted secret")
     }
 
-    fn unlock_with_secret(&self, secret: SecretTree) {
+    /// Unlocks the manager if `profile` is still active.
+    fn unlock_with_secret(&self, profile: &str, secret: UnlockedSecret) {
+        if *self.profile.borrow() != profile {
+            return; // The profile was switched while the secret was being obtained
+        }
         *self.state.borrow_mut() = SecretManagerState::Unlocked(secret);
+        // Cached keys may correspond to a different secret.
+        self.pk_cache.borrow_mut().clear();
+    }
+
+    fn open_box(
+        &self,
+        password: &str,
+        box_json: &str,
+    ) -> impl Future<Output = Result<UnlockedSecret, Error>> {
+        let cache_key = Self::cache_key(&self.profile.borrow());
+        let task = self.crypto.open(password, box_json, &cache_key);
+        async move {
+            JsFuture::from(task)
+                .await
+                .map(|secret_bytes| {
+                    let secret_bytes = secret_bytes
+                        .dyn_into::<Uint8Array>()
+                        .expect_throw("unexpected open_fn output");
+                    UnlockedSecret::from_bytes(&secret_bytes)
+                })
+                .map_err(|err| {
+                    err.dyn_into::<Error>()
+                        .unwrap_or_else(|_| Error::new("(unknown error)"))
+                })
+        }
+    }
+
+    /// Returns the name of the active profile.
+    pub fn active_profile(&self) -> String {
+        self.profile.borrow().clone()
+    }
+
+    /// Lists names of profiles with a stored secret, together with the active profile.
+    /// The default profile goes first; other profiles are sorted alphabetically.
+    pub fn profiles(&self) -> Vec<String> {
+        let local_storage = local_storage();
+        let profile_key_prefix = format!("{}::secret::", self.storage_key_prefix);
+        // This iteration protocol assumes that the storage is not modified concurrently.
+        let len = local_storage
+            .length()
+            .expect_throw("cannot obtain local storage length");
+        let mut profiles: Vec<_> = (0..len)
+            .filter_map(|idx| {
+                let key = local_storage
+                    .key(idx)
+                    .expect_throw("cannot obtain key from storage")?;
+                let profile = key.strip_prefix(&profile_key_prefix)?;
+                Self::check_profile_name(profile)
+                    .is_ok()
+                    .then(|| profile.to_owned())
+            })
+            .collect();
+
+        let has_default_secret = local_storage
+            .get_item(&self.secret_storage_key(DEFAULT_PROFILE))
+            .expect_throw("failed getting encrypted secret")
+            .is_some();
+        if has_default_secret {
+            profiles.push(DEFAULT_PROFILE.to_owned());
+        }
+        profiles.push(self.active_profile());
+
+        profiles.sort_unstable_by(|name, other| {
+            (name != DEFAULT_PROFILE, name).cmp(&(other != DEFAULT_PROFILE, other))
+        });
+        profiles.dedup();
+        profiles
+    }
+
+    /// Checks whether the provided profile name is valid.
+    pub fn check_profile_name(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            Err("Profile name cannot be empty".to_owned())
+        } else if name.chars().count() > MAX_PROFILE_NAME_LEN {
+            Err(format!(
+                "Profile name cannot be longer than {MAX_PROFILE_NAME_LEN} chars"
+            ))
+        } else if !name
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_')
+        {
+            Err("Profile name may only contain letters, digits, `-` and `_`".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Switches to the specified profile, locking the manager. If the profile does not exist,
+    /// it will be created once a secret is created for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid profile name.
+    pub fn switch_profile(&self, name: &str) {
+        if let Err(err) = Self::check_profile_name(name) {
+            panic!("invalid profile name: {err}");
+        }
+        if *self.profile.borrow() == name {
+            return;
+        }
+
+        self.lock();
+        *self.profile.borrow_mut() = name.to_owned();
+        local_storage()
+            .set_item(&format!("{}::profile", self.storage_key_prefix), name)
+            .expect_throw("cannot persist active profile");
+    }
+
+    /// Returns the policy for automatically locking the secret.
+    pub fn lock_policy(&self) -> LockPolicy {
+        local_storage()
+            .get_item(&format!("{}::lock_policy", self.storage_key_prefix))
+            .expect_throw("failed getting lock policy")
+            .and_then(|policy| serde_json::from_str(&policy).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_lock_policy(&self, policy: LockPolicy) {
+        let policy = serde_json::to_string(&policy).expect_throw("cannot serialize lock policy");
+        local_storage()
+            .set_item(
+                &format!("{}::lock_policy", self.storage_key_prefix),
+                &policy,
+            )
+            .expect_throw("cannot persist lock policy");
+    }
+
+    /// Sets a callback invoked each time an unlocked secret gets locked.
+    pub fn set_onlock(&self, callback: Callback<()>) {
+        *self.onlock.borrow_mut() = callback;
+    }
+
+    /// Locks the secret if it is unlocked. The in-memory secret is dropped (which zeroes
+    /// its seed), derived keys are forgotten and the host is asked to evict the cached secret,
+    /// so that it cannot be silently restored.
+    pub fn lock(&self) {
+        self.lock_generation
+            .set(self.lock_generation.get().wrapping_add(1));
+        self.crypto.forget(&Self::cache_key(&self.profile.borrow()));
+        self.pk_cache.borrow_mut().clear();
+        let prev_state = self.state.replace(SecretManagerState::Locked);
+        if matches!(prev_state, SecretManagerState::Unlocked(_)) {
+            drop(prev_state);
+            let onlock = self.onlock.borrow().clone();
+            onlock.emit(());
+        }
     }
 
     pub fn status(&self) -> Option<SecretManagerStatus> {
@@ -167,20 +497,23 @@ impl SecretManager {
 
     /// Returns `true` if the load was successful and `false` otherwise.
     pub fn try_load_cached(self: &Rc<Self>) -> impl Future<Output = bool> {
-        let task = self.crypto.cached();
+        let profile = self.active_profile();
+        let lock_generation = self.lock_generation.get();
+        let task = self.crypto.cached(&Self::cache_key(&profile));
         let this = Rc::clone(self);
         async move {
             if let Ok(maybe_secret_bytes) = JsFuture::from(task).await {
                 if maybe_secret_bytes.is_falsy() {
                     return false; // no cached value
                 }
+                if this.lock_generation.get() != lock_generation {
+                    return false; // the secret was locked while loading the cached value
+                }
                 let secret_bytes = maybe_secret_bytes
                     .dyn_into::<Uint8Array>()
                     .expect_throw("unexpected cached output");
-                let mut seed = [0_u8; 32];
-                secret_bytes.copy_to(&mut seed);
-                this.unlock_with_secret(SecretTree::from_seed(Seed::from(&seed)));
-                true
+                this.unlock_with_secret(&profile, UnlockedSecret::from_bytes(&secret_bytes));
+                this.status() == Some(SecretManagerStatus::Unlocked)
             } else {
                 // TODO: log errors?
                 false
@@ -191,13 +524,28 @@ impl SecretManager {
     pub fn encrypt_new_secret(
         self: &Rc<Self>,
         password: &str,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let secret = UnlockedSecret::new(SecretTree::new(&mut OsRng));
+        self.seal_secret(self.active_profile(), secret, password)
+    }
+
+    /// Seals `secret` with `password`, persists the sealed box for `profile` and unlocks
+    /// the manager (if `profile` is still active).
+    fn seal_secret(
+        self: &Rc<Self>,
+        profile: String,
+        secret: UnlockedSecret,
+        password: &str,
     ) -> impl Future<Output = Result<(), Error>> {
         // We use pinning to enable to pass a ref `&[u8]` of the seed to the host
         // (i.e., not copying seed bytes to a `Box<[u8]>`. If pinning is not used,
         // the seed is moved to the closure and will lead to `seal` encrypting garbage
         // instead of the seed.
-        let secret = Box::pin(SecretTree::new(&mut OsRng));
-        let task = self.crypto.seal(password, secret.seed().expose_secret());
+        let secret = Box::pin(secret);
+        let cache_key = Self::cache_key(&profile);
+        let task = self
+            .crypto
+            .seal(password, secret.seed().expose_secret(), &cache_key);
 
         let this = Rc::clone(self);
         async move {
@@ -207,8 +555,8 @@ impl SecretManager {
                     let box_json = box_json
                         .dyn_into::<JsString>()
                         .expect_throw("unexpected seal_fn output");
-                    this.persist(&String::from(box_json));
-                    this.unlock_with_secret(*Pin::into_inner(secret));
+                    this.persist(&profile, &String::from(box_json));
+                    this.unlock_with_secret(&profile, *Pin::into_inner(secret));
                 })
                 .map_err(|err| {
                     err.dyn_into::<Error>()
@@ -221,28 +569,191 @@ impl SecretManager {
         let encrypted_secret = self
             .encrypted_secret()
             .expect_throw("called `unlock` without stored secret");
-        let task = self.crypto.open(password, &encrypted_secret);
+        let task = self.open_box(password, &encrypted_secret);
+        let profile = self.active_profile();
 
         let this = Rc::clone(self);
         async move {
-            JsFuture::from(task)
-                .await
-                .map(|secret_bytes| {
-                    let secret_bytes = secret_bytes
-                        .dyn_into::<Uint8Array>()
-                        .expect_throw("unexpected open_fn output");
-                    let mut seed = [0_u8; 32];
-                    secret_bytes.copy_to(&mut seed);
-                    this.unlock_with_secret(SecretTree::from_seed(Seed::from(&seed)));
-                })
-                .map_err(|err| {
-                    err.dyn_into::<Error>()
-                        .unwrap_or_else(|_| Error::new("(unknown error)"))
-                })
+            let secret = task.await?;
+            this.unlock_with_secret(&profile, secret);
+            Ok(())
         }
     }
 
-    /// Returns `None` if the manager is not unlocked.
+    /// Changes the password for the stored secret. The secret is re-sealed with
+    /// `new_password`; thus, keys for all polls remain the same. The stored secret is replaced
+    /// only if it can be opened with `old_password` and successfully re-sealed.
+    pub fn change_password(
+        self: &Rc<Self>,
+        old_password: &str,
+        new_password: &str,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let encrypted_secret = self
+            .encrypted_secret()
+            .expect_throw("called `change_password` without stored secret");
+        let task = self.open_box(old_password, &encrypted_secret);
+        let new_password = Zeroizing::new(new_password.to_owned());
+        let profile = self.active_profile();
+
+        let this = Rc::clone(self);
+        async move {
+            let secret = task.await?;
+            if this.active_profile() != profile {
+                // Otherwise, the secret would be persisted for the wrong profile.
+                return Err(Error::new(
+                    "Profile was switched while changing the password",
+                ));
+            }
+            this.seal_secret(profile, secret, &new_password).await
+        }
+    }
+
+    /// Returns the password-encrypted secret box that can be used as a backup,
+    /// or `None` if there is no secret.
+    pub fn backup(&self) -> Option<String> {
+        self.encrypted_secret()
+    }
+
+    /// Checks whether our key for the poll differs from the key used to join it, which means
+    /// that the secret of the active profile was replaced. Returns `false` if this cannot
+    /// be checked, e.g., if the secret is locked or the poll was joined by another profile.
+    pub fn has_key_mismatch(&self, poll_id: &PollId, state: &PollState) -> bool {
+        let Some(participation) = state.participation() else {
+            return false;
+        };
+        let Some(joined_key) = &participation.public_key else {
+            return false;
+        };
+        if participation.profile != self.active_profile() || !state.has_participant(joined_key) {
+            return false; // We've left the poll, or joined it with another profile
+        }
+        self.public_key_for_poll(poll_id)
+            .map_or(false, |key| key != *joined_key)
+    }
+
+    /// Returns IDs of polls for which [`Self::has_key_mismatch()`] holds.
+    pub fn polls_with_key_mismatch(&self, polls: &[(PollId, PollState)]) -> Vec<PollId> {
+        polls
+            .iter()
+            .filter(|(id, state)| self.has_key_mismatch(id, state))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns IDs of unfinished polls that may become orphaned (i.e., impossible to vote in
+    /// or tally) if the secret of the active profile is replaced. If the secret is locked,
+    /// all unfinished polls not joined by other profiles are returned since it is impossible
+    /// to check participation.
+    pub fn polls_at_risk(&self, polls: &[(PollId, PollState)]) -> Vec<PollId> {
+        if self.encrypted_secret().is_none() {
+            return vec![];
+        }
+        let is_unlocked = matches!(*self.state.borrow(), SecretManagerState::Unlocked(_));
+        let profile = self.active_profile();
+
+        let unfinished_polls = polls.iter().filter(|(_, state)| {
+            let is_other_profile = state
+                .participation()
+                .map_or(false, |participation| participation.profile != profile);
+            !is_other_profile && !matches!(state.stage(), PollStage::Finished)
+        });
+        let polls_at_risk = unfinished_polls.filter(|(id, state)| {
+            if is_unlocked {
+                let public_key = self.public_key_for_poll(id);
+                public_key.map_or(false, |key| state.has_participant(&key))
+            } else {
+                true
+            }
+        });
+        polls_at_risk.map(|(id, _)| *id).collect()
+    }
+
+    /// Restores the secret from a backup produced by [`Self::backup()`]. The backup
+    /// is only persisted if it can be opened with the provided `password`.
+    pub fn restore(
+        self: &Rc<Self>,
+        password: &str,
+        box_json: &str,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let box_json = box_json.trim().to_owned();
+        let is_object = serde_json::from_str::<serde_json::Value>(&box_json)
+            .map_or(false, |value| value.is_object());
+        let task = is_object.then(|| self.open_box(password, &box_json));
+        let profile = self.active_profile();
+
+        let this = Rc::clone(self);
+        async move {
+            let task = task.ok_or_else(|| Error::new("Backup is not a password-encrypted box"))?;
+            let secret = task.await?;
+            this.persist(&profile, &box_json);
+            this.unlock_with_secret(&profile, secret);
+            Ok(())
+        }
+    }
+
+    /// Returns the mnemonic phrase encoding the secret seed, or `None` if the manager
+    /// is not unlocked.
+    pub fn mnemonic(&self) -> Option<Zeroizing<String>> {
+        match &*self.state.borrow() {
+            SecretManagerState::Unlocked(tree) => {
+                Some(encode_mnemonic(tree.seed().expose_secret()))
+            }
+            SecretManagerState::Locked => None,
+        }
+    }
+
+    /// Recovers the secret from a mnemonic phrase produced by [`Self::mnemonic()`]
+    /// and seals it with a new `password`.
+    pub fn restore_from_mnemonic(
+        self: &Rc<Self>,
+        phrase: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let task = decode_mnemonic(phrase).map(|seed| {
+            let secret = UnlockedSecret::from_seed(&seed);
+            self.seal_secret(self.active_profile(), secret, password)
+        });
+
+        async move {
+            match task {
+                Ok(task) => task.await,
+                Err(err) => Err(Error::new(&format!("Invalid recovery phrase: {err}"))),
+            }
+        }
+    }
+
+    /// Splits the secret seed into `count` shares with the specified `threshold`
+    /// for social recovery. Returns `None` if the manager is not unlocked.
+    pub fn split_secret(&self, threshold: u8, count: u8) -> Option<Vec<SecretShare>> {
+        match &*self.state.borrow() {
+            SecretManagerState::Unlocked(tree) => {
+                let seed = tree.seed().expose_secret();
+                Some(SecretShare::split(seed, threshold, count, &mut OsRng))
+            }
+            SecretManagerState::Locked => None,
+        }
+    }
+
+    /// Recovers the secret from whitespace-separated shares produced by
+    /// [`Self::split_secret()`] and seals it with a new `password`.
+    pub fn restore_from_shares(
+        self: &Rc<Self>,
+        shares: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let seed = SecretShare::parse_all(shares).and_then(|shares| SecretShare::combine(&shares));
+        let task = seed.map(|seed| self.seal_secret(UnlockedSecret::from_seed(&seed), password));
+
+        async move {
+            match task {
+                Ok(task) => task.await,
+                Err(err) => Err(Error::new(&format!("Invalid shares: {err}"))),
+            }
+        }
+    }
+
+    /// Returns keys for the poll: either imported with [`Self::import_poll_key()`],
+    /// or derived from the secret. Returns `None` if the manager is not unlocked.
     pub fn keys_for_poll(&self, poll_id: &PollId) -> Option<Keypair> {
         let state = self.state.borrow();
         let secret = match &*state {
@@ -250,8 +761,9 @@ impl SecretManager {
             SecretManagerState::Locked => return None,
         };
 
-        let child = secret.digest(&poll_id.0);
-        let keypair = Keypair::generate(&mut child.rng());
+        let keypair = self
+            .imported_keys(secret, poll_id)
+            .unwrap_or_else(|| derive_poll_keys(secret, poll_id));
         self.pk_cache
             .borrow_mut()
             .insert(*poll_id, keypair.public().clone());
@@ -263,4 +775,143 @@ impl SecretManager {
         let pk = self.pk_cache.borrow().get(poll_id).cloned();
         pk.or_else(|| self.keys_for_poll(poll_id).map(|keys| keys.into_tuple().0))
     }
+
+    fn poll_key_storage_key(&self, profile: &str, poll_id: &PollId) -> String {
+        format!(
+            "{}::poll_key::{profile}::{poll_id}",
+            self.storage_key_prefix
+        )
+    }
+
+    /// Derives the cipher used to wrap imported keys for the poll with the profile secret.
+    fn poll_key_cipher(secret: &SecretTree, poll_id: &PollId) -> Aes128Gcm {
+        let mut hasher = Sha256::new();
+        hasher.update(b"elastic_poll::poll_key");
+        hasher.update(poll_id.as_bytes());
+        let digest: [u8; 32] = hasher.finalize().into();
+
+        let mut rng = secret.digest(&digest).rng();
+        let mut key = Zeroizing::new([0_u8; 16]);
+        rng.fill_bytes(&mut *key);
+        wipe_rng(&mut rng);
+        Aes128Gcm::new_from_slice(&*key).expect_throw("invalid key length")
+    }
+
+    /// Loads keys imported for the poll by the active profile. Returns `None` if there are
+    /// no such keys, or they cannot be unwrapped (e.g., because the secret was replaced).
+    fn imported_keys(&self, secret: &SecretTree, poll_id: &PollId) -> Option<Keypair> {
+        let storage_key = self.poll_key_storage_key(&self.profile.borrow(), poll_id);
+        let wrapped = local_storage()
+            .get_item(&storage_key)
+            .expect_throw("failed getting imported poll key")?;
+        let wrapped = Base64UrlUnpadded::decode_vec(&wrapped).ok()?;
+        if wrapped.len() < POLL_KEY_NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = wrapped.split_at(POLL_KEY_NONCE_LEN);
+        let cipher = Self::poll_key_cipher(secret, poll_id);
+        let secret_bytes = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+        keypair_from_bytes(&Zeroizing::new(secret_bytes))
+    }
+
+    /// Checks whether the active profile uses imported keys for the poll.
+    pub fn has_imported_key(&self, poll_id: &PollId) -> bool {
+        let storage_key = self.poll_key_storage_key(&self.profile.borrow(), poll_id);
+        local_storage()
+            .get_item(&storage_key)
+            .expect_throw("failed getting imported poll key")
+            .is_some()
+    }
+
+    /// Exports keys for the poll sealed with a freshly generated one-time passphrase,
+    /// which is returned together with the sealed keys. Returns `None` if the manager
+    /// is not unlocked.
+    pub fn export_poll_key(
+        &self,
+        poll_id: &PollId,
+    ) -> Option<(ExportedPollKey, Zeroizing<String>)> {
+        let keypair = self.keys_for_poll(poll_id)?;
+        let passphrase = random_passphrase(&mut OsRng, POLL_KEY_PASSPHRASE_LEN);
+        let secret_bytes = secret_key_bytes(&keypair);
+        let sealed_key = PasswordBox::seal(&passphrase, &*secret_bytes, Kdf::default(), &mut OsRng)
+            .expect_throw("cannot seal poll key");
+
+        let exported = ExportedPollKey {
+            poll_id: *poll_id,
+            public_key: keypair.public().clone(),
+            sealed_key,
+        };
+        Some((exported, passphrase))
+    }
+
+    /// Imports keys for the poll exported on another device with [`Self::export_poll_key()`].
+    /// The keys are stored wrapped with the secret of the active profile and are used
+    /// instead of the derived keys for this poll.
+    pub fn import_poll_key(
+        &self,
+        poll_id: &PollId,
+        exported: &ExportedPollKey,
+        passphrase: &str,
+    ) -> Result<(), Error> {
+        if exported.poll_id != *poll_id {
+            return Err(Error::new("The key was exported for another poll"));
+        }
+        let state = self.state.borrow();
+        let SecretManagerState::Unlocked(secret) = &*state else {
+            return Err(Error::new("The secret is locked"));
+        };
+
+        // Normalize the passphrase in the same way as mnemonic phrases.
+        let passphrase = Zeroizing::new(passphrase.to_lowercase());
+        let passphrase =
+            Zeroizing::new(passphrase.split_whitespace().collect::<Vec<_>>().join(" "));
+        let secret_bytes = exported
+            .sealed_key
+            .open(&passphrase)
+            .map_err(|err| Error::new(&format!("Cannot open key: {err}")))?;
+        let keypair = keypair_from_bytes(&secret_bytes)
+            .filter(|keypair| *keypair.public() == exported.public_key)
+            .ok_or_else(|| Error::new("Exported key is corrupted"))?;
+
+        let mut nonce = [0_u8; POLL_KEY_NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = Self::poll_key_cipher(secret, poll_id);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                secret_key_bytes(&keypair).as_slice(),
+            )
+            .expect_throw("cannot wrap poll key");
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&ciphertext);
+
+        let storage_key = self.poll_key_storage_key(&self.profile.borrow(), poll_id);
+        local_storage()
+            .set_item(&storage_key, &Base64UrlUnpadded::encode_string(&wrapped))
+            .expect_throw("cannot persist imported poll key");
+        self.pk_cache.borrow_mut().remove(poll_id);
+        Ok(())
+    }
+
+    /// Removes keys imported for the poll by all profiles, e.g., when the poll is removed.
+    pub fn remove_imported_keys(&self, poll_id: &PollId) {
+        let local_storage = local_storage();
+        for profile in self.profiles() {
+            local_storage
+                .remove_item(&self.poll_key_storage_key(&profile, poll_id))
+                .expect_throw("cannot remove imported poll key");
+        }
+        self.pk_cache.borrow_mut().remove(poll_id);
+    }
+}
+
+fn secret_key_bytes(keypair: &Keypair) -> Zeroizing<[u8; 32]> {
+    let mut bytes = Zeroizing::new([0_u8; 32]);
+    Group::serialize_scalar(keypair.secret().expose_scalar(), &mut *bytes);
+    bytes
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> Option<Keypair> {
+    let scalar = Group::deserialize_scalar(bytes)?;
+    Some(Keypair::from(SecretKey::new(scalar)))
 }
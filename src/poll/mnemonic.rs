@@ -0,0 +1,119 @@
+//! BIP-39-style mnemonic encoding for 32-byte secret seeds.
+//!
+//! A seed is encoded as [`MNEMONIC_LEN`] words from the English BIP-39 word list. Each word
+//! encodes 11 bits; the first 256 bits are the seed, and the last 8 bits are the first byte
+//! of the SHA-256 digest of the seed (the checksum). Thus, the encoding is compatible with
+//! BIP-39 mnemonics for 256-bit entropy.
+
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use std::{error::Error as StdError, fmt, sync::OnceLock};
+
+/// Number of words in a mnemonic.
+pub const MNEMONIC_LEN: usize = 24;
+
+const SEED_LEN: usize = 32;
+const BITS_PER_WORD: usize = 11;
+
+/// English BIP-39 word list (2048 words, sorted). The list is parsed on first use.
+fn wordlist() -> &'static [&'static str] {
+    static WORDLIST: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDLIST.get_or_init(|| include_str!("bip39-english.txt").lines().collect())
+}
+
+/// Errors that can occur when decoding a mnemonic.
+#[derive(Debug)]
+pub enum MnemonicError {
+    /// Unexpected number of words.
+    WordCount(usize),
+    /// Word is not in the word list.
+    UnknownWord(String),
+    /// Checksum mismatch.
+    Checksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WordCount(count) => write!(
+                formatter,
+                "unexpected number of words: expected {MNEMONIC_LEN}, got {count}"
+            ),
+            Self::UnknownWord(word) => write!(formatter, "unknown word `{word}`"),
+            Self::Checksum => formatter.write_str("checksum mismatch; check words and their order"),
+        }
+    }
+}
+
+impl StdError for MnemonicError {}
+
+fn checksum(seed: &[u8; SEED_LEN]) -> u8 {
+    Sha256::digest(seed)[0]
+}
+
+/// Encodes `seed` as a space-separated mnemonic phrase.
+pub fn encode_mnemonic(seed: &[u8; SEED_LEN]) -> Zeroizing<String> {
+    let wordlist = wordlist();
+    let mut bytes = Zeroizing::new([0_u8; SEED_LEN + 1]);
+    bytes[..SEED_LEN].copy_from_slice(seed);
+    bytes[SEED_LEN] = checksum(seed);
+
+    let words = (0..MNEMONIC_LEN).map(|i| {
+        let index = (0..BITS_PER_WORD).fold(0_usize, |acc, j| {
+            let bit_idx = i * BITS_PER_WORD + j;
+            let bit = (bytes[bit_idx / 8] >> (7 - bit_idx % 8)) & 1;
+            (acc << 1) | usize::from(bit)
+        });
+        wordlist[index]
+    });
+    Zeroizing::new(words.collect::<Vec<_>>().join(" "))
+}
+
+/// Decodes a seed from a mnemonic phrase. Words are case-insensitive and may be separated
+/// by arbitrary whitespace.
+pub fn decode_mnemonic(phrase: &str) -> Result<Zeroizing<[u8; SEED_LEN]>, MnemonicError> {
+    let words: Vec<_> = phrase.split_whitespace().collect();
+    if words.len() != MNEMONIC_LEN {
+        return Err(MnemonicError::WordCount(words.len()));
+    }
+
+    let wordlist = wordlist();
+    let mut bytes = Zeroizing::new([0_u8; SEED_LEN + 1]);
+    for (i, word) in words.into_iter().enumerate() {
+        let word = word.to_lowercase();
+        let index = wordlist
+            .binary_search(&word.as_str())
+            .map_err(|_| MnemonicError::UnknownWord(word))?;
+        for j in 0..BITS_PER_WORD {
+            if (index >> (BITS_PER_WORD - 1 - j)) & 1 == 1 {
+                let bit_idx = i * BITS_PER_WORD + j;
+                bytes[bit_idx / 8] |= 1 << (7 - bit_idx % 8);
+            }
+        }
+    }
+
+    let mut seed = Zeroizing::new([0_u8; SEED_LEN]);
+    seed.copy_from_slice(&bytes[..SEED_LEN]);
+    if checksum(&seed) == bytes[SEED_LEN] {
+        Ok(seed)
+    } else {
+        Err(MnemonicError::Checksum)
+    }
+}
+
+/// Generates a random passphrase consisting of `word_count` words from the BIP-39 word list.
+/// Each word carries 11 bits of entropy.
+pub(super) fn random_passphrase<R: CryptoRng + RngCore>(
+    rng: &mut R,
+    word_count: usize,
+) -> Zeroizing<String> {
+    let wordlist = wordlist();
+    let words = (0..word_count).map(|_| {
+        // The word list has 2^11 entries, so masking produces a uniformly distributed index.
+        let index = rng.next_u32() as usize & ((1 << BITS_PER_WORD) - 1);
+        wordlist[index]
+    });
+    Zeroizing::new(words.collect::<Vec<_>>().join(" "))
+}
@@ -0,0 +1,314 @@
+//! Shamir secret sharing for 32-byte secret seeds (social recovery).
+//!
+//! The seed is shared byte-wise over `GF(2^8)` (with the AES reduction polynomial). Each share
+//! is encoded as [`SHARE_PREFIX`] followed by base64url-encoded bytes:
+//!
+//! - Split identifier (4 bytes). Chosen randomly for each split, so that shares from different
+//!   splits cannot be mixed up.
+//! - Threshold (1 byte).
+//! - Share index (1 byte, 1-based).
+//! - Share value (32 bytes).
+//! - Checksum (4 bytes): first bytes of the SHA-256 digest of the preceding data.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use std::{collections::HashMap, error::Error as StdError, fmt, str::FromStr};
+
+/// Prefix of the text encoding of a share.
+pub const SHARE_PREFIX: &str = "epr1.";
+/// Maximum supported number of shares.
+pub const MAX_SHARES: u8 = 16;
+
+const SEED_LEN: usize = 32;
+const ID_LEN: usize = 4;
+const CHECKSUM_LEN: usize = 4;
+const ENCODED_LEN: usize = ID_LEN + 2 + SEED_LEN + CHECKSUM_LEN;
+
+/// Multiplication in `GF(2^8)` with the reduction polynomial `x^8 + x^4 + x^3 + x + 1`.
+fn gf_mul(mut x: u8, mut y: u8) -> u8 {
+    let mut product = 0;
+    while y != 0 {
+        if y & 1 == 1 {
+            product ^= x;
+        }
+        let carry = x & 0x80;
+        x <<= 1;
+        if carry != 0 {
+            x ^= 0x1b;
+        }
+        y >>= 1;
+    }
+    product
+}
+
+/// Inversion in `GF(2^8)` (`x^254 == x^-1` for non-zero `x`).
+fn gf_inv(x: u8) -> u8 {
+    debug_assert_ne!(x, 0, "cannot invert zero");
+    let mut result = 1;
+    let mut power = x;
+    let mut exp = 254_u8;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, power);
+        }
+        power = gf_mul(power, power);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Errors that can occur when decoding or combining shares.
+#[derive(Debug)]
+pub enum ShareError {
+    /// Share does not start with [`SHARE_PREFIX`].
+    Prefix,
+    /// Error decoding base64.
+    Base64(base64ct::Error),
+    /// Unexpected share length or field values.
+    Malformed,
+    /// Share checksum mismatch.
+    Checksum,
+    /// Shares belong to different splits.
+    Mixed,
+    /// Two shares have the same index, but different values.
+    Conflict(u8),
+    /// Not enough shares to recover the secret.
+    NotEnough {
+        /// Number of required shares.
+        threshold: u8,
+        /// Number of provided distinct shares.
+        count: usize,
+    },
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Prefix => write!(formatter, "share should start with `{SHARE_PREFIX}`"),
+            Self::Base64(err) => write!(formatter, "error decoding base64: {err}"),
+            Self::Malformed => formatter.write_str("malformed share"),
+            Self::Checksum => formatter.write_str("share checksum mismatch; check share text"),
+            Self::Mixed => formatter.write_str("shares belong to different splits"),
+            Self::Conflict(index) => {
+                write!(formatter, "conflicting values for share #{index}")
+            }
+            Self::NotEnough { threshold, count } => write!(
+                formatter,
+                "not enough shares: {threshold} required, {count} provided"
+            ),
+        }
+    }
+}
+
+impl StdError for ShareError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Base64(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Share of a secret seed. The share value is zeroized on drop.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretShare {
+    id: [u8; ID_LEN],
+    threshold: u8,
+    index: u8,
+    value: [u8; SEED_LEN],
+}
+
+impl fmt::Debug for SecretShare {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SecretShare")
+            .field("id", &self.id)
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SecretShare {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl SecretShare {
+    /// Splits `seed` into `count` shares, any `threshold` of which are sufficient
+    /// to recover the seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is less than 2 or greater than `count`, or if `count`
+    /// is greater than [`MAX_SHARES`].
+    pub fn split<R: CryptoRng + RngCore>(
+        seed: &[u8; SEED_LEN],
+        threshold: u8,
+        count: u8,
+        rng: &mut R,
+    ) -> Vec<Self> {
+        assert!(threshold >= 2, "threshold must be at least 2");
+        assert!(
+            threshold <= count,
+            "threshold cannot exceed the number of shares"
+        );
+        assert!(count <= MAX_SHARES, "too many shares");
+
+        let mut id = [0_u8; ID_LEN];
+        rng.fill_bytes(&mut id);
+        // `coefficients[i]` are non-constant polynomial coefficients for the `i`th seed byte.
+        let mut coefficients = Zeroizing::new(vec![[0_u8; SEED_LEN]; usize::from(threshold) - 1]);
+        for coefficient in coefficients.iter_mut() {
+            rng.fill_bytes(coefficient);
+        }
+
+        (1..=count)
+            .map(|x| {
+                let mut value = [0_u8; SEED_LEN];
+                for (i, byte) in value.iter_mut().enumerate() {
+                    // Evaluate the polynomial using Horner's method.
+                    let higher_terms = coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, coefficient| gf_mul(acc, x) ^ coefficient[i]);
+                    *byte = gf_mul(higher_terms, x) ^ seed[i];
+                }
+                Self {
+                    id,
+                    threshold,
+                    index: x,
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the 1-based index of this share.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the number of shares required to recover the secret.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Parses whitespace-separated shares.
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, ShareError> {
+        input.split_whitespace().map(str::parse).collect()
+    }
+
+    /// Recovers the secret seed from shares. Shares may be provided in any order
+    /// and may contain duplicates.
+    pub fn combine(shares: &[Self]) -> Result<Zeroizing<[u8; SEED_LEN]>, ShareError> {
+        let Some(first_share) = shares.first() else {
+            return Err(ShareError::NotEnough {
+                threshold: 2,
+                count: 0,
+            });
+        };
+        let mut distinct_shares = HashMap::new();
+        for share in shares {
+            if share.id != first_share.id || share.threshold != first_share.threshold {
+                return Err(ShareError::Mixed);
+            }
+            let existing_value = distinct_shares.entry(share.index).or_insert(&share.value);
+            if *existing_value != &share.value {
+                return Err(ShareError::Conflict(share.index));
+            }
+        }
+
+        let threshold = first_share.threshold;
+        if distinct_shares.len() < usize::from(threshold) {
+            return Err(ShareError::NotEnough {
+                threshold,
+                count: distinct_shares.len(),
+            });
+        }
+        let points: Vec<_> = distinct_shares
+            .into_iter()
+            .take(usize::from(threshold))
+            .collect();
+
+        // Lagrange interpolation at zero.
+        let mut seed = Zeroizing::new([0_u8; SEED_LEN]);
+        for &(x, value) in &points {
+            let basis = points
+                .iter()
+                .filter(|(other_x, _)| *other_x != x)
+                .fold(1, |acc, &(other_x, _)| {
+                    gf_mul(acc, gf_mul(other_x, gf_inv(other_x ^ x)))
+                });
+            for (seed_byte, &value_byte) in seed.iter_mut().zip(value) {
+                *seed_byte ^= gf_mul(basis, value_byte);
+            }
+        }
+        Ok(seed)
+    }
+
+    fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+        let digest = Sha256::new()
+            .chain_update(b"elastic_poll::share")
+            .chain_update(bytes)
+            .finalize();
+        let mut checksum = [0_u8; CHECKSUM_LEN];
+        checksum.copy_from_slice(&digest[..CHECKSUM_LEN]);
+        checksum
+    }
+}
+
+impl fmt::Display for SecretShare {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bytes = Zeroizing::new([0_u8; ENCODED_LEN]);
+        bytes[..ID_LEN].copy_from_slice(&self.id);
+        bytes[ID_LEN] = self.threshold;
+        bytes[ID_LEN + 1] = self.index;
+        bytes[(ID_LEN + 2)..(ENCODED_LEN - CHECKSUM_LEN)].copy_from_slice(&self.value);
+        let checksum = Self::checksum(&bytes[..(ENCODED_LEN - CHECKSUM_LEN)]);
+        bytes[(ENCODED_LEN - CHECKSUM_LEN)..].copy_from_slice(&checksum);
+
+        let encoded = Zeroizing::new(Base64UrlUnpadded::encode_string(&*bytes));
+        write!(formatter, "{SHARE_PREFIX}{}", encoded.as_str())
+    }
+}
+
+impl FromStr for SecretShare {
+    type Err = ShareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded = s.strip_prefix(SHARE_PREFIX).ok_or(ShareError::Prefix)?;
+        let mut bytes = Zeroizing::new([0_u8; ENCODED_LEN]);
+        let decoded_len = Base64UrlUnpadded::decode(encoded, &mut *bytes)
+            .map_err(ShareError::Base64)?
+            .len();
+        if decoded_len != ENCODED_LEN {
+            return Err(ShareError::Malformed);
+        }
+
+        let (data, checksum) = bytes.split_at(ENCODED_LEN - CHECKSUM_LEN);
+        if Self::checksum(data) != checksum {
+            return Err(ShareError::Checksum);
+        }
+        let threshold = data[ID_LEN];
+        let index = data[ID_LEN + 1];
+        if threshold < 2 || index == 0 {
+            return Err(ShareError::Malformed);
+        }
+
+        let mut id = [0_u8; ID_LEN];
+        id.copy_from_slice(&data[..ID_LEN]);
+        let mut value = [0_u8; SEED_LEN];
+        value.copy_from_slice(&data[(ID_LEN + 2)..]);
+        Ok(Self {
+            id,
+            threshold,
+            index,
+            value,
+        })
+    }
+}
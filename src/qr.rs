@@ -0,0 +1,215 @@
+//! QR code rendering and chunked transport for exported data.
+//!
+//! Large payloads (e.g., multi-choice votes or entire polls) do not fit into a single QR code
+//! that could be reliably scanned by a phone camera. Such payloads are split into numbered
+//! chunks having the form `epq1.{index}.{count}.{checksum}.{data}`, where `checksum`
+//! is the hex-encoded prefix of the SHA-256 digest of the entire payload. Chunks can be pasted
+//! into import fields in any order (separated by whitespace); they are reassembled
+//! before being passed to the ordinary import logic.
+
+use qrcode::{Color, EcLevel, QrCode};
+use sha2::{Digest, Sha256};
+
+use std::{borrow::Cow, collections::BTreeMap, error::Error as StdError, fmt, fmt::Write as _};
+
+use crate::poll::CompactEncoding;
+
+/// Prefix of chunk strings.
+pub const CHUNK_PREFIX: &str = "epq1.";
+/// Maximum number of payload chars in a single QR code. Larger payloads are split
+/// into chunks.
+pub const MAX_CHUNK_LEN: usize = 600;
+/// Maximum supported number of chunks.
+pub const MAX_CHUNK_COUNT: usize = 64;
+
+fn checksum(payload: &str) -> u32 {
+    let digest = Sha256::digest(payload.as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]])
+}
+
+/// Splits the `payload` into strings that should be encoded as separate QR codes.
+/// If the payload is small enough, it is returned as is.
+///
+/// The payload is assumed to not contain whitespace (which is the case for
+/// the [compact encoding](CompactEncoding)). Payloads requiring more than
+/// [`MAX_CHUNK_COUNT`] chunks are rejected since such chunks could not be reassembled.
+pub fn split_into_chunks(payload: &str) -> Result<Vec<String>, ChunkError> {
+    let chars: Vec<_> = payload.chars().collect();
+    if chars.len() <= MAX_CHUNK_LEN {
+        return Ok(vec![payload.to_owned()]);
+    }
+
+    let checksum = checksum(payload);
+    let chunks = chars.chunks(MAX_CHUNK_LEN);
+    let count = chunks.len();
+    if count > MAX_CHUNK_COUNT {
+        return Err(ChunkError::TooLarge { count });
+    }
+    let chunks = chunks.enumerate().map(|(i, chunk)| {
+        let mut chunk_str = format!("{CHUNK_PREFIX}{}.{count}.{checksum:08x}.", i + 1);
+        chunk_str.extend(chunk);
+        chunk_str
+    });
+    Ok(chunks.collect())
+}
+
+/// Errors that can occur when reassembling chunks.
+#[derive(Debug)]
+pub enum ChunkError {
+    /// Chunk is malformed.
+    Malformed(String),
+    /// Chunks belong to different payloads.
+    Mixed,
+    /// Some chunks are missing.
+    Missing {
+        /// 1-based indices of missing chunks.
+        indices: Vec<usize>,
+        /// Total number of chunks.
+        count: usize,
+    },
+    /// Reassembled payload does not match the checksum.
+    Checksum,
+    /// Payload is too large to be split into chunks.
+    TooLarge {
+        /// Number of chunks the payload would require.
+        count: usize,
+    },
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(chunk) => write!(formatter, "malformed QR code chunk: {chunk}"),
+            Self::Mixed => formatter.write_str("QR code chunks belong to different values"),
+            Self::Missing { indices, count } => {
+                let indices: Vec<_> = indices.iter().map(ToString::to_string).collect();
+                write!(
+                    formatter,
+                    "missing QR code chunk(s) {} out of {count}",
+                    indices.join(", ")
+                )
+            }
+            Self::Checksum => formatter.write_str("checksum mismatch for QR code chunks"),
+            Self::TooLarge { count } => write!(
+                formatter,
+                "value is too large to be exported as QR codes ({count} codes required, \
+                 at most {MAX_CHUNK_COUNT} supported)"
+            ),
+        }
+    }
+}
+
+impl StdError for ChunkError {}
+
+#[derive(Debug)]
+struct Chunk<'a> {
+    index: usize,
+    count: usize,
+    checksum: u32,
+    data: &'a str,
+}
+
+impl<'a> Chunk<'a> {
+    fn parse(chunk_str: &'a str) -> Option<Self> {
+        let mut parts = chunk_str.strip_prefix(CHUNK_PREFIX)?.splitn(4, '.');
+        let index: usize = parts.next()?.parse().ok()?;
+        let count: usize = parts.next()?.parse().ok()?;
+        let checksum = parts.next()?;
+        if checksum.len() != 8 {
+            return None;
+        }
+        let checksum = u32::from_str_radix(checksum, 16).ok()?;
+        let data = parts.next()?;
+
+        let is_valid = (1..=count).contains(&index) && count <= MAX_CHUNK_COUNT;
+        is_valid.then_some(Self {
+            index,
+            count,
+            checksum,
+            data,
+        })
+    }
+}
+
+/// Reassembles the payload from whitespace-separated chunks in any order. If the input
+/// does not consist of chunks, it is returned as is.
+pub fn reassemble_chunks(input: &str) -> Result<Cow<'_, str>, ChunkError> {
+    let input = input.trim();
+    if !input.starts_with(CHUNK_PREFIX) {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut header = None;
+    let mut data_by_index = BTreeMap::new();
+    for chunk_str in input.split_whitespace() {
+        let chunk =
+            Chunk::parse(chunk_str).ok_or_else(|| ChunkError::Malformed(chunk_str.to_owned()))?;
+        let chunk_header = (chunk.count, chunk.checksum);
+        if *header.get_or_insert(chunk_header) != chunk_header {
+            return Err(ChunkError::Mixed);
+        }
+        if let Some(prev_data) = data_by_index.insert(chunk.index, chunk.data) {
+            if prev_data != chunk.data {
+                return Err(ChunkError::Mixed);
+            }
+        }
+    }
+
+    let (count, expected_checksum) = header.unwrap(); // `input` contains at least 1 chunk
+    if data_by_index.len() < count {
+        let indices = (1..=count)
+            .filter(|idx| !data_by_index.contains_key(idx))
+            .collect();
+        return Err(ChunkError::Missing { indices, count });
+    }
+    let payload: String = data_by_index.into_values().collect();
+    if checksum(&payload) == expected_checksum {
+        Ok(Cow::Owned(payload))
+    } else {
+        Err(ChunkError::Checksum)
+    }
+}
+
+/// Decodes a value pasted by the user, which may be JSON, a compact value,
+/// or QR code chunks for a compact value.
+pub fn decode_pasted<T: CompactEncoding>(input: &str) -> Result<T, Box<dyn StdError>> {
+    let input = reassemble_chunks(input)?;
+    T::decode(&input)
+}
+
+/// QR code image represented as an SVG path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrImage {
+    /// Width of the code in modules, not including the quiet zone.
+    pub width: usize,
+    /// SVG path commands drawing dark modules in a `width × width` box.
+    pub path: String,
+}
+
+impl QrImage {
+    /// Encodes `data` as a QR code.
+    pub fn new(data: &str) -> Result<Self, qrcode::types::QrError> {
+        let code = QrCode::with_error_correction_level(data, EcLevel::M)?;
+        let width = code.width();
+        let modules: Vec<_> = code
+            .into_colors()
+            .into_iter()
+            .map(|color| color == Color::Dark)
+            .collect();
+
+        let mut path = String::new();
+        for (y, row) in modules.chunks(width).enumerate() {
+            let mut x = 0;
+            while x < width {
+                if row[x] {
+                    let run = row[x..].iter().take_while(|&&dark| dark).count();
+                    write!(path, "M{x},{y}h{run}v1h-{run}z").unwrap();
+                    x += run;
+                } else {
+                    x += 1;
+                }
+            }
+        }
+        Ok(Self { width, path })
+    }
+}
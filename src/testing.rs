@@ -2,7 +2,21 @@
 
 use yew::{html::Scope, Component, Properties};
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+thread_local! {
+    /// Number of unlocked secrets currently in memory.
+    pub(crate) static LIVE_SECRETS: Cell<usize> = Cell::new(0);
+}
+
+/// Returns the number of unlocked secrets currently in memory, across all `SecretManager`s.
+/// Allows checking that the secrets are dropped (and thus zeroized) when locked.
+pub fn live_secrets() -> usize {
+    LIVE_SECRETS.with(Cell::get)
+}
 
 #[derive(Debug)]
 pub struct ComponentRef<C: Component> {
@@ -0,0 +1,150 @@
+//! Tests for the headless JS API.
+
+use js_sys::JSON;
+use rand::{rngs::OsRng, RngCore};
+use serde_json::{json, Value};
+use wasm_bindgen::{JsCast, JsError, JsValue, UnwrapThrowExt};
+use wasm_bindgen_test::*;
+
+use elasticpoll_wasm::api::{
+    cast_vote, create_poll, import_poll, make_application, make_tallier_share, poll_id, verify_poll,
+};
+
+fn to_js<T: JsCast>(value: &Value) -> T {
+    JSON::parse(&value.to_string())
+        .unwrap_throw()
+        .unchecked_into()
+}
+
+fn from_js(value: &JsValue) -> Value {
+    let json = JSON::stringify(value).unwrap_throw();
+    serde_json::from_str(&String::from(json)).unwrap()
+}
+
+fn error_message<T>(result: Result<T, JsError>) -> String {
+    let Err(err) = result else {
+        panic!("unexpected success");
+    };
+    let err: js_sys::Error = JsValue::from(err).unchecked_into();
+    err.message().into()
+}
+
+fn random_secret() -> [u8; 32] {
+    let mut secret = [0_u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+#[wasm_bindgen_test]
+fn poll_lifecycle() {
+    let params = json!({
+        "title": "Sample poll",
+        "poll_type": "multi_choice",
+        "options": ["Yes", "No", "Maybe"],
+    });
+    let poll = from_js(&create_poll(&to_js(&params)).unwrap_throw());
+    let spec = &poll["spec"];
+    assert_eq!(spec["title"], "Sample poll");
+    assert_eq!(spec["description"], "");
+    assert_eq!(spec["options"].as_array().unwrap().len(), 3);
+    let id = poll_id(&to_js(spec)).unwrap_throw();
+
+    let secrets = [random_secret(), random_secret()];
+    let applications: Vec<_> = secrets
+        .iter()
+        .map(|secret| from_js(&make_application(&to_js(spec), secret).unwrap_throw()))
+        .collect();
+    // Applications are deterministic.
+    let application = from_js(&make_application(&to_js(spec), &secrets[0]).unwrap_throw());
+    assert_eq!(application, applications[0]);
+
+    let mut poll = json!({ "spec": spec, "participant_applications": applications });
+    let summary = from_js(&import_poll(&to_js(&poll)).unwrap_throw());
+    assert_eq!(summary["id"], id.as_str());
+    assert_eq!(summary["stage"], "participants");
+    assert_eq!(summary["participants"].as_array().unwrap().len(), 2);
+
+    let votes: Vec<_> = secrets
+        .iter()
+        .zip([json!([0, 2]), json!([0])])
+        .map(|(secret, choice)| {
+            let vote = cast_vote(&to_js(&poll), secret, &to_js(&choice)).unwrap_throw();
+            from_js(&vote)
+        })
+        .collect();
+    poll["votes"] = votes.into();
+    let summary = from_js(&import_poll(&to_js(&poll)).unwrap_throw());
+    assert_eq!(summary["stage"], "voting");
+    assert_eq!(summary["voters"].as_array().unwrap().len(), 2);
+
+    let shares: Vec<_> = secrets
+        .iter()
+        .map(|secret| from_js(&make_tallier_share(&to_js(&poll), secret).unwrap_throw()))
+        .collect();
+    poll["tallier_shares"] = shares.into();
+
+    let summary = from_js(&import_poll(&to_js(&poll)).unwrap_throw());
+    assert_eq!(summary["stage"], "finished");
+    assert_eq!(summary["results"], json!([2, 0, 1]));
+    let report = from_js(&verify_poll(&to_js(&poll)));
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["poll"], summary);
+}
+
+#[wasm_bindgen_test]
+fn invalid_poll_params() {
+    let params = json!({ "title": "", "options": ["Yes"] });
+    let err = error_message(create_poll(&to_js(&params)));
+    assert!(err.contains("title"), "{err}");
+
+    let params = json!({ "title": "Poll", "options": ["Yes", "Yes"] });
+    let err = error_message(create_poll(&to_js(&params)));
+    assert!(err.contains("unique"), "{err}");
+
+    let params = json!({ "title": "Poll" });
+    let err = error_message(create_poll(&to_js(&params)));
+    assert!(err.contains("invalid poll params"), "{err}");
+}
+
+#[wasm_bindgen_test]
+fn voting_errors() {
+    let params = json!({ "title": "Sample poll", "options": ["Yes", "No"] });
+    let poll = from_js(&create_poll(&to_js(&params)).unwrap_throw());
+    let spec = &poll["spec"];
+    let secret = random_secret();
+    let application = from_js(&make_application(&to_js(spec), &secret).unwrap_throw());
+    let poll = json!({ "spec": spec, "participant_applications": [application] });
+    let poll_js = to_js(&poll);
+
+    let err = error_message(cast_vote(&poll_js, &secret, &to_js(&json!([2]))));
+    assert!(err.contains("invalid option index 2"));
+    let err = error_message(cast_vote(&poll_js, &secret, &to_js(&json!([0, 1]))));
+    assert!(err.contains("exactly one option"));
+    let err = error_message(cast_vote(&poll_js, &random_secret(), &to_js(&json!([0]))));
+    assert!(err.contains("not correspond to a poll participant"));
+    let err = error_message(cast_vote(&poll_js, &[0; 16], &to_js(&json!([0]))));
+    assert!(err.contains("32 bytes"));
+    let err = error_message(make_tallier_share(&poll_js, &secret));
+    assert!(err.contains("does not contain votes"));
+}
+
+#[wasm_bindgen_test]
+fn verifying_invalid_poll() {
+    let params = json!({ "title": "Sample poll", "options": ["Yes", "No"] });
+    let poll = from_js(&create_poll(&to_js(&params)).unwrap_throw());
+    let secret = random_secret();
+    let application = from_js(&make_application(&to_js(&poll["spec"]), &secret).unwrap_throw());
+    let mut poll = json!({ "spec": poll["spec"], "participant_applications": [application] });
+    let vote = cast_vote(&to_js(&poll), &secret, &to_js(&json!([1]))).unwrap_throw();
+    poll["votes"] = json!([from_js(&vote)]);
+
+    // Changing the spec invalidates all applications.
+    poll["spec"]["title"] = "Other poll".into();
+    let report = from_js(&verify_poll(&to_js(&poll)));
+    assert_eq!(report["valid"], false);
+    let err = report["error"].as_str().unwrap();
+    assert!(err.contains("cannot validate application"), "{err}");
+    assert!(report.get("poll").is_none());
+    let err = error_message(import_poll(&to_js(&poll)));
+    assert!(err.contains("cannot validate application"), "{err}");
+}
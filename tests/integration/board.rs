@@ -0,0 +1,241 @@
+//! Tests for syncing polls with a bulletin board.
+
+use assert_matches::assert_matches;
+use rand::rngs::OsRng;
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_test::*;
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use elasticpoll_wasm::poll::{
+    BoardClient, BoardEnvelope, BoardError, BoardFuture, BoardItem, BoardItemKind, BoardTransport,
+    ItemStatus, Keypair, ParticipantApplication, PollId, PollSpec, PollStage, PollState,
+    TallierShare, Vote, VoteChoice,
+};
+
+use crate::sample_poll;
+
+/// In-process board storing items in memory without verifying them.
+#[derive(Debug, Default)]
+struct MockBoard {
+    spec: RefCell<Option<PollSpec>>,
+    items: RefCell<Vec<BoardEnvelope>>,
+    rejects_items: Cell<bool>,
+}
+
+impl BoardTransport for MockBoard {
+    fn register_poll(&self, spec: &PollSpec) -> BoardFuture<()> {
+        self.spec.borrow_mut().get_or_insert_with(|| spec.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn items(&self, poll_id: &PollId, since: usize) -> BoardFuture<Vec<BoardEnvelope>> {
+        let result = match &*self.spec.borrow() {
+            Some(spec) if PollId::for_spec(spec) == *poll_id => {
+                let items = self.items.borrow();
+                Ok(items.get(since..).unwrap_or_default().to_vec())
+            }
+            _ => Err(BoardError::Http {
+                status: 404,
+                message: "poll is not registered on the board".to_owned(),
+            }),
+        };
+        Box::pin(async move { result })
+    }
+
+    fn submit(&self, _poll_id: &PollId, item: &BoardItem) -> BoardFuture<()> {
+        let result = if self.rejects_items.get() {
+            Err(BoardError::Http {
+                status: 422,
+                message: "invalid item".to_owned(),
+            })
+        } else {
+            let mut items = self.items.borrow_mut();
+            let index = items.len();
+            items.push(BoardEnvelope {
+                index,
+                received_at: 0.0,
+                item: item.clone(),
+            });
+            Ok(())
+        };
+        Box::pin(async move { result })
+    }
+}
+
+fn single_choice(option: usize) -> VoteChoice {
+    VoteChoice::SingleChoice(option)
+}
+
+/// Participant with its local copy of the poll.
+struct TestParticipant {
+    keys: Keypair,
+    state: PollState,
+}
+
+impl TestParticipant {
+    fn new(spec: &PollSpec) -> Self {
+        let keys = Keypair::generate(&mut OsRng);
+        let mut state = PollState::new(spec.clone());
+        let poll_id = PollId::for_spec(spec);
+        state.insert_participant(ParticipantApplication::new(&keys, &poll_id));
+        Self { keys, state }
+    }
+
+    async fn push(&self, client: &BoardClient) -> ItemStatus {
+        let item = BoardItem::ours(&self.state, self.keys.public()).unwrap_throw();
+        let report = client.push(self.state.spec(), item).await.unwrap_throw();
+        assert_eq!(report.public_key, *self.keys.public());
+        report.status
+    }
+
+    async fn pull(&mut self, client: &BoardClient) -> Vec<ItemStatus> {
+        let poll_id = PollId::for_spec(self.state.spec());
+        let items = client.pull(&poll_id).await.unwrap_throw();
+        let reports = BoardClient::apply_items(&poll_id, &mut self.state, items);
+        reports.into_iter().map(|report| report.status).collect()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn syncing_poll_via_board() {
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let client = BoardClient::new(Rc::new(MockBoard::default()));
+    let mut participants = [TestParticipant::new(&spec), TestParticipant::new(&spec)];
+
+    for participant in &participants {
+        assert_eq!(participant.push(&client).await, ItemStatus::Submitted);
+    }
+    let statuses = participants[0].pull(&client).await;
+    assert_eq!(statuses, [ItemStatus::AlreadyPresent, ItemStatus::Added]);
+    let statuses = participants[1].pull(&client).await;
+    assert_eq!(statuses, [ItemStatus::Added, ItemStatus::AlreadyPresent]);
+
+    for (i, participant) in participants.iter_mut().enumerate() {
+        assert_eq!(participant.state.participants().len(), 2);
+        participant.state.finalize_participants();
+        let vote = Vote::new(
+            &participant.keys,
+            &poll_id,
+            &participant.state,
+            &single_choice(i),
+        );
+        participant.state.insert_vote(&poll_id, vote).unwrap_throw();
+    }
+    for participant in &participants {
+        assert_eq!(participant.push(&client).await, ItemStatus::Submitted);
+        // Pushing the same item again is a no-op.
+        assert_eq!(participant.push(&client).await, ItemStatus::AlreadyPresent);
+    }
+    for participant in &mut participants {
+        let statuses = participant.pull(&client).await;
+        assert_eq!(statuses.len(), 4);
+        // Applications are already present in the poll.
+        assert_eq!(
+            statuses[..2],
+            [ItemStatus::AlreadyPresent, ItemStatus::AlreadyPresent]
+        );
+        assert!(statuses[2..].contains(&ItemStatus::Added));
+        assert!(statuses[2..].contains(&ItemStatus::AlreadyPresent));
+        assert!(participant
+            .state
+            .participants()
+            .iter()
+            .all(|p| p.vote.is_some()));
+    }
+
+    for participant in &mut participants {
+        participant.state.finalize_votes();
+        let share = TallierShare::new(&participant.keys, &poll_id, &participant.state);
+        participant.state.insert_unchecked_tallier_share(share);
+        assert_eq!(participant.push(&client).await, ItemStatus::Submitted);
+    }
+    for participant in &mut participants {
+        let statuses = participant.pull(&client).await;
+        assert_eq!(statuses.len(), 6);
+        assert!(statuses[4..].contains(&ItemStatus::Added));
+        assert_eq!(participant.state.stage(), PollStage::Finished);
+        assert_eq!(participant.state.results().unwrap_throw(), [1, 1]);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn pulled_items_statuses() {
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let board = Rc::new(MockBoard::default());
+    let client = BoardClient::new(board.clone());
+
+    let mut voter = TestParticipant::new(&spec);
+    voter.state.finalize_participants();
+    for option in [0, 1] {
+        let vote = Vote::new(&voter.keys, &poll_id, &voter.state, &single_choice(option));
+        voter.state.insert_vote(&poll_id, vote).unwrap_throw();
+        assert_eq!(voter.push(&client).await, ItemStatus::Submitted);
+    }
+
+    // Votes cannot be applied before participants are finalized.
+    let mut observer = PollState::new(spec.clone());
+    let items = client.pull(&poll_id).await.unwrap_throw();
+    let reports = BoardClient::apply_items(&poll_id, &mut observer, items);
+    assert_eq!(reports.len(), 2);
+    assert!(reports
+        .iter()
+        .all(|report| report.kind == BoardItemKind::Vote));
+    assert_eq!(reports[0].status, ItemStatus::Superseded);
+    assert_matches!(reports[1].status, ItemStatus::Skipped(_));
+    assert_eq!(observer.participants().len(), 0);
+
+    // Only the latest vote is applied.
+    observer.insert_participant(voter.state.participants()[0].application.clone());
+    observer.finalize_participants();
+    let items = client.pull(&poll_id).await.unwrap_throw();
+    let reports = BoardClient::apply_items(&poll_id, &mut observer, items);
+    assert_eq!(reports[0].status, ItemStatus::Superseded);
+    assert_eq!(reports[1].status, ItemStatus::Added);
+    let observed_vote = &observer.participants()[0]
+        .vote
+        .as_ref()
+        .unwrap_throw()
+        .inner;
+    let our_vote = &voter.state.participants()[0]
+        .vote
+        .as_ref()
+        .unwrap_throw()
+        .inner;
+    assert_eq!(observed_vote.public_key(), our_vote.public_key());
+
+    // A vote from a non-participant is rejected by local verification.
+    let outsider = TestParticipant::new(&spec);
+    let vote = Vote::new(&outsider.keys, &poll_id, &observer, &single_choice(0));
+    board
+        .submit(&poll_id, &BoardItem::Vote(vote))
+        .await
+        .unwrap_throw();
+    let items = client.pull(&poll_id).await.unwrap_throw();
+    let reports = BoardClient::apply_items(&poll_id, &mut observer, items);
+    assert_eq!(reports[1].status, ItemStatus::AlreadyPresent);
+    assert_matches!(reports[2].status, ItemStatus::Rejected(_));
+    assert_eq!(observer.participants().len(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn items_rejected_by_board() {
+    let spec = sample_poll(0);
+    let board = Rc::new(MockBoard::default());
+    board.rejects_items.set(true);
+    let client = BoardClient::new(board.clone());
+
+    let participant = TestParticipant::new(&spec);
+    let status = participant.push(&client).await;
+    assert_eq!(status, ItemStatus::Rejected("invalid item".to_owned()));
+    assert!(board.items.borrow().is_empty());
+
+    let other_poll = PollId::for_spec(&sample_poll(1));
+    let err = client.pull(&other_poll).await.unwrap_err();
+    assert_matches!(err, BoardError::Http { status: 404, .. });
+}
@@ -0,0 +1,75 @@
+//! Tests for the pure-Rust password-based encryption in the secret manager.
+//! Boxes themselves are tested in the `elasticpoll-core` crate.
+
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_test::*;
+
+use std::rc::Rc;
+
+use elasticpoll_wasm::{
+    crypto::{Kdf, PasswordBox, RustCrypto},
+    poll::{PollId, PollSpec, PollType, SecretManager, SecretManagerStatus},
+};
+
+const PASSWORD: &str = "correct horse battery staple";
+
+/// Cheap Argon2id params to keep tests fast.
+const TEST_KDF: Kdf = Kdf::Argon2id {
+    memory: 1_024,
+    iterations: 2,
+    parallelism: 1,
+};
+
+fn clear_storage() {
+    let local_storage = web_sys::window()
+        .unwrap_throw()
+        .local_storage()
+        .unwrap_throw()
+        .unwrap_throw();
+    local_storage.clear().unwrap_throw();
+}
+
+#[wasm_bindgen_test]
+async fn secret_manager_with_rust_crypto() {
+    clear_storage();
+    let crypto = Rc::new(RustCrypto::new(TEST_KDF));
+    let secrets = Rc::new(SecretManager::new(Rc::clone(&crypto) as _));
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let poll_id = PollId::for_spec(&PollSpec {
+        title: "Poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce: 0,
+        options: vec!["Yes".to_owned(), "No".to_owned()],
+    });
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    let backup = secrets.backup().unwrap_throw();
+    let sealed: PasswordBox = serde_json::from_str(&backup).unwrap();
+    assert_eq!(sealed.kdf(), TEST_KDF);
+
+    secrets.lock();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Locked));
+    let err = secrets.unlock("wrong").await.unwrap_err();
+    assert!(String::from(err.message()).contains("failed decryption"));
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+
+    // The opened secret is cached, so another manager instance can load it.
+    let secrets = Rc::new(SecretManager::new(Rc::clone(&crypto) as _));
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Locked));
+    assert!(secrets.try_load_cached().await);
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+
+    // Locking must remove the secret from the cache.
+    secrets.lock();
+    let secrets = Rc::new(SecretManager::new(crypto));
+    assert!(!secrets.try_load_cached().await);
+    clear_storage();
+}
@@ -0,0 +1,105 @@
+//! Tests for events reported to the host and messages exchanged with the parent page
+//! of the embedded app.
+
+use assert_matches::assert_matches;
+use rand::rngs::OsRng;
+use serde_json::json;
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_test::*;
+
+use elasticpoll_wasm::{
+    js::{AppEvent, EmbedMessage, EmbedRequest, ExportedDataType},
+    poll::{BoardItem, Keypair, ParticipantApplication, PollId, PollStage},
+};
+
+use crate::sample_poll;
+
+#[wasm_bindgen_test]
+fn serializing_events() {
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let event = AppEvent::PollCreated {
+        poll_id,
+        spec: spec.clone(),
+    };
+    let event = serde_json::to_value(event).unwrap_throw();
+    assert_eq!(event["poll_id"], poll_id.to_string());
+    assert_eq!(event["spec"]["title"], "Poll #0");
+
+    let event = AppEvent::stage_changed(poll_id, PollStage::Finished);
+    let event = serde_json::to_value(event).unwrap_throw();
+    assert_eq!(
+        event,
+        json!({ "poll_id": poll_id.to_string(), "stage": "finished" })
+    );
+
+    let keys = Keypair::generate(&mut OsRng);
+    let application = ParticipantApplication::new(&keys, &poll_id);
+    let event = AppEvent::ItemInserted {
+        poll_id,
+        item: BoardItem::Application(application.clone()),
+    };
+    let event = serde_json::to_value(event).unwrap_throw();
+    assert_eq!(event["item"]["type"], "application");
+    assert_eq!(
+        event["item"]["payload"],
+        serde_json::to_value(&application).unwrap_throw()
+    );
+}
+
+#[wasm_bindgen_test]
+fn parsing_embed_requests() {
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let application = ParticipantApplication::new(&Keypair::generate(&mut OsRng), &poll_id);
+    let request = json!({
+        "type": "insert_item",
+        "item": { "type": "application", "payload": application },
+    });
+    let request: EmbedRequest = serde_json::from_value(request).unwrap_throw();
+    let EmbedRequest::InsertItem { item } = request else {
+        panic!("unexpected request: {request:?}");
+    };
+    assert_eq!(item.public_key(), &application.public_key);
+
+    let request = json!({ "type": "export", "data_type": "results_link" });
+    let request: EmbedRequest = serde_json::from_value(request).unwrap_throw();
+    assert_matches!(
+        request,
+        EmbedRequest::Export {
+            data_type: ExportedDataType::ResultsLink
+        }
+    );
+
+    let request = json!({ "type": "remove_poll" });
+    serde_json::from_value::<EmbedRequest>(request).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn converting_events_to_embed_messages() {
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let event = AppEvent::PollCreated { poll_id, spec };
+    assert!(EmbedMessage::from_event(event).is_none());
+
+    let event = AppEvent::stage_changed(poll_id, PollStage::Finished);
+    let message = EmbedMessage::from_event(event).unwrap_throw();
+    let message = serde_json::to_value(message).unwrap_throw();
+    assert_eq!(
+        message,
+        json!({
+            "type": "stage_changed",
+            "poll_id": poll_id.to_string(),
+            "stage": "finished",
+        })
+    );
+
+    let message = EmbedMessage::Error {
+        message: "poll is not found".to_owned(),
+    };
+    let message = serde_json::to_value(message).unwrap_throw();
+    assert_eq!(
+        message,
+        json!({ "type": "error", "message": "poll is not found" })
+    );
+}
@@ -1,7 +1,24 @@
+use elasticpoll_wasm::poll::{PollSpec, PollType};
 use wasm_bindgen_test::wasm_bindgen_test_configure;
 
+mod api;
+mod board;
+mod crypto;
+mod events;
 #[cfg(feature = "testing")]
 mod pages;
-mod poll;
+mod qr;
+mod secrets;
 
 wasm_bindgen_test_configure!(run_in_browser);
+
+/// Single-choice poll spec shared by the test modules. Poll IDs for different `nonce`s differ.
+fn sample_poll(nonce: u32) -> PollSpec {
+    PollSpec {
+        title: format!("Poll #{nonce}"),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce,
+        options: vec!["Yes".to_owned(), "No".to_owned()],
+    }
+}
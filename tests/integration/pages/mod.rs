@@ -22,17 +22,17 @@ mod new_poll;
 struct MockCrypto;
 
 impl PasswordBasedCrypto for MockCrypto {
-    fn seal(&self, password: &str, secret_bytes: &[u8]) -> Promise {
+    fn seal(&self, password: &str, secret_bytes: &[u8], _cache_key: &str) -> Promise {
         assert_eq!(password, "correct horse battery staple");
         let encoded = Base64UrlUnpadded::encode_string(secret_bytes);
         Promise::resolve(&encoded.into())
     }
 
-    fn cached(&self) -> Promise {
+    fn cached(&self, _cache_key: &str) -> Promise {
         Promise::resolve(&JsValue::null())
     }
 
-    fn open(&self, password: &str, encrypted: &str) -> Promise {
+    fn open(&self, password: &str, encrypted: &str, _cache_key: &str) -> Promise {
         if password == "correct horse battery staple" {
             let decoded = Base64UrlUnpadded::decode_vec(encrypted).unwrap_throw();
             Promise::resolve(&Uint8Array::from(decoded.as_slice()).into())
@@ -40,6 +40,10 @@ impl PasswordBasedCrypto for MockCrypto {
             Promise::reject(&Error::new("invalid password"))
         }
     }
+
+    fn forget(&self, _cache_key: &str) {
+        // do nothing
+    }
 }
 
 #[derive(Debug)]
@@ -106,6 +110,11 @@ where
                 secrets: Rc::new(SecretManager::new(mock_crypto)),
                 modals: mock_modals,
                 onexport: Callback::from(move |(data, _)| export_calls_.push_call(data)),
+                onqrexport: Callback::noop(),
+                onevent: Callback::noop(),
+                embed_origins: Rc::default(),
+                is_embedded: false,
+                lock_epoch: 0,
             },
             export_calls,
             _component: PhantomData,
@@ -0,0 +1,123 @@
+//! Tests for QR code export and chunked transport.
+
+use assert_matches::assert_matches;
+use rand::{rngs::OsRng, seq::SliceRandom};
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_test::*;
+
+use elasticpoll_wasm::{
+    poll::{
+        CompactEncoding, Keypair, ParticipantApplication, PollId, PollSpec, PollState, PollType,
+        Vote, VoteChoice,
+    },
+    qr::{
+        decode_pasted, reassemble_chunks, split_into_chunks, ChunkError, QrImage, CHUNK_PREFIX,
+        MAX_CHUNK_COUNT, MAX_CHUNK_LEN,
+    },
+};
+
+fn multi_choice_poll() -> PollSpec {
+    PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::MultiChoice,
+        nonce: 0,
+        options: (0..16).map(|i| format!("Option #{i}")).collect(),
+    }
+}
+
+fn sample_vote() -> (PollId, Vote) {
+    let poll_spec = multi_choice_poll();
+    let poll_id = PollId::for_spec(&poll_spec);
+    let mut poll = PollState::new(poll_spec);
+    let keys = Keypair::generate(&mut OsRng);
+    poll.insert_participant(ParticipantApplication::new(&keys, &poll_id));
+    poll.finalize_participants();
+
+    let choice = VoteChoice::MultiChoice([false, true].repeat(8));
+    (poll_id, Vote::new(&keys, &poll_id, &poll, &choice))
+}
+
+#[wasm_bindgen_test]
+fn small_payload_is_not_chunked() {
+    let poll_id = PollId::for_spec(&multi_choice_poll());
+    let app = ParticipantApplication::new(&Keypair::generate(&mut OsRng), &poll_id);
+    let compact = app.to_compact();
+    assert!(compact.len() <= MAX_CHUNK_LEN);
+
+    let chunks = split_into_chunks(&compact).unwrap_throw();
+    assert_eq!(chunks, [compact.clone()]);
+    assert_eq!(reassemble_chunks(&compact).unwrap_throw(), compact);
+    let restored: ParticipantApplication = decode_pasted(&chunks[0]).unwrap_throw();
+    restored.validate(&poll_id).unwrap_throw();
+}
+
+#[wasm_bindgen_test]
+fn chunked_vote_can_be_reassembled_in_any_order() {
+    let (_, vote) = sample_vote();
+    let compact = vote.to_compact();
+    let mut chunks = split_into_chunks(&compact).unwrap_throw();
+    assert!(chunks.len() > 1, "{chunks:?}");
+    for (i, chunk) in chunks.iter().enumerate() {
+        let expected_prefix = format!("{CHUNK_PREFIX}{}.{}.", i + 1, chunks.len());
+        assert!(chunk.starts_with(&expected_prefix), "{chunk}");
+        QrImage::new(chunk).unwrap_throw();
+    }
+
+    chunks.shuffle(&mut OsRng);
+    let pasted = chunks.join("\n");
+    assert_eq!(reassemble_chunks(&pasted).unwrap_throw(), compact);
+    let restored: Vote = decode_pasted(&pasted).unwrap_throw();
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap_throw(),
+        serde_json::to_value(&vote).unwrap_throw()
+    );
+
+    // Duplicate chunks should be fine.
+    let pasted = format!("{pasted} {}", chunks[0]);
+    assert_eq!(reassemble_chunks(&pasted).unwrap_throw(), compact);
+}
+
+#[wasm_bindgen_test]
+fn errors_reassembling_chunks() {
+    let (_, vote) = sample_vote();
+    let chunks = split_into_chunks(&vote.to_compact()).unwrap_throw();
+    let count = chunks.len();
+
+    let err = reassemble_chunks(&chunks[1..].join(" ")).unwrap_err();
+    assert_matches!(
+        err,
+        ChunkError::Missing { indices, count: c } if indices == [1] && c == count
+    );
+
+    let (_, other_vote) = sample_vote();
+    let other_chunks = split_into_chunks(&other_vote.to_compact()).unwrap_throw();
+    let mixed = format!("{} {}", chunks[0], other_chunks[1]);
+    let err = reassemble_chunks(&mixed).unwrap_err();
+    assert_matches!(err, ChunkError::Mixed);
+
+    let mut mangled_chunk = chunks[0].clone();
+    let last_char = mangled_chunk.pop().unwrap_throw();
+    mangled_chunk.push(if last_char == 'A' { 'B' } else { 'A' });
+    let mangled = format!("{mangled_chunk} {}", chunks[1..].join(" "));
+    let err = reassemble_chunks(&mangled).unwrap_err();
+    assert_matches!(err, ChunkError::Checksum);
+
+    let err = reassemble_chunks("epq1.3.2.00000000.test").unwrap_err();
+    assert_matches!(err, ChunkError::Malformed(_));
+}
+
+#[wasm_bindgen_test]
+fn payload_exceeding_max_chunk_count_is_not_split() {
+    let max_payload = "A".repeat(MAX_CHUNK_LEN * MAX_CHUNK_COUNT);
+    let chunks = split_into_chunks(&max_payload).unwrap_throw();
+    assert_eq!(chunks.len(), MAX_CHUNK_COUNT);
+    assert_eq!(
+        reassemble_chunks(&chunks.join(" ")).unwrap_throw(),
+        max_payload
+    );
+
+    let payload = "A".repeat(MAX_CHUNK_LEN * MAX_CHUNK_COUNT + 1);
+    let err = split_into_chunks(&payload).unwrap_err();
+    assert_matches!(err, ChunkError::TooLarge { count } if count == MAX_CHUNK_COUNT + 1);
+}
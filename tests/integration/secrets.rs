@@ -0,0 +1,691 @@
+//! Tests for `SecretManager` and secret backups.
+
+use assert_matches::assert_matches;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use js_sys::{Error, Promise, Uint8Array};
+use rand::{rngs::OsRng, seq::SliceRandom, RngCore};
+use serde_json::json;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use wasm_bindgen_test::*;
+use yew::Callback;
+
+use std::{cell::Cell, rc::Rc};
+
+use elasticpoll_wasm::{
+    js::PasswordBasedCrypto,
+    poll::{
+        decode_mnemonic, encode_mnemonic, ExportedPollKey, Keypair, LockPolicy, MnemonicError,
+        ParticipantApplication, Participation, PollId, PollManager, PollState, SecretManager,
+        SecretManagerStatus, SecretShare, ShareError, DEFAULT_PROFILE, MNEMONIC_LEN,
+        POLL_KEY_PASSPHRASE_LEN, SHARE_PREFIX,
+    },
+};
+
+use crate::sample_poll;
+
+const PASSWORD: &str = "correct horse battery staple";
+const SECRET_STORAGE_KEY: &str = "elastic_poll::secret";
+
+/// Mock crypto producing boxes in the form `{ "password": _, "secret": _ }`.
+#[derive(Debug)]
+struct MockCrypto;
+
+impl PasswordBasedCrypto for MockCrypto {
+    fn seal(&self, password: &str, secret_bytes: &[u8], _cache_key: &str) -> Promise {
+        let encoded = Base64UrlUnpadded::encode_string(secret_bytes);
+        let box_json = json!({ "password": password, "secret": encoded }).to_string();
+        Promise::resolve(&box_json.into())
+    }
+
+    fn cached(&self, _cache_key: &str) -> Promise {
+        Promise::resolve(&JsValue::null())
+    }
+
+    fn open(&self, password: &str, encrypted: &str, _cache_key: &str) -> Promise {
+        let encrypted: serde_json::Value = serde_json::from_str(encrypted).unwrap_throw();
+        if encrypted["password"] == password {
+            let secret = encrypted["secret"].as_str().unwrap_throw();
+            let decoded = Base64UrlUnpadded::decode_vec(secret).unwrap_throw();
+            Promise::resolve(&Uint8Array::from(decoded.as_slice()).into())
+        } else {
+            Promise::reject(&Error::new("invalid password"))
+        }
+    }
+
+    fn forget(&self, _cache_key: &str) {
+        // do nothing
+    }
+}
+
+fn clear_storage() {
+    let local_storage = web_sys::window()
+        .unwrap_throw()
+        .local_storage()
+        .unwrap_throw()
+        .unwrap_throw();
+    local_storage.clear().unwrap_throw();
+}
+
+fn get_storage_item(key: &str) -> Option<String> {
+    let local_storage = web_sys::window()
+        .unwrap_throw()
+        .local_storage()
+        .unwrap_throw()
+        .unwrap_throw();
+    local_storage.get_item(key).unwrap_throw()
+}
+
+#[wasm_bindgen_test]
+async fn backing_up_and_restoring_secret() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    assert!(secrets.backup().is_none());
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let backup = secrets.backup().unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    // Emulate another device.
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let err = secrets.restore("wrong", &backup).await.unwrap_err();
+    assert_eq!(String::from(err.message()), "invalid password");
+    assert_eq!(secrets.status(), None);
+    let err = secrets.restore(PASSWORD, "garbage").await.unwrap_err();
+    assert!(String::from(err.message()).contains("not a password-encrypted box"));
+    assert_eq!(secrets.status(), None);
+
+    secrets.restore(PASSWORD, &backup).await.unwrap_throw();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Unlocked));
+    assert_eq!(secrets.backup().unwrap_throw(), backup);
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn detecting_polls_at_risk() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let poll_manager = PollManager::default();
+    assert!(secrets.polls_at_risk(&poll_manager.polls()).is_empty());
+
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let spec = sample_poll(0);
+    let our_poll_id = PollId::for_spec(&spec);
+    let mut our_poll = PollState::new(spec);
+    let our_keys = secrets.keys_for_poll(&our_poll_id).unwrap_throw();
+    our_poll.insert_participant(ParticipantApplication::new(&our_keys, &our_poll_id));
+    poll_manager.update_poll(&our_poll_id, &our_poll);
+
+    let spec = sample_poll(1);
+    let other_poll_id = PollId::for_spec(&spec);
+    let mut other_poll = PollState::new(spec);
+    let other_keys = Keypair::generate(&mut OsRng);
+    other_poll.insert_participant(ParticipantApplication::new(&other_keys, &other_poll_id));
+    poll_manager.update_poll(&other_poll_id, &other_poll);
+
+    let polls = poll_manager.polls();
+    assert_eq!(secrets.polls_at_risk(&polls), [our_poll_id]);
+
+    // Participation cannot be checked if the secret is locked.
+    let locked_secrets = SecretManager::new(Rc::new(MockCrypto));
+    let mut polls_at_risk = locked_secrets.polls_at_risk(&polls);
+    polls_at_risk.sort_unstable_by_key(ToString::to_string);
+    let mut expected_ids = vec![our_poll_id, other_poll_id];
+    expected_ids.sort_unstable_by_key(ToString::to_string);
+    assert_eq!(polls_at_risk, expected_ids);
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn mnemonic_test_vectors() {
+    // Test vectors from the BIP-39 spec (for 256-bit entropy).
+    let vectors = [
+        (
+            [0_u8; 32],
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon art",
+        ),
+        (
+            [0x7f; 32],
+            "legal winner thank year wave sausage worth useful legal winner thank year wave \
+             sausage worth useful legal winner thank year wave sausage worth title",
+        ),
+        (
+            [0xff; 32],
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo \
+             zoo zoo zoo vote",
+        ),
+    ];
+
+    for (seed, expected_phrase) in vectors {
+        let phrase = encode_mnemonic(&seed);
+        assert_eq!(*phrase, expected_phrase);
+        assert_eq!(phrase.split(' ').count(), MNEMONIC_LEN);
+        assert_eq!(*decode_mnemonic(&phrase).unwrap_throw(), seed);
+        // Case and whitespace should not matter.
+        let mangled_phrase = phrase.to_uppercase().replace(' ', "\n  ");
+        assert_eq!(*decode_mnemonic(&mangled_phrase).unwrap_throw(), seed);
+    }
+}
+
+#[wasm_bindgen_test]
+fn mnemonic_errors() {
+    let phrase = encode_mnemonic(&[0x7f; 32]);
+    let err = decode_mnemonic("legal winner thank").unwrap_err();
+    assert_matches!(err, MnemonicError::WordCount(3));
+
+    let bogus_phrase = phrase.replacen("legal", "legit", 1);
+    let err = decode_mnemonic(&bogus_phrase).unwrap_err();
+    assert_matches!(err, MnemonicError::UnknownWord(word) if word == "legit");
+
+    let bogus_phrase = phrase.replace("title", "zoo");
+    let err = decode_mnemonic(&bogus_phrase).unwrap_err();
+    assert_matches!(err, MnemonicError::Checksum);
+
+    let mut words: Vec<_> = phrase.split(' ').collect();
+    words.swap(0, 1);
+    let err = decode_mnemonic(&words.join(" ")).unwrap_err();
+    assert_matches!(err, MnemonicError::Checksum);
+}
+
+#[wasm_bindgen_test]
+async fn restoring_secret_from_mnemonic() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    assert!(secrets.mnemonic().is_none());
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let phrase = secrets.mnemonic().unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    // Emulate another device.
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let bogus_phrase = phrase.replacen(' ', "", 1);
+    let err = secrets
+        .restore_from_mnemonic(&bogus_phrase, "new password")
+        .await
+        .unwrap_err();
+    assert!(
+        String::from(err.message()).starts_with("Invalid recovery phrase"),
+        "{err:?}"
+    );
+    assert_eq!(secrets.status(), None);
+
+    secrets
+        .restore_from_mnemonic(&phrase, "new password")
+        .await
+        .unwrap_throw();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Unlocked));
+    assert_eq!(secrets.mnemonic().unwrap_throw(), phrase);
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+
+    // The restored secret should be sealed with the new password.
+    let backup = secrets.backup().unwrap_throw();
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.restore(PASSWORD, &backup).await.unwrap_err();
+    secrets
+        .restore("new password", &backup)
+        .await
+        .unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn changing_password() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let old_backup = secrets.backup().unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    let err = secrets
+        .change_password("wrong", "new password")
+        .await
+        .unwrap_err();
+    assert_eq!(String::from(err.message()), "invalid password");
+    assert_eq!(secrets.backup().unwrap_throw(), old_backup);
+
+    secrets
+        .change_password(PASSWORD, "new password")
+        .await
+        .unwrap_throw();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Unlocked));
+    assert_ne!(secrets.backup().unwrap_throw(), old_backup);
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+
+    // Check that the secret can be unlocked with the new password only.
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.unlock(PASSWORD).await.unwrap_err();
+    secrets.unlock("new password").await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn splitting_and_combining_shares() {
+    let mut seed = [0_u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    for (threshold, count) in [(2, 2), (2, 3), (3, 5), (5, 16)] {
+        let mut shares = SecretShare::split(&seed, threshold, count, &mut OsRng);
+        assert_eq!(shares.len(), usize::from(count));
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(usize::from(share.index()), i + 1);
+            assert_eq!(share.threshold(), threshold);
+            let share_string = share.to_string();
+            assert!(share_string.starts_with(SHARE_PREFIX), "{share_string}");
+            assert_eq!(share_string.parse::<SecretShare>().unwrap_throw(), *share);
+        }
+
+        for _ in 0..5 {
+            shares.shuffle(&mut OsRng);
+            let subset = &shares[..usize::from(threshold)];
+            assert_eq!(*SecretShare::combine(subset).unwrap_throw(), seed);
+
+            let input: Vec<_> = subset.iter().map(ToString::to_string).collect();
+            let parsed = SecretShare::parse_all(&input.join("\n")).unwrap_throw();
+            assert_eq!(*SecretShare::combine(&parsed).unwrap_throw(), seed);
+
+            let err = SecretShare::combine(&subset[1..]).unwrap_err();
+            assert_matches!(
+                err,
+                ShareError::NotEnough { threshold: t, count: c }
+                    if t == threshold && c == usize::from(threshold) - 1
+            );
+            // Duplicate shares should be fine.
+            let mut subset_with_duplicate = subset.to_vec();
+            subset_with_duplicate.push(subset[0].clone());
+            assert_eq!(
+                *SecretShare::combine(&subset_with_duplicate).unwrap_throw(),
+                seed
+            );
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn share_errors() {
+    let seed = [42; 32];
+    let shares = SecretShare::split(&seed, 2, 3, &mut OsRng);
+    let other_shares = SecretShare::split(&seed, 2, 3, &mut OsRng);
+    let err = SecretShare::combine(&[shares[0].clone(), other_shares[1].clone()]).unwrap_err();
+    assert_matches!(err, ShareError::Mixed);
+
+    let share_string = shares[0].to_string();
+    let err = share_string[1..].parse::<SecretShare>().unwrap_err();
+    assert_matches!(err, ShareError::Prefix);
+    let err = share_string[..share_string.len() - 4]
+        .parse::<SecretShare>()
+        .unwrap_err();
+    assert_matches!(err, ShareError::Malformed);
+
+    // Change a char in the middle of the share so that base64 stays canonical.
+    let mid = share_string.len() / 2;
+    let replacement = if &share_string[mid..=mid] == "A" {
+        "B"
+    } else {
+        "A"
+    };
+    let mangled = format!(
+        "{}{replacement}{}",
+        &share_string[..mid],
+        &share_string[(mid + 1)..]
+    );
+    let err = mangled.parse::<SecretShare>().unwrap_err();
+    assert_matches!(err, ShareError::Checksum);
+}
+
+#[wasm_bindgen_test]
+async fn restoring_secret_from_shares() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let shares = secrets.split_secret(2, 3).unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    // Emulate another device.
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    assert!(secrets.split_secret(2, 3).is_none());
+    let err = secrets
+        .restore_from_shares(&shares[2].to_string(), "new password")
+        .await
+        .unwrap_err();
+    assert!(
+        String::from(err.message()).starts_with("Invalid shares"),
+        "{err:?}"
+    );
+    assert_eq!(secrets.status(), None);
+
+    let input = format!("{}\n{}", shares[2], shares[0]);
+    secrets
+        .restore_from_shares(&input, "new password")
+        .await
+        .unwrap_throw();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Unlocked));
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn checking_profile_names() {
+    assert!(SecretManager::check_profile_name("work").is_ok());
+    assert!(SecretManager::check_profile_name("Work_2-b").is_ok());
+    assert!(SecretManager::check_profile_name("").is_err());
+    assert!(SecretManager::check_profile_name("with space").is_err());
+    assert!(SecretManager::check_profile_name("a::b").is_err());
+    assert!(SecretManager::check_profile_name(&"x".repeat(33)).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn switching_profiles() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    assert_eq!(secrets.active_profile(), DEFAULT_PROFILE);
+    assert_eq!(secrets.profiles(), [DEFAULT_PROFILE]);
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let default_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    secrets.switch_profile("work");
+    assert_eq!(secrets.active_profile(), "work");
+    assert_eq!(secrets.status(), None);
+    assert!(secrets.public_key_for_poll(&poll_id).is_none());
+    assert_eq!(secrets.profiles(), [DEFAULT_PROFILE, "work"]);
+
+    secrets
+        .encrypt_new_secret("other password")
+        .await
+        .unwrap_throw();
+    let work_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+    assert_ne!(work_key, default_key);
+    assert!(get_storage_item("elastic_poll::secret::work").is_some());
+    assert!(get_storage_item(SECRET_STORAGE_KEY).is_some());
+
+    // The active profile should be persisted.
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    assert_eq!(secrets.active_profile(), "work");
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Locked));
+    secrets.unlock("other password").await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        work_key
+    );
+
+    secrets.switch_profile(DEFAULT_PROFILE);
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Locked));
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        default_key
+    );
+    assert_eq!(secrets.profiles(), [DEFAULT_PROFILE, "work"]);
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn switching_profile_while_changing_password() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let default_backup = secrets.backup().unwrap_throw();
+    secrets.switch_profile("work");
+    secrets
+        .encrypt_new_secret("other password")
+        .await
+        .unwrap_throw();
+    let work_backup = secrets.backup().unwrap_throw();
+    secrets.switch_profile(DEFAULT_PROFILE);
+
+    let task = secrets.change_password(PASSWORD, "new password");
+    secrets.switch_profile("work");
+    let err = task.await.unwrap_err();
+    assert!(String::from(err.message()).contains("switched"), "{err:?}");
+    assert_eq!(secrets.backup().unwrap_throw(), work_backup);
+    assert_eq!(
+        get_storage_item(SECRET_STORAGE_KEY).unwrap_throw(),
+        default_backup
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn polls_at_risk_with_profiles() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let poll_manager = PollManager::default();
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let mut poll = PollState::new(spec);
+    poll.set_participation(Some(Participation {
+        profile: "work".to_owned(),
+        public_key: None,
+    }));
+    poll_manager.update_poll(&poll_id, &poll);
+
+    // The poll is joined by another profile, so it's not at risk.
+    let polls = poll_manager.polls();
+    assert!(secrets.polls_at_risk(&polls).is_empty());
+    secrets.switch_profile("work");
+    assert_eq!(secrets.polls_at_risk(&polls), [poll_id]);
+
+    // Participation is local info and should not be exported.
+    let (_, stored_poll) = polls.into_iter().next().unwrap_throw();
+    assert_eq!(stored_poll.participation().unwrap_throw().profile, "work");
+    let (_, imported_poll) = PollState::import(stored_poll.export()).unwrap_throw();
+    assert!(imported_poll.participation().is_none());
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn detecting_key_mismatch() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let poll_manager = PollManager::default();
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let backup = secrets.backup().unwrap_throw();
+
+    let spec = sample_poll(0);
+    let poll_id = PollId::for_spec(&spec);
+    let mut poll = PollState::new(spec);
+    let our_keys = secrets.keys_for_poll(&poll_id).unwrap_throw();
+    poll.insert_participant(ParticipantApplication::new(&our_keys, &poll_id));
+    poll_manager.update_poll(&poll_id, &poll);
+
+    // Participation is remembered for polls joined before it was tracked.
+    poll_manager.remember_participation(&secrets);
+    let poll = poll_manager.poll(&poll_id).unwrap_throw();
+    let participation = poll.participation().unwrap_throw();
+    assert_eq!(participation.profile, DEFAULT_PROFILE);
+    assert_eq!(participation.public_key.as_ref(), Some(our_keys.public()));
+    assert!(!secrets.has_key_mismatch(&poll_id, &poll));
+
+    // Recreate the secret.
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    assert!(secrets.has_key_mismatch(&poll_id, &poll));
+    let polls = poll_manager.polls();
+    assert_eq!(secrets.polls_with_key_mismatch(&polls), [poll_id]);
+    // Remembered participation must not be overwritten.
+    poll_manager.remember_participation(&secrets);
+    let poll = poll_manager.poll(&poll_id).unwrap_throw();
+    assert_eq!(
+        poll.participation().unwrap_throw().public_key.as_ref(),
+        Some(our_keys.public())
+    );
+
+    // A mismatch cannot be detected if the secret is locked.
+    secrets.lock();
+    assert!(!secrets.has_key_mismatch(&poll_id, &poll));
+
+    secrets.restore(PASSWORD, &backup).await.unwrap_throw();
+    assert!(!secrets.has_key_mismatch(&poll_id, &poll));
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn locking_secret() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let lock_count = Rc::new(Cell::new(0));
+    let lock_count_ = Rc::clone(&lock_count);
+    secrets.set_onlock(Callback::from(move |()| {
+        lock_count_.set(lock_count_.get() + 1);
+    }));
+
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+
+    secrets.lock();
+    assert_eq!(secrets.status(), Some(SecretManagerStatus::Locked));
+    assert!(secrets.public_key_for_poll(&poll_id).is_none());
+    assert!(secrets.keys_for_poll(&poll_id).is_none());
+    assert_eq!(lock_count.get(), 1);
+    // Locking a locked secret is a no-op.
+    secrets.lock();
+    assert_eq!(lock_count.get(), 1);
+
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn persisting_lock_policy() {
+    clear_storage();
+    let secrets = SecretManager::new(Rc::new(MockCrypto));
+    assert_eq!(secrets.lock_policy(), LockPolicy::default());
+
+    let policy = LockPolicy {
+        idle_timeout_minutes: None,
+        lock_on_hide: true,
+    };
+    secrets.set_lock_policy(policy);
+    assert_eq!(secrets.lock_policy(), policy);
+    let secrets = SecretManager::new(Rc::new(MockCrypto));
+    assert_eq!(secrets.lock_policy(), policy);
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+async fn exporting_and_importing_poll_keys() {
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let poll_id = PollId::for_spec(&sample_poll(0));
+    assert!(secrets.export_poll_key(&poll_id).is_none());
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let public_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+    let (exported, passphrase) = secrets.export_poll_key(&poll_id).unwrap_throw();
+    assert_eq!(exported.public_key, public_key);
+    assert_eq!(passphrase.split(' ').count(), POLL_KEY_PASSPHRASE_LEN);
+    let exported_json = serde_json::to_string(&exported).unwrap();
+
+    // Emulate another device.
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    let other_poll_id = PollId::for_spec(&sample_poll(1));
+    let derived_key = secrets.public_key_for_poll(&poll_id).unwrap_throw();
+    let other_key = secrets.public_key_for_poll(&other_poll_id).unwrap_throw();
+    assert_ne!(derived_key, public_key);
+
+    let exported: ExportedPollKey = serde_json::from_str(&exported_json).unwrap();
+    let err = secrets
+        .import_poll_key(&other_poll_id, &exported, &passphrase)
+        .unwrap_err();
+    assert!(String::from(err.message()).contains("another poll"));
+    let err = secrets
+        .import_poll_key(&poll_id, &exported, "wrong")
+        .unwrap_err();
+    assert!(String::from(err.message()).contains("failed decryption"));
+    assert!(!secrets.has_imported_key(&poll_id));
+
+    // The passphrase is normalized in the same way as mnemonic phrases.
+    let passphrase_with_spaces = format!("  {}\n", passphrase.to_uppercase());
+    secrets
+        .import_poll_key(&poll_id, &exported, &passphrase_with_spaces)
+        .unwrap_throw();
+    assert!(secrets.has_imported_key(&poll_id));
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+    assert_eq!(
+        *secrets.keys_for_poll(&poll_id).unwrap_throw().public(),
+        public_key
+    );
+    assert_eq!(
+        secrets.public_key_for_poll(&other_poll_id).unwrap_throw(),
+        other_key
+    );
+
+    // The imported key survives locking, but is unavailable while the secret is locked.
+    secrets.lock();
+    assert!(secrets.keys_for_poll(&poll_id).is_none());
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        public_key
+    );
+
+    secrets.remove_imported_keys(&poll_id);
+    assert!(!secrets.has_imported_key(&poll_id));
+    assert_eq!(
+        secrets.public_key_for_poll(&poll_id).unwrap_throw(),
+        derived_key
+    );
+    clear_storage();
+}
+
+#[cfg(feature = "testing")]
+#[wasm_bindgen_test]
+async fn secret_is_dropped_on_lock() {
+    use elasticpoll_wasm::testing::live_secrets;
+
+    clear_storage();
+    let secrets = Rc::new(SecretManager::new(Rc::new(MockCrypto)));
+    let initial_count = live_secrets();
+    secrets.encrypt_new_secret(PASSWORD).await.unwrap_throw();
+    assert_eq!(live_secrets(), initial_count + 1);
+    secrets.lock();
+    assert_eq!(live_secrets(), initial_count);
+
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    assert_eq!(live_secrets(), initial_count + 1);
+    // A failed unlock attempt must not leave secrets in memory.
+    secrets.lock();
+    secrets.unlock("wrong").await.unwrap_err();
+    assert_eq!(live_secrets(), initial_count);
+
+    secrets.unlock(PASSWORD).await.unwrap_throw();
+    drop(secrets);
+    assert_eq!(live_secrets(), initial_count);
+    clear_storage();
+}
@@ -0,0 +1,74 @@
+//! Command-line verifier for polls exported from the Elastic Poll app.
+
+// Linter settings.
+#![warn(missing_debug_implementations, bare_trait_objects, rust_2018_idioms)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+use clap::Parser;
+use elasticpoll_core::{CompactEncoding, ExportedPoll};
+
+use std::{
+    error::Error as StdError,
+    fs,
+    io::{self, Read as _},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+mod report;
+
+use crate::report::Report;
+
+/// Verifies a poll exported from the Elastic Poll app.
+///
+/// Checks participants' consent proofs, votes (eligibility, signature and choice proofs)
+/// and tallier shares, and outputs decrypted results if all tallier shares are present.
+/// Exits with code 1 if verification fails, and with code 2 if the poll cannot be read.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Path to the exported poll in JSON or compact format. Use `-` to read from stdin.
+    file: PathBuf,
+    /// Output the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Args {
+    fn read_poll(&self) -> Result<ExportedPoll, Box<dyn StdError>> {
+        let contents = if self.file.as_os_str() == "-" {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents)?;
+            contents
+        } else {
+            fs::read_to_string(&self.file)?
+        };
+        ExportedPoll::decode(&contents)
+    }
+
+    fn run(&self) -> Result<bool, Box<dyn StdError>> {
+        let poll = self
+            .read_poll()
+            .map_err(|err| format!("cannot read poll from `{}`: {err}", self.file.display()))?;
+        let report = Report::new(&poll);
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{report}");
+        }
+        Ok(report.is_valid())
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match args.run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
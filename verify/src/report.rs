@@ -0,0 +1,308 @@
+//! Verification report for an exported poll.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use elasticpoll_core::{
+    ExportedPoll, Fingerprint, PollId, PollState, PollType, PublicKey, TallierShareError, VoteError,
+};
+use serde::Serialize;
+
+use std::fmt;
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// The check has passed.
+    Ok,
+    /// The check has failed.
+    Failed { error: String },
+    /// The check was not performed because a preceding check has failed.
+    Skipped,
+}
+
+impl CheckStatus {
+    fn failed(err: &impl fmt::Display) -> Self {
+        Self::Failed {
+            error: err.to_string(),
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => formatter.write_str("ok"),
+            Self::Failed { error } => write!(formatter, "FAILED ({error})"),
+            Self::Skipped => formatter.write_str("skipped"),
+        }
+    }
+}
+
+/// Identity of a participant.
+#[derive(Debug, Serialize)]
+pub struct KeyInfo {
+    /// Public key encoded with base64url.
+    pub public_key: String,
+    /// Fingerprint of the public key as displayed in the app.
+    pub fingerprint: String,
+}
+
+impl KeyInfo {
+    fn new(key: &PublicKey) -> Self {
+        Self {
+            public_key: Base64UrlUnpadded::encode_string(key.as_bytes()),
+            fingerprint: Fingerprint::from(key).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParticipantReport {
+    #[serde(flatten)]
+    pub key: KeyInfo,
+    /// Status of the participation consent proof.
+    pub consent: CheckStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoteReport {
+    #[serde(flatten)]
+    pub key: KeyInfo,
+    /// Whether the voter is one of the poll participants.
+    pub eligibility: CheckStatus,
+    /// Status of the voter's signature.
+    pub signature: CheckStatus,
+    /// Status of the zero-knowledge proofs for the encrypted choice.
+    pub choice: CheckStatus,
+}
+
+impl VoteReport {
+    fn new(key: &PublicKey, result: Result<(), VoteError>) -> Self {
+        let (eligibility, signature, choice) = match result {
+            Ok(()) => (CheckStatus::Ok, CheckStatus::Ok, CheckStatus::Ok),
+            Err(err @ VoteError::IneligibleVoter) => (
+                CheckStatus::failed(&err),
+                CheckStatus::Skipped,
+                CheckStatus::Skipped,
+            ),
+            Err(err @ VoteError::Signature(_)) => (
+                CheckStatus::Ok,
+                CheckStatus::failed(&err),
+                CheckStatus::Skipped,
+            ),
+            Err(err) => (CheckStatus::Ok, CheckStatus::Ok, CheckStatus::failed(&err)),
+        };
+        Self {
+            key: KeyInfo::new(key),
+            eligibility,
+            signature,
+            choice,
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        self.eligibility.is_ok() && self.signature.is_ok() && self.choice.is_ok()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TallierShareReport {
+    #[serde(flatten)]
+    pub key: KeyInfo,
+    /// Status of the decryption share and its proofs.
+    pub share: CheckStatus,
+}
+
+impl TallierShareReport {
+    fn new(key: &PublicKey, result: Result<(), TallierShareError>) -> Self {
+        Self {
+            key: KeyInfo::new(key),
+            share: result.map_or_else(|err| CheckStatus::failed(&err), |()| CheckStatus::Ok),
+        }
+    }
+}
+
+/// Decrypted number of votes for a poll option.
+#[derive(Debug, Serialize)]
+pub struct OptionResult {
+    pub option: String,
+    pub votes: u64,
+}
+
+/// Verification report for an [`ExportedPoll`].
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub poll_id: String,
+    pub poll_fingerprint: String,
+    pub title: String,
+    pub poll_type: PollType,
+    /// Stage of the poll as follows from the exported data; one of the names returned by
+    /// [`PollStage::name()`](elasticpoll_core::PollStage::name()).
+    pub stage: &'static str,
+    pub participants: Vec<ParticipantReport>,
+    pub votes: Vec<VoteReport>,
+    pub tallier_shares: Vec<TallierShareReport>,
+    /// Decrypted results; only present if all tallier shares are collected.
+    pub results: Option<Vec<OptionResult>>,
+    /// Error returned when importing the poll, or `None` if the poll is valid.
+    pub error: Option<String>,
+}
+
+impl Report {
+    /// Verifies the poll and creates a report. Unlike [`PollState::import()`], verification
+    /// does not stop on the first error, so that the report lists the status of every
+    /// application, vote and tallier share.
+    pub fn new(exported: &ExportedPoll) -> Self {
+        let spec = exported.spec();
+        let poll_id = PollId::for_spec(spec);
+        let mut poll = PollState::new(spec.clone());
+
+        let participants = exported
+            .participant_applications()
+            .iter()
+            .map(|application| {
+                let consent = application
+                    .validate(&poll_id)
+                    .map_or_else(|err| CheckStatus::failed(&err), |()| CheckStatus::Ok);
+                // Insert all participants so that votes are checked against the shared key
+                // from the exported data.
+                poll.insert_participant(application.clone());
+                ParticipantReport {
+                    key: KeyInfo::new(&application.public_key),
+                    consent,
+                }
+            })
+            .collect();
+
+        let has_votes = !exported.votes().is_empty();
+        if has_votes {
+            poll.finalize_participants();
+        }
+        let votes = exported
+            .votes()
+            .iter()
+            .map(|vote| {
+                let result = poll.insert_vote(&poll_id, vote.clone());
+                VoteReport::new(vote.public_key(), result)
+            })
+            .collect();
+
+        if has_votes && !exported.tallier_shares().is_empty() {
+            poll.finalize_votes();
+        }
+        let tallier_shares = exported
+            .tallier_shares()
+            .iter()
+            .map(|share| {
+                if has_votes {
+                    let result = poll.insert_tallier_share(&poll_id, share.clone());
+                    TallierShareReport::new(share.public_key(), result)
+                } else {
+                    TallierShareReport {
+                        key: KeyInfo::new(share.public_key()),
+                        share: CheckStatus::Skipped,
+                    }
+                }
+            })
+            .collect();
+
+        let results = poll.results().map(|results| {
+            let options = spec.options.iter().zip(results);
+            options
+                .map(|(option, &votes)| OptionResult {
+                    option: option.clone(),
+                    votes,
+                })
+                .collect()
+        });
+        // `PollState::import()` is the source of truth on whether the poll is valid.
+        let error = PollState::import(exported.clone())
+            .err()
+            .map(|err| err.to_string());
+
+        Self {
+            poll_id: poll_id.to_string(),
+            poll_fingerprint: Fingerprint::from(&poll_id).to_string(),
+            title: spec.title.clone(),
+            poll_type: spec.poll_type,
+            stage: poll.stage().name(),
+            participants,
+            votes,
+            tallier_shares,
+            results,
+            error,
+        }
+    }
+
+    /// Checks whether the poll has passed verification.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+            && self.participants.iter().all(|p| p.consent.is_ok())
+            && self.votes.iter().all(VoteReport::is_ok)
+            && self.tallier_shares.iter().all(|share| share.share.is_ok())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "Poll: {}", self.title)?;
+        writeln!(formatter, "ID: {}", self.poll_id)?;
+        writeln!(formatter, "Fingerprint: {}", self.poll_fingerprint)?;
+        writeln!(formatter, "Type: {}", self.poll_type.as_human_string())?;
+        writeln!(formatter, "Stage: {}", self.stage)?;
+
+        writeln!(formatter, "\nParticipants ({}):", self.participants.len())?;
+        for participant in &self.participants {
+            writeln!(
+                formatter,
+                "  {} [{}]: consent {}",
+                participant.key.public_key, participant.key.fingerprint, participant.consent
+            )?;
+        }
+
+        writeln!(formatter, "\nVotes ({}):", self.votes.len())?;
+        for vote in &self.votes {
+            writeln!(
+                formatter,
+                "  {} [{}]: eligibility {}, signature {}, choice {}",
+                vote.key.public_key,
+                vote.key.fingerprint,
+                vote.eligibility,
+                vote.signature,
+                vote.choice
+            )?;
+        }
+
+        writeln!(
+            formatter,
+            "\nTallier shares ({}):",
+            self.tallier_shares.len()
+        )?;
+        for share in &self.tallier_shares {
+            writeln!(
+                formatter,
+                "  {} [{}]: share {}",
+                share.key.public_key, share.key.fingerprint, share.share
+            )?;
+        }
+
+        if let Some(results) = &self.results {
+            writeln!(formatter, "\nResults:")?;
+            for result in results {
+                writeln!(formatter, "  {}: {} votes", result.option, result.votes)?;
+            }
+        }
+
+        if let Some(err) = &self.error {
+            write!(formatter, "\nVerification FAILED: {err}")
+        } else if self.is_valid() {
+            write!(formatter, "\nVerification passed")
+        } else {
+            write!(formatter, "\nVerification FAILED")
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! End-to-end tests for the verifier CLI.
+
+use rand::rngs::OsRng;
+use serde_json::Value;
+
+use std::{
+    env, fs,
+    process::{Command, Output},
+};
+
+use elasticpoll_core::{
+    CompactEncoding, Keypair, ParticipantApplication, PollId, PollSpec, PollState, PollType,
+    TallierShare, Vote, VoteChoice,
+};
+
+fn finished_poll() -> PollState {
+    let spec = PollSpec {
+        title: "Sample poll".to_owned(),
+        description: "".to_owned(),
+        poll_type: PollType::SingleChoice,
+        nonce: 0,
+        options: vec!["Option #1".to_owned(), "Option #2".to_owned()],
+    };
+    let poll_id = PollId::for_spec(&spec);
+    let mut poll = PollState::new(spec);
+    let keys: Vec<_> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+    for our_keys in &keys {
+        poll.insert_participant(ParticipantApplication::new(our_keys, &poll_id));
+    }
+    poll.finalize_participants();
+    for (i, our_keys) in keys.iter().enumerate() {
+        let choice = VoteChoice::SingleChoice(usize::from(i == 0));
+        let vote = Vote::new(our_keys, &poll_id, &poll, &choice);
+        poll.insert_vote(&poll_id, vote).unwrap();
+    }
+    poll.finalize_votes();
+    for our_keys in &keys {
+        let share = TallierShare::new(our_keys, &poll_id, &poll);
+        poll.insert_tallier_share(&poll_id, share).unwrap();
+    }
+    poll
+}
+
+fn run_verifier(name: &str, contents: &str, args: &[&str]) -> Output {
+    let path = env::temp_dir().join(format!("elasticpoll-verify-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_elasticpoll-verify"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap();
+    fs::remove_file(&path).ok();
+    output
+}
+
+fn json_report(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn verifying_valid_poll() {
+    let poll = finished_poll();
+    let exported = serde_json::to_string(&poll.export()).unwrap();
+    let output = run_verifier("valid.json", &exported, &["--json"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let report = json_report(&output);
+    let poll_id = PollId::for_spec(poll.spec()).to_string();
+    assert_eq!(report["poll_id"], poll_id);
+    assert_eq!(report["stage"], "finished");
+    assert_eq!(report["error"], Value::Null);
+    assert_eq!(report["participants"].as_array().unwrap().len(), 3);
+    for participant in report["participants"].as_array().unwrap() {
+        assert_eq!(participant["consent"]["status"], "ok");
+    }
+    for vote in report["votes"].as_array().unwrap() {
+        for check in ["eligibility", "signature", "choice"] {
+            assert_eq!(vote[check]["status"], "ok", "{vote:#}");
+        }
+    }
+    for share in report["tallier_shares"].as_array().unwrap() {
+        assert_eq!(share["share"]["status"], "ok");
+    }
+    assert_eq!(report["results"][0]["votes"], 2);
+    assert_eq!(report["results"][1]["votes"], 1);
+
+    // Check the text output and the compact format.
+    let compact = poll.export().to_compact();
+    let output = run_verifier("valid.txt", &compact, &[]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains(&poll_id), "{text}");
+    assert!(text.contains("Option #1: 2 votes"), "{text}");
+    assert!(text.contains("Verification passed"), "{text}");
+}
+
+#[test]
+fn verifying_poll_with_invalid_vote() {
+    let poll = finished_poll();
+    let mut exported = serde_json::to_value(poll.export()).unwrap();
+    // Attribute the first vote to another participant.
+    let other_key = exported["participant_applications"][1]["public_key"].clone();
+    exported["votes"][0]["public_key"] = other_key;
+
+    let output = run_verifier("invalid-vote.json", &exported.to_string(), &["--json"]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let report = json_report(&output);
+    let error = report["error"].as_str().unwrap();
+    assert!(error.contains("cannot validate vote"), "{error}");
+
+    let vote = &report["votes"][0];
+    assert_eq!(vote["eligibility"]["status"], "ok");
+    assert_eq!(vote["signature"]["status"], "failed");
+    assert_eq!(vote["choice"]["status"], "skipped");
+    assert_eq!(report["votes"][1]["signature"]["status"], "ok");
+    // Shares are computed for the original votes, so they cannot be verified.
+    assert_eq!(report["tallier_shares"][0]["share"]["status"], "failed");
+    assert_eq!(report["results"], Value::Null);
+}
+
+#[test]
+fn verifying_poll_with_modified_spec() {
+    let poll = finished_poll();
+    let mut exported = serde_json::to_value(poll.export()).unwrap();
+    exported["spec"]["nonce"] = 1.into();
+
+    let output = run_verifier("modified-spec.json", &exported.to_string(), &[]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("consent FAILED"), "{text}");
+    assert!(text.contains("Verification FAILED"), "{text}");
+}
+
+#[test]
+fn reading_malformed_poll() {
+    let output = run_verifier("malformed.json", "{ \"spec\": 42 }", &[]);
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cannot read poll"), "{stderr}");
+}
@@ -6,6 +6,8 @@ const SALT_LEN = 32;
 const IV_LEN = 12;
 const MAC_LEN = 16;
 const KDF_ITERATIONS = 100000;
+/** Upper bound on iterations in opened boxes; must match the bound in the Rust core. */
+const MAX_KDF_ITERATIONS = 10000000;
 
 const ENCODER = new TextEncoder();
 
@@ -103,6 +105,11 @@ export async function openBox(password, box) {
   if (cipher !== 'aes-128-gcm') {
     throw new Error(`Unknown cipher ${cipher}; aes-128-gcm was expected`);
   }
+  if (!Number.isInteger(iterations) || iterations <= 0 || iterations > MAX_KDF_ITERATIONS) {
+    throw new Error(
+      `Invalid KDF iterations ${iterations}; at most ${MAX_KDF_ITERATIONS} are allowed`,
+    );
+  }
 
   ciphertext = fromHex(ciphertext);
   mac = fromHex(mac);
@@ -6,6 +6,19 @@
 
 import { openBox, sealBox } from './crypto';
 
+/** Box sealed by `PasswordBox::seal()` in the Rust core (see `core/tests/crypto.rs`). */
+const RUST_BOX = {
+  kdf: 'pbkdf2-sha256',
+  cipher: 'aes-128-gcm',
+  ciphertext: 'c7dccd655b815020ce007ba75a9ad8640acb73ec63b120e9b610a6610a0c6dd5',
+  mac: '71e4c20570459518a82bc68ae366d541',
+  kdfparams: {
+    salt: '202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f',
+    iterations: 100000,
+  },
+  cipherparams: { iv: '404142434445464748494a4b' },
+};
+
 test('box roundtrip', async () => {
   const password = 'correct horse battery staple';
   const plaintext = new Uint8Array(32);
@@ -29,3 +42,19 @@ test('opening box with incorrect password', async () => {
     expect(e.message).toMatch('failed decryption');
   }
 });
+
+test('opening box with excessive KDF iterations', async () => {
+  const password = 'correct horse battery staple';
+  const box = await sealBox(password, new Uint8Array(32));
+  box.kdfparams.iterations = 4000000000;
+
+  await expect(openBox(password, box)).rejects.toThrow('Invalid KDF iterations');
+});
+
+test('opening box sealed by Rust core', async () => {
+  const expected = new Uint8Array(32).map((_, i) => i);
+  const opened = await openBox('correct horse battery staple', RUST_BOX);
+  expect(opened).toEqual(expected);
+
+  await expect(openBox('bogus', RUST_BOX)).rejects.toThrow();
+});
@@ -14,8 +14,13 @@ import copyTextToClipboard from 'copy-text-to-clipboard';
 import { openBox, sealBox } from './crypto';
 
 const SERVICE_WORKER_URL = '/service-worker.js';
-const CACHE_KEY = 'secret_seed';
 const PING_INTERVAL = 10000;
+// Implementation of password-based encryption: 'host' (`./crypto.js`), 'rust' (built into WASM)
+// or 'rust-argon2id'. The latter seals new boxes with Argon2id, which cannot be opened by the host
+// implementation; thus, switching back to 'host' would make stored secrets and backups unusable.
+const PASSWORD_CRYPTO = 'host';
+// Origins of parent pages allowed to communicate with the app embedded via the `/embed/:id` route.
+const EMBED_ORIGINS = [];
 
 function onValueExported({ data }, target) {
   copyTextToClipboard(data);
@@ -50,17 +55,22 @@ async function postMessageAsync(controller, message) {
 
 let getCachedBox = null;
 let cacheBox = null;
+let forgetCachedBox = null;
 
 if ('serviceWorker' in navigator) {
   const { serviceWorker } = navigator;
 
-  getCachedBox = async () => {
+  getCachedBox = async (key) => {
     await serviceWorker.ready;
-    return postMessageAsync(serviceWorker.controller, { type: 'GET_CACHE', key: CACHE_KEY });
+    return postMessageAsync(serviceWorker.controller, { type: 'GET_CACHE', key });
   };
-  cacheBox = async (value) => {
+  cacheBox = async (key, value) => {
     await serviceWorker.ready;
-    serviceWorker.controller.postMessage({ type: 'SET_CACHE', key: CACHE_KEY, value });
+    serviceWorker.controller.postMessage({ type: 'SET_CACHE', key, value });
+  };
+  forgetCachedBox = async (key) => {
+    await serviceWorker.ready;
+    serviceWorker.controller.postMessage({ type: 'DELETE_CACHE', key });
   };
 
   window.addEventListener('load', () => {
@@ -80,23 +90,38 @@ if ('serviceWorker' in navigator) {
 } else {
   // Use a per-page cache (less efficient than with a service worker, since different
   // tabs / windows will have separate caches).
-  let cachedValue;
+  const cachedValues = new Map();
 
-  getCachedBox = async () => cachedValue;
-  cacheBox = async (value) => {
-    cachedValue = value;
+  getCachedBox = async (key) => cachedValues.get(key);
+  cacheBox = async (key, value) => {
+    cachedValues.set(key, value);
+  };
+  forgetCachedBox = async (key) => {
+    cachedValues.delete(key);
   };
 }
 
 import(/* webpackChunkName: "bundle" */ '../pkg').then((wasm) => {
   wasm.runApp({
     onexport: onValueExported,
-
-    sealBox: (password, secretBytes) => sealBox(password, secretBytes).then(JSON.stringify),
+    passwordCrypto: PASSWORD_CRYPTO,
+    embedOrigins: EMBED_ORIGINS,
+
+    sealBox: async (password, secretBytes, cacheKey) => {
+      // `secretBytes` is a view into WASM memory, so it needs to be copied before caching.
+      const secret = new Uint8Array(secretBytes);
+      const box = await sealBox(password, secret);
+      // The sealed secret becomes the active one (e.g., after recovery), so it needs to be cached.
+      cacheBox(cacheKey, secret).catch(console.error);
+      return JSON.stringify(box);
+    },
     getCachedBox,
-    openBox: async (password, boxJson) => {
+    forgetCachedBox: (cacheKey) => {
+      forgetCachedBox(cacheKey).catch(console.error);
+    },
+    openBox: async (password, boxJson, cacheKey) => {
       const secret = await openBox(password, JSON.parse(boxJson));
-      cacheBox(secret).catch(console.error);
+      cacheBox(cacheKey, secret).catch(console.error);
       return secret;
     },
 
@@ -19,6 +19,11 @@ const icons = [
   'clipboard',
   'backspace',
   'check-lg',
+  'file-zip',
+  'qr-code',
+  'link-45deg',
+  'download',
+  'upload',
 ];
 
 const iconsDir = path.resolve(__dirname, '../icons');
@@ -19,6 +19,11 @@ self.addEventListener('message', (event) => {
       cache.set(key, value);
       break;
     }
+    case 'DELETE_CACHE': {
+      const { key } = event.data;
+      cache.delete(key);
+      break;
+    }
     case 'GET_CACHE': {
       const { key, responsePort } = event.data;
       const cachedValue = cache.get(key);